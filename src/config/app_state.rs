@@ -0,0 +1,61 @@
+use std::{collections::HashSet, sync::{Arc, Mutex}};
+
+use crate::{
+    config::settings::Settings,
+    interface::store::{Store, StoreResult},
+    service::{auth::Authenticator, id_allocator::IdAllocator},
+    util::error::AppError,
+};
+
+///
+/// State shared by every worker through `web::Data`.
+///
+/// - `store`: The storage backend selected at startup.
+/// - `settings`: Settings validated at startup, e.g: the name of the sheets.
+/// - `ids`: Allocator of the ids of new entities, shared so the ids are never repeated.
+/// - `auth`: It identifies the callers, with the keys loaded once.
+pub struct AppState {
+    pub store: Arc<dyn Store>,
+    pub settings: Settings,
+    pub ids: IdAllocator,
+    pub auth: Authenticator,
+    ready_sheets: Mutex<HashSet<String>>
+}
+
+impl AppState {
+    pub fn new(store: Arc<dyn Store>, settings: Settings) -> Self {
+        AppState {
+            store,
+            ids: IdAllocator::new(&settings),
+            auth: Authenticator::new(&settings.auth),
+            settings,
+            ready_sheets: Mutex::new(HashSet::new())
+        }
+    }
+
+    ///
+    /// Name of the sheet where every entity is stored.
+    pub fn sheet(&self) -> &str {
+        self.settings.sheets.projects.as_str()
+    }
+
+    ///
+    /// It creates the sheet when it does not exist yet, only the first time it is called for every sheet.
+    pub async fn ensure_sheet(&self, sheet_name: &str) -> StoreResult<()> {
+        let ready = self.ready_sheets
+            .lock()
+            .map_err(|err| AppError::Upstream(err.to_string()))?
+            .contains(sheet_name);
+
+        if ready { return Ok(()) }
+
+        self.store.ensure_sheet(sheet_name).await?;
+
+        self.ready_sheets
+            .lock()
+            .map_err(|err| AppError::Upstream(err.to_string()))?
+            .insert(sheet_name.to_string());
+
+        Ok(())
+    }
+}
@@ -1,20 +1,31 @@
-use google_sheets4::{hyper_util::{client::legacy::Client, rt::TokioExecutor}, yup_oauth2::{self, ServiceAccountAuthenticator}, Sheets};
+use google_sheets4::{hyper_util::{client::legacy::Client, rt::TokioExecutor}, Sheets};
 use hyper_rustls::HttpsConnectorBuilder;
 use google_sheets4::hyper_util::client::legacy::connect::HttpConnector;
+use yup_oauth2::ServiceAccountAuthenticator;
 
-///
-/// Obtains the `credentials.json` of the root project and authenticate it
-pub async fn get_credentials() -> Result<Sheets<hyper_rustls::HttpsConnector<HttpConnector>>, Box<dyn std::error::Error>> {
-    // `creadentials.json`: Provided by Google
-    let credentials = yup_oauth2::read_service_account_key("credentials.json")
-        .await
-        .expect("Cannot read credential, an error occured.");
+/// Authenticated client of the Sheets API.
+pub type SheetsHub = Sheets<hyper_rustls::HttpsConnector<HttpConnector>>;
 
-    let auth = ServiceAccountAuthenticator::builder(credentials)
-        .build()
-        .await
-        .expect("There was an error, trying to build connection with authenticator");
+///
+/// Custom location of the Sheets API, e.g: a local fake of the API used by the contract tests.
+///
+/// SheetsEndpoint attributes:
+///
+/// - `base_url`: Scheme, host and port, e.g: `http://127.0.0.1:9000`.
+/// - `token`: Sent as bearer token instead of authenticating with the service account key.
+#[derive(Debug, Clone)]
+pub struct SheetsEndpoint {
+    pub base_url: String,
+    pub token: String
+}
 
+///
+/// Obtains the service account key located in `credentials_path` (e.g: `credentials.json`) and authenticate it,
+/// unless a custom endpoint is given, then its static token is used.
+///
+/// It is meant to be called once at startup: the authenticator keeps the token
+/// and refreshes it when it expires, so the hub can be shared by every request.
+pub async fn get_credentials(credentials_path: &str, endpoint: Option<&SheetsEndpoint>) -> Result<SheetsHub, Box<dyn std::error::Error>> {
     let connector = HttpsConnectorBuilder::new()
         .with_native_roots()?
         .https_or_http()
@@ -23,7 +34,25 @@ pub async fn get_credentials() -> Result<Sheets<hyper_rustls::HttpsConnector<Htt
 
     let client = Client::builder(TokioExecutor::new()).build(connector);
 
+    if let Some(SheetsEndpoint { base_url, token }) = endpoint {
+        let base_url = base_url.trim_end_matches('/');
+
+        let mut hub = Sheets::new(client, token.clone());
+        // The paths of the methods already start with `v4/`
+        hub.root_url(format!("{}/", base_url));
+        hub.base_url(format!("{}/", base_url));
+
+        return Ok(hub)
+    }
+
+    // Service account key: Provided by Google
+    let credentials = yup_oauth2::read_service_account_key(credentials_path).await?;
+
+    let auth = ServiceAccountAuthenticator::builder(credentials)
+        .build()
+        .await?;
+
     let hub = Sheets::new(client, auth);
-    
+
     Ok(hub)
-}
\ No newline at end of file
+}
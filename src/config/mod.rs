@@ -1 +1,3 @@
-pub mod google_sheet_authenticator;
\ No newline at end of file
+pub mod google_sheet_authenticator;
+pub mod app_state;
+pub mod settings;
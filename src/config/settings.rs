@@ -0,0 +1,566 @@
+use std::{collections::{BTreeMap, HashMap}, env, fmt, fs, path::Path, str::FromStr};
+
+use jsonwebtoken::DecodingKey;
+use serde::Deserialize;
+
+use crate::util::util::{ItemStatus, Status};
+
+/// File read when `SETTINGS_PATH` is not set, it is optional.
+pub const DEFAULT_SETTINGS_PATH: &str = "little_things.toml";
+
+///
+/// Settings of the server, loaded at startup from a TOML file and then from the environment
+/// (the `.env` included), the environment takes precedence.
+///
+/// ```toml
+/// [server]
+/// host = "127.0.0.1"
+/// port = 8080
+/// cors_origin = "http://localhost:1420"
+///
+/// [storage]
+/// backend = "google_sheets"
+/// spreadsheet_id = "..."
+/// credentials_path = "credentials.json"
+///
+/// [sheets]
+/// projects = "Projects"
+///
+/// [ids]
+/// mode = "sequential"
+///
+/// [trash]
+/// retention_days = 30
+///
+/// [workflow.sprint]
+/// Pending = ["InProgress"]
+/// InProgress = ["Done"]
+/// Done = []
+///
+/// [workflow.backlog_item]
+/// ToDo = ["InProgress"]
+/// InProgress = ["Done"]
+///
+/// [auth]
+/// jwt_hs256_secret = "..."
+///
+/// [auth.api_keys]
+/// "..." = "M1"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub server: ServerSettings,
+    pub storage: StorageSettings,
+    pub sheets: SheetSettings,
+    pub ids: IdSettings,
+    pub trash: TrashSettings,
+    pub workflow: WorkflowSettings,
+    pub auth: AuthSettings
+}
+
+///
+/// ServerSettings attributes:
+///
+/// - `host` and `port`: Address where the server listens.
+/// - `cors_origin`: Origin allowed to call the API, e.g: the desktop client.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    pub host: String,
+    pub port: u16,
+    pub cors_origin: String
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        ServerSettings {
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            cors_origin: "http://localhost:1420".to_string()
+        }
+    }
+}
+
+///
+/// StorageSettings attributes:
+///
+/// - `backend`: Where the data is stored.
+/// - `spreadsheet_id`: Spreadsheet used by the `google_sheets` backend.
+/// - `sheets_base_url`: Another server implementing the Sheets v4 API, e.g: a local fake.
+/// - `sheets_token`: Bearer token sent to `sheets_base_url`, none by default.
+/// - `credentials_path`: Service account key provided by Google, unused with `sheets_base_url`.
+/// - `sqlite_path`: Database file used by the `sqlite` backend.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageSettings {
+    pub backend: StorageBackend,
+    pub spreadsheet_id: Option<String>,
+    pub sheets_base_url: Option<String>,
+    pub sheets_token: String,
+    pub credentials_path: String,
+    pub sqlite_path: String
+}
+
+impl Default for StorageSettings {
+    fn default() -> Self {
+        StorageSettings {
+            backend: StorageBackend::GoogleSheets,
+            spreadsheet_id: None,
+            sheets_base_url: None,
+            sheets_token: String::new(),
+            credentials_path: "credentials.json".to_string(),
+            sqlite_path: "little_things.db".to_string()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    GoogleSheets,
+    Sqlite,
+    Memory
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(backend: &str) -> Result<Self, Self::Err> {
+        match backend {
+            "google_sheets" => Ok(StorageBackend::GoogleSheets),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            "memory" => Ok(StorageBackend::Memory),
+            backend => Err(format!("unknown backend '{}', expected google_sheets, sqlite or memory", backend))
+        }
+    }
+}
+
+///
+/// Names of the sheets (tabs) of the spreadsheet.
+///
+/// - `projects`: Sheet where every entity is stored.
+/// - `metadata`: Sheet of the counters used to allocate the ids.
+/// - `sprint_items`: Sheet of the backlog items committed to every sprint.
+/// - `scope_changes`: Sheet of the backlog items added to, removed from and completed in every sprint, records are only appended.
+/// - `assignments`: Sheet of the members assigned to the stories and backlog items.
+/// - `permissions`: Sheet of the roles of the callers in every project.
+/// - `audit`: Sheet of the changes made to the entities, records are only appended.
+/// - `trash`: Sheet of the removed entities, until they are restored or purged.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SheetSettings {
+    pub projects: String,
+    pub metadata: String,
+    pub sprint_items: String,
+    pub scope_changes: String,
+    pub assignments: String,
+    pub permissions: String,
+    pub audit: String,
+    pub trash: String
+}
+
+impl Default for SheetSettings {
+    fn default() -> Self {
+        SheetSettings {
+            projects: "Projects".to_string(),
+            metadata: "Metadata".to_string(),
+            sprint_items: "SprintItems".to_string(),
+            scope_changes: "ScopeChanges".to_string(),
+            assignments: "Assignments".to_string(),
+            permissions: "Permissions".to_string(),
+            audit: "Audit".to_string(),
+            trash: "Trash".to_string()
+        }
+    }
+}
+
+///
+/// IdSettings attributes:
+///
+/// - `mode`: How the ids of new entities are generated.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdSettings {
+    pub mode: IdMode
+}
+
+///
+/// TrashSettings attributes:
+///
+/// - `retention_days`: Days a removed entity is kept in the trash, the older ones are purged.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrashSettings {
+    pub retention_days: u32
+}
+
+impl Default for TrashSettings {
+    fn default() -> Self {
+        TrashSettings { retention_days: 30 }
+    }
+}
+
+///
+/// - `Sequential` (default): The prefix and a counter of the entity, e.g: `E3`.
+/// - `Ulid`: The prefix and a ULID, e.g: `E01ARZ3NDEKTSV4RRFFQ69G5FAV`, no counter is kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdMode {
+    #[default]
+    Sequential,
+    Ulid
+}
+
+impl FromStr for IdMode {
+    type Err = String;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "sequential" => Ok(IdMode::Sequential),
+            "ulid" => Ok(IdMode::Ulid),
+            mode => Err(format!("unknown mode '{}', expected sequential or ulid", mode))
+        }
+    }
+}
+
+///
+/// Statuses that can follow every status, e.g: `Done = ["InProgress"]`, a status without entry can not be left.
+pub type Transitions = BTreeMap<String, Vec<String>>;
+
+///
+/// (entity, whether a status is valid, statuses expected) of a workflow table being validated.
+type StatusCheck<'a> = (&'a str, &'a dyn Fn(&str) -> bool, &'a str);
+
+///
+/// Transitions allowed per entity, a table replaces the default one as a whole.
+///
+/// By default a project, epic or sprint `Done` can not be left and the other statuses can follow each other,
+/// except `Standby` that goes back to `Pending` or `InProgress`.
+/// User stories and backlog items move forward through `InReview` to `Done` and can be reopened.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkflowSettings {
+    pub project: Transitions,
+    pub epic: Transitions,
+    pub sprint: Transitions,
+    pub user_story: Transitions,
+    pub backlog_item: Transitions
+}
+
+impl Default for WorkflowSettings {
+    fn default() -> Self {
+        let transitions = |table: Vec<(&str, Vec<&str>)>| {
+            table
+                .into_iter()
+                .map(|(status, next)| (status.to_string(), next.into_iter().map(String::from).collect()))
+                .collect::<Transitions>()
+        };
+
+        let table = transitions(vec![
+            ("Pending", vec!["InProgress", "Standby", "Done"]),
+            ("InProgress", vec!["Pending", "Standby", "Done"]),
+            ("Standby", vec!["Pending", "InProgress"]),
+            ("Done", vec![]),
+        ]);
+
+        let item_table = transitions(vec![
+            ("ToDo", vec!["InProgress", "Blocked"]),
+            ("InProgress", vec!["ToDo", "InReview", "Done", "Blocked"]),
+            ("InReview", vec!["InProgress", "Done", "Blocked"]),
+            ("Blocked", vec!["ToDo", "InProgress"]),
+            ("Done", vec!["InProgress"]),
+        ]);
+
+        WorkflowSettings {
+            project: table.clone(),
+            epic: table.clone(),
+            sprint: table,
+            user_story: item_table.clone(),
+            backlog_item: item_table
+        }
+    }
+}
+
+impl WorkflowSettings {
+    ///
+    /// Table of the entity, e.g: `sprint`, `None` when its status is not checked.
+    pub fn table(&self, kind: &str) -> Option<&Transitions> {
+        match kind {
+            "project" => Some(&self.project),
+            "epic" => Some(&self.epic),
+            "sprint" => Some(&self.sprint),
+            "user_story" => Some(&self.user_story),
+            "backlog_item" => Some(&self.backlog_item),
+            _ => None
+        }
+    }
+}
+
+///
+/// AuthSettings attributes:
+///
+/// - `api_keys`: Static keys sent in the `X-API-Key` header and the caller they identify, e.g: `{ "..." = "M1" }`.
+/// - `jwt_hs256_secret`: Secret shared with the issuer of the HS256 tokens.
+/// - `jwt_rs256_public_key`: PEM file of the public key that verifies the RS256 tokens.
+/// - `jwt_issuer` and `jwt_audience`: Required `iss` and `aud` of the tokens, they are not checked when unset.
+///
+/// The API is open when there are no keys and no JWT key.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSettings {
+    pub api_keys: BTreeMap<String, String>,
+    pub jwt_hs256_secret: Option<String>,
+    pub jwt_rs256_public_key: Option<String>,
+    pub jwt_issuer: Option<String>,
+    pub jwt_audience: Option<String>
+}
+
+impl AuthSettings {
+    pub fn is_enabled(&self) -> bool {
+        !self.api_keys.is_empty() || self.jwt_hs256_secret.is_some() || self.jwt_rs256_public_key.is_some()
+    }
+}
+
+///
+/// Every problem found in the settings, they are reported together so they can be fixed at once.
+#[derive(Debug)]
+pub struct SettingsError {
+    pub problems: Vec<String>
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid settings:")?;
+
+        for problem in &self.problems {
+            writeln!(f, "  - {}", problem)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl Settings {
+    ///
+    /// It loads the file in `SETTINGS_PATH` (or `little_things.toml` when it exists)
+    /// and applies the environment over it.
+    pub fn load() -> Result<Settings, SettingsError> {
+        let vars: HashMap<String, String> = env::vars().collect();
+
+        let file = match vars.get("SETTINGS_PATH") {
+            Some(path) => Some(fs::read_to_string(path).map_err(|err| SettingsError {
+                problems: vec![format!("SETTINGS_PATH: could not read '{}': {}", path, err)]
+            })?),
+            None => fs::read_to_string(DEFAULT_SETTINGS_PATH).ok()
+        };
+
+        Settings::from_sources(file.as_deref(), &vars)
+    }
+
+    ///
+    /// It builds the settings from the content of a TOML file and the environment variables:
+    ///
+    /// `SERVER_HOST`, `SERVER_PORT`, `CORS_ORIGIN`, `STORAGE_BACKEND`, `SPREADSHEET_ID`, `SHEETS_BASE_URL`,
+    /// `SHEETS_TOKEN`, `CREDENTIALS_PATH`, `SQLITE_PATH`, `PROJECTS_SHEET_NAME`, `METADATA_SHEET_NAME`,
+    /// `SPRINT_ITEMS_SHEET_NAME`, `SCOPE_CHANGES_SHEET_NAME`, `ASSIGNMENTS_SHEET_NAME`, `PERMISSIONS_SHEET_NAME`, `AUDIT_SHEET_NAME`,
+    /// `TRASH_SHEET_NAME`, `ID_MODE`, `TRASH_RETENTION_DAYS`, `AUTH_API_KEYS` (e.g: `key1:M1,key2:M2`), `JWT_HS256_SECRET`, `JWT_RS256_PUBLIC_KEY`, `JWT_ISSUER` and `JWT_AUDIENCE`.
+    pub fn from_sources(file: Option<&str>, vars: &HashMap<String, String>) -> Result<Settings, SettingsError> {
+        let mut settings = match file {
+            Some(content) => toml::from_str::<Settings>(content).map_err(|err| SettingsError {
+                problems: vec![format!("settings file: {}", err.to_string().trim())]
+            })?,
+            None => Settings::default()
+        };
+
+        let mut problems = settings.apply_env(vars);
+        problems.extend(settings.validate());
+
+        if !problems.is_empty() { return Err(SettingsError { problems }) }
+
+        Ok(settings)
+    }
+
+    fn apply_env(&mut self, vars: &HashMap<String, String>) -> Vec<String> {
+        let mut problems = vec![];
+        let var = |name: &str| vars.get(name).cloned();
+
+        if let Some(host) = var("SERVER_HOST") { self.server.host = host }
+        if let Some(origin) = var("CORS_ORIGIN") { self.server.cors_origin = origin }
+        if let Some(id) = var("SPREADSHEET_ID") { self.storage.spreadsheet_id = Some(id) }
+        if let Some(base_url) = var("SHEETS_BASE_URL") { self.storage.sheets_base_url = Some(base_url) }
+        if let Some(token) = var("SHEETS_TOKEN") { self.storage.sheets_token = token }
+        if let Some(path) = var("CREDENTIALS_PATH") { self.storage.credentials_path = path }
+        if let Some(path) = var("SQLITE_PATH") { self.storage.sqlite_path = path }
+        if let Some(name) = var("PROJECTS_SHEET_NAME") { self.sheets.projects = name }
+        if let Some(name) = var("METADATA_SHEET_NAME") { self.sheets.metadata = name }
+        if let Some(name) = var("SPRINT_ITEMS_SHEET_NAME") { self.sheets.sprint_items = name }
+        if let Some(name) = var("SCOPE_CHANGES_SHEET_NAME") { self.sheets.scope_changes = name }
+        if let Some(name) = var("ASSIGNMENTS_SHEET_NAME") { self.sheets.assignments = name }
+        if let Some(name) = var("PERMISSIONS_SHEET_NAME") { self.sheets.permissions = name }
+        if let Some(name) = var("AUDIT_SHEET_NAME") { self.sheets.audit = name }
+        if let Some(name) = var("TRASH_SHEET_NAME") { self.sheets.trash = name }
+        if let Some(secret) = var("JWT_HS256_SECRET") { self.auth.jwt_hs256_secret = Some(secret) }
+        if let Some(path) = var("JWT_RS256_PUBLIC_KEY") { self.auth.jwt_rs256_public_key = Some(path) }
+        if let Some(issuer) = var("JWT_ISSUER") { self.auth.jwt_issuer = Some(issuer) }
+        if let Some(audience) = var("JWT_AUDIENCE") { self.auth.jwt_audience = Some(audience) }
+
+        if let Some(port) = var("SERVER_PORT") {
+            match port.parse() {
+                Ok(port) => self.server.port = port,
+                Err(_) => problems.push(format!("SERVER_PORT: '{}' is not a port", port))
+            }
+        }
+
+        if let Some(backend) = var("STORAGE_BACKEND") {
+            match backend.parse() {
+                Ok(backend) => self.storage.backend = backend,
+                Err(err) => problems.push(format!("STORAGE_BACKEND: {}", err))
+            }
+        }
+
+        if let Some(mode) = var("ID_MODE") {
+            match mode.parse() {
+                Ok(mode) => self.ids.mode = mode,
+                Err(err) => problems.push(format!("ID_MODE: {}", err))
+            }
+        }
+
+        if let Some(days) = var("TRASH_RETENTION_DAYS") {
+            match days.parse() {
+                Ok(days) => self.trash.retention_days = days,
+                Err(_) => problems.push(format!("TRASH_RETENTION_DAYS: '{}' is not a number of days", days))
+            }
+        }
+
+        if let Some(keys) = var("AUTH_API_KEYS") {
+            for pair in keys.split(',').filter(|pair| !pair.trim().is_empty()) {
+                match pair.split_once(':') {
+                    Some((key, subject)) => { self.auth.api_keys.insert(key.trim().to_string(), subject.trim().to_string()); }
+                    None => problems.push("AUTH_API_KEYS: every key must be followed by its caller, e.g: key1:M1,key2:M2".to_string())
+                }
+            }
+        }
+
+        problems
+    }
+
+    ///
+    /// It checks the settings are usable before the server starts.
+    fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
+        let is_url = |url: &str| url.starts_with("http://") || url.starts_with("https://");
+
+        if self.server.host.trim().is_empty() {
+            problems.push("server.host: it can not be empty".to_string());
+        }
+
+        if self.server.port == 0 {
+            problems.push("server.port: it must be between 1 and 65535".to_string());
+        }
+
+        if !is_url(&self.server.cors_origin) || self.server.cors_origin.ends_with('/') {
+            problems.push(format!(
+                "server.cors_origin: '{}' is not an origin, e.g: http://localhost:1420",
+                self.server.cors_origin
+            ));
+        }
+
+        match self.storage.backend {
+            StorageBackend::GoogleSheets => {
+                if self.storage.spreadsheet_id.as_deref().is_none_or(|id| id.trim().is_empty()) {
+                    problems.push("storage.spreadsheet_id: it is required by the google_sheets backend (SPREADSHEET_ID)".to_string());
+                }
+
+                match &self.storage.sheets_base_url {
+                    Some(base_url) if !is_url(base_url) => {
+                        problems.push(format!("storage.sheets_base_url: '{}' is not a URL", base_url));
+                    }
+                    Some(_) => {}
+                    None if !Path::new(&self.storage.credentials_path).is_file() => {
+                        problems.push(format!(
+                            "storage.credentials_path: '{}' does not exist",
+                            self.storage.credentials_path
+                        ));
+                    }
+                    None => {}
+                }
+            }
+            StorageBackend::Sqlite if self.storage.sqlite_path.trim().is_empty() => {
+                problems.push("storage.sqlite_path: it is required by the sqlite backend".to_string());
+            }
+            _ => {}
+        }
+
+        // The name is part of every range, e.g: `Projects!A1:G1`
+        let is_sheet_name = |name: &str| !name.trim().is_empty() && !name.contains(['!', '\'']);
+
+        let sheets = [
+            ("sheets.projects", &self.sheets.projects),
+            ("sheets.metadata", &self.sheets.metadata),
+            ("sheets.sprint_items", &self.sheets.sprint_items),
+            ("sheets.scope_changes", &self.sheets.scope_changes),
+            ("sheets.assignments", &self.sheets.assignments),
+            ("sheets.permissions", &self.sheets.permissions),
+            ("sheets.audit", &self.sheets.audit),
+            ("sheets.trash", &self.sheets.trash),
+        ];
+
+        for (index, (key, name)) in sheets.iter().enumerate() {
+            if !is_sheet_name(name) {
+                problems.push(format!("{}: '{}' is not a valid sheet name", key, name));
+            } else if let Some((other, _)) = sheets[..index].iter().find(|(_, other)| other == name) {
+                problems.push(format!("{}: '{}' is already used by {}", key, name, other));
+            }
+        }
+
+        if self.auth.api_keys.iter().any(|(key, subject)| key.trim().is_empty() || subject.trim().is_empty()) {
+            problems.push("auth.api_keys: the keys and their callers can not be empty".to_string());
+        }
+
+        if self.auth.jwt_hs256_secret.as_deref().is_some_and(|secret| secret.trim().is_empty()) {
+            problems.push("auth.jwt_hs256_secret: it can not be empty".to_string());
+        }
+
+        if let Some(path) = &self.auth.jwt_rs256_public_key {
+            match fs::read(path) {
+                Ok(pem) if DecodingKey::from_rsa_pem(&pem).is_err() => {
+                    problems.push(format!("auth.jwt_rs256_public_key: '{}' is not a RSA public key in PEM", path));
+                }
+                Ok(_) => {}
+                Err(err) => problems.push(format!("auth.jwt_rs256_public_key: could not read '{}': {}", path, err))
+            }
+        }
+
+        let is_status = |status: &str| status.parse::<Status>().is_ok();
+        let is_item_status = |status: &str| status.parse::<ItemStatus>().is_ok();
+
+        let tables: [StatusCheck; 5] = [
+            ("project", &is_status, "Pending, InProgress, Done or Standby"),
+            ("epic", &is_status, "Pending, InProgress, Done or Standby"),
+            ("sprint", &is_status, "Pending, InProgress, Done or Standby"),
+            ("user_story", &is_item_status, "ToDo, InProgress, InReview, Done or Blocked"),
+            ("backlog_item", &is_item_status, "ToDo, InProgress, InReview, Done or Blocked"),
+        ];
+
+        for (kind, is_valid, expected) in tables {
+            let Some(table) = self.workflow.table(kind) else { continue };
+
+            for status in table.iter().flat_map(|(status, next)| [status].into_iter().chain(next)) {
+                if !is_valid(status) {
+                    problems.push(format!("workflow.{}: '{}' is not a status, expected {}", kind, status, expected));
+                }
+            }
+        }
+
+        problems
+    }
+
+    ///
+    /// Address where the server listens, e.g: ("127.0.0.1", 8080).
+    pub fn bind_address(&self) -> (String, u16) {
+        (self.server.host.clone(), self.server.port)
+    }
+}
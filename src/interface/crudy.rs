@@ -1,18 +1,40 @@
-use actix_web::{web, HttpResponse};
+use actix_web::web;
 use async_trait::async_trait;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{config::app_state::AppState, model::response::Response, util::error::AppError};
+
+///
+/// Result of every endpoint: the `Response` envelope, or an `AppError`
+/// that actix-web turns into the proper HTTP status (with the same envelope).
+pub type ApiResult<T> = Result<web::Json<Response<T>>, AppError>;
 
 ///
 /// It encompasess common main functions in a process.
+///
+/// `Entity`: Model returned in the `content` of the responses.
 #[async_trait]
 pub trait CRUD {
-    type CreatePayload: DeserializeOwned + Send + 'static;
-    type ReadPayload: DeserializeOwned + Send + 'static;
+    type Entity: Serialize + Send + 'static;
+    type CreatePayload: ProjectScoped + Send + 'static;
+    type ReadPayload: ProjectScoped + Send + 'static;
     type UpdatePayload: DeserializeOwned + Send + 'static;
     type DeletePayload: DeserializeOwned + Send + 'static;
 
-    async fn create(data: web::Json<Self::CreatePayload>) -> HttpResponse;
-    async fn read(param: Option<web::Query<Self::ReadPayload>>) -> HttpResponse;
-    async fn update(data: web::Json<Self::UpdatePayload>) -> HttpResponse;
-    async fn delete(data: web::Json<Self::DeletePayload>) -> HttpResponse;
-}
\ No newline at end of file
+    async fn create(state: web::Data<AppState>, data: web::Json<Self::CreatePayload>) -> ApiResult<Self::Entity>;
+    async fn read(state: web::Data<AppState>, param: Option<web::Query<Self::ReadPayload>>) -> ApiResult<Self::Entity>;
+    async fn update(state: web::Data<AppState>, data: web::Json<Self::UpdatePayload>) -> ApiResult<Self::Entity>;
+    async fn delete(state: web::Data<AppState>, data: web::Json<Self::DeletePayload>) -> ApiResult<Self::Entity>;
+}
+
+///
+/// Payload of `create` or `read` that tells the project of the request, the roles of the caller are checked
+/// in that project (see `guard`). `None` when it does not belong to a project, e.g: the members.
+pub trait ProjectScoped: DeserializeOwned {
+    fn project_id(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// The routes addressed by a path take the project from it.
+impl ProjectScoped for () {}
@@ -1 +1,5 @@
-pub mod crudy;
\ No newline at end of file
+pub mod crudy;
+pub mod store;
+pub mod sheet_entity;
+pub mod resource;
+pub mod relation;
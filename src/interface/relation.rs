@@ -0,0 +1,26 @@
+use serde_json::Value;
+
+use crate::util::error::AppError;
+
+///
+/// It describes how a relation between entities is stored in a sheet of its own, one row per relation
+/// (e.g: the backlog items of the sprints in `SprintItems`), so its changes are recorded as the ones of the entities.
+///
+/// - `COLUMNS`: First and last column, e.g: ("A", "F").
+/// - `FIELDS`: Name of the field stored in every column, in order.
+/// - `KEYS`: Position of the columns that identify the relation, the project first.
+/// - `KIND`: Name of the relation in the `Audit` sheet, e.g: `sprint_item`.
+pub trait Relation: Sized {
+    const COLUMNS: (&'static str, &'static str);
+    const FIELDS: &'static [&'static str];
+    const KEYS: &'static [usize];
+    const KIND: &'static str;
+
+    ///
+    /// It builds the relation from the cells of a row.
+    fn from_row(cells: &[Value]) -> Result<Self, AppError>;
+
+    ///
+    /// Cells of the row, in the same order as `FIELDS`.
+    fn to_row(&self) -> Vec<String>;
+}
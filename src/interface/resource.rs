@@ -0,0 +1,205 @@
+use actix_web::web;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::{
+    config::app_state::AppState,
+    interface::{crudy::ApiResult, sheet_entity::SheetEntity, store::StoreResult},
+    model::{
+        response::{FieldError, Response},
+        spreadsheet::FieldChanges,
+        trash::Relations,
+    },
+    service::{audit::AuditService, spreadsheet::find_row, trash::TrashService, workflow::check_transition},
+    util::{error::AppError, row::cells, util::{merge_patch, unix_now}},
+};
+
+///
+/// Parameters of the path that identify an entity, e.g: `/projects/{id}/epics/{epic_id}`.
+pub trait PathKeys {
+    fn keys(self) -> Vec<String>;
+}
+
+impl PathKeys for String {
+    fn keys(self) -> Vec<String> {
+        vec![self]
+    }
+}
+
+impl PathKeys for (String, String) {
+    fn keys(self) -> Vec<String> {
+        vec![self.0, self.1]
+    }
+}
+
+impl PathKeys for (String, String, String) {
+    fn keys(self) -> Vec<String> {
+        vec![self.0, self.1, self.2]
+    }
+}
+
+///
+/// It addresses an entity by its id instead of its cells, the row is resolved by the server.
+#[async_trait]
+pub trait Resource: Send + 'static {
+    type Entity: SheetEntity;
+    type Path: PathKeys + DeserializeOwned + Send + 'static;
+
+    async fn get(state: web::Data<AppState>, path: web::Path<Self::Path>) -> ApiResult<Self::Entity> {
+        let (row, stored) = find_row::<Self::Entity>(state.store.as_ref(), state.sheet(), &path.into_inner().keys()).await?;
+
+        let mut entity = Self::Entity::from_row(&stored, Self::Entity::row_label(row))?;
+
+        Self::loaded(&state, &mut entity).await?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(vec![entity]))))
+    }
+
+    ///
+    /// It applies the changes as a JSON Merge Patch (RFC 7396) against the entity,
+    /// every field is validated with its type and the row is written at once with a new `updatedAt`.
+    ///
+    /// The keys of the entity (e.g: `id`), `row` and `updatedAt` can not be changed,
+    /// and the status only follows the transitions of the workflow.
+    async fn patch(state: web::Data<AppState>, path: web::Path<Self::Path>, data: web::Json<FieldChanges>) -> ApiResult<Self::Entity> {
+        let (row, stored) = find_row::<Self::Entity>(state.store.as_ref(), state.sheet(), &path.into_inner().keys()).await?;
+
+        let current = Self::Entity::from_row(&stored, Self::Entity::row_label(row))?;
+        let mut document = serde_json::to_value(&current).map_err(|err| AppError::CorruptRow(err.to_string()))?;
+
+        let changes = data.into_inner();
+
+        let fixed_fields = Self::Entity::KEYS
+            .iter()
+            .map(|index| Self::Entity::FIELDS[*index])
+            .chain(["row", "updatedAt"])
+            .collect::<Vec<&str>>();
+
+        let mut invalid_fields = vec![];
+
+        for (field, value) in changes.iter() {
+            if document.get(field).is_none() {
+                invalid_fields.push(FieldError { field: field.clone(), message: "Unknown field".to_string() });
+                continue
+            }
+
+            if fixed_fields.contains(&field.as_str()) {
+                invalid_fields.push(FieldError { field: field.clone(), message: "It can not be changed".to_string() });
+                continue
+            }
+
+            // Each field is checked on its own, so every invalid field is reported
+            let mut candidate = document.clone();
+            merge_patch(&mut candidate, &json!({ field: value }));
+
+            if let Err(err) = serde_json::from_value::<Self::Entity>(candidate) {
+                invalid_fields.push(FieldError { field: field.clone(), message: err.to_string() });
+            }
+        }
+
+        if !invalid_fields.is_empty() { return Err(AppError::InvalidFields(invalid_fields)) }
+
+        merge_patch(&mut document, &Value::Object(changes.clone()));
+        document["updatedAt"] = json!(unix_now());
+
+        let entity: Self::Entity = serde_json::from_value(document).map_err(|err| AppError::Validation(err.to_string()))?;
+
+        let entity = Self::save(&state, row, &current, entity, &changes).await?;
+
+        Ok(web::Json(Response::ok("Data was updated in the sheet successfully!", Some(vec![entity]))))
+    }
+
+    ///
+    /// It writes the entity over `current` in its row, the status only follows the transitions of the workflow
+    /// and the hooks of `patch` are called around the write.
+    ///
+    /// `changes`: The fields changed, as they were sent.
+    async fn save(
+        state: &AppState,
+        row: usize,
+        current: &Self::Entity,
+        mut entity: Self::Entity,
+        changes: &FieldChanges
+    ) -> StoreResult<Self::Entity> {
+        check_transition(&state.settings.workflow, current, &entity)?;
+        Self::check(state, Some(current), &entity).await?;
+        Self::prepare(current, &mut entity, changes);
+
+        let (first_col, last_col) = Self::Entity::COLUMNS;
+        let full_range = format!("{}!{}{}:{}{}", state.sheet(), first_col, row, last_col, row);
+
+        let values = entity.to_row();
+
+        state.store.update(full_range.as_str(), values.clone()).await?;
+
+        // The entity is answered as it was stored, e.g: an optional text removed is read back as empty
+        let mut entity = Self::Entity::from_row(&cells(&values), Self::Entity::row_label(row))?;
+
+        AuditService::entity(state, Some(current), Some(&entity)).await?;
+
+        Self::patched(state, &entity).await?;
+        Self::loaded(state, &mut entity).await?;
+
+        Ok(entity)
+    }
+
+    ///
+    /// It moves the entity to the trash, returning what was removed.
+    async fn remove(state: web::Data<AppState>, path: web::Path<Self::Path>) -> ApiResult<Self::Entity> {
+        let (row, stored) = find_row::<Self::Entity>(state.store.as_ref(), state.sheet(), &path.into_inner().keys()).await?;
+
+        let entity = Self::Entity::from_row(&stored, Self::Entity::row_label(row))?;
+
+        Self::removing(&state, &entity).await?;
+
+        TrashService::discard::<Self>(&state, row, &entity).await?;
+
+        Ok(web::Json(Response::ok("It was removed successfully", Some(vec![entity]))))
+    }
+
+    ///
+    /// Called before `get` and `patch` answer the entity, e.g: to add what is not stored in its row.
+    async fn loaded(_state: &AppState, _entity: &mut Self::Entity) -> StoreResult<()> {
+        Ok(())
+    }
+
+    ///
+    /// It checks the entity can be stored, e.g: the members it refers to exist.
+    ///
+    /// `current`: The entity as it is stored, `None` when it is created.
+    async fn check(_state: &AppState, _current: Option<&Self::Entity>, _entity: &Self::Entity) -> StoreResult<()> {
+        Ok(())
+    }
+
+    ///
+    /// Called before `patch` writes the entity, e.g: to stamp a date when the status changes.
+    ///
+    /// `current`: The entity as it is stored.
+    fn prepare(_current: &Self::Entity, _entity: &mut Self::Entity, _changes: &FieldChanges) {}
+
+    ///
+    /// Called once `patch` has written the entity, e.g: to update what is computed from it.
+    async fn patched(_state: &AppState, _entity: &Self::Entity) -> StoreResult<()> {
+        Ok(())
+    }
+
+    ///
+    /// Called before `remove` removes the entity, e.g: to refuse when something refers to it.
+    async fn removing(_state: &AppState, _entity: &Self::Entity) -> StoreResult<()> {
+        Ok(())
+    }
+
+    ///
+    /// Called when the entity is moved to the trash, it removes what refers to the entity (e.g: its assignee)
+    /// and returns it, so it is kept in the trash with the entity.
+    async fn detach(_state: &AppState, _entity: &Self::Entity) -> StoreResult<Relations> {
+        Ok(Relations::default())
+    }
+
+    ///
+    /// Called once the entity is restored from the trash, it puts back what `detach` removed.
+    async fn attach(_state: &AppState, _entity: &Self::Entity, _relations: &Relations) -> StoreResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::util::error::AppError;
+
+///
+/// It describes how an entity is stored in the `Projects` sheet, one row per entity.
+///
+/// - `COLUMNS`: First and last column, e.g: ("I", "P").
+/// - `FIELDS`: Name of the field stored in every column, in order.
+/// - `KEYS`: Position of the columns that identify the entity, in the same order as the path of its routes
+///   (e.g: `/projects/{id}/epics/{epic_id}` is the project id and then the epic id).
+/// - `ID_PREFIX`: Prefix of the ids, e.g: `E` for `E1`.
+/// - `KIND`: Name of the entity in the settings, e.g: `epic` in `[workflow.epic]`.
+pub trait SheetEntity: Serialize + DeserializeOwned + Sized + Send + Sync + 'static {
+    const COLUMNS: (&'static str, &'static str);
+    const FIELDS: &'static [&'static str];
+    const KEYS: &'static [usize];
+    const ID_PREFIX: &'static str;
+    const KIND: &'static str;
+
+    ///
+    /// It builds the entity from the cells of a row.
+    ///
+    /// `row`: Position of the row, e.g: `A1:G1`.
+    fn from_row(cells: &[Value], row: String) -> Result<Self, AppError>;
+
+    ///
+    /// Cells of the row, in the same order as `FIELDS`.
+    fn to_row(&self) -> Vec<String>;
+
+    ///
+    /// Position reported in `row` for an entity stored in `row_index` (one based), e.g: `I3:P3`.
+    /// It is the range the legacy routes address the entity with.
+    fn row_label(row_index: usize) -> String {
+        format!("{}{}:{}{}", Self::COLUMNS.0, row_index, Self::COLUMNS.1, row_index)
+    }
+}
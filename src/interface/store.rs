@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::util::error::AppError;
+
+pub type StoreResult<T> = Result<T, AppError>;
+
+///
+/// It encompasses the operations that a storage backend must provide to the services.
+///
+/// Ranges are written in A1 notation including the sheet name, e.g: `Projects!A1:G1`,
+/// so every backend keeps the same layout as the original spreadsheet.
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Creates the sheet (or its equivalent) when it does not exist yet.
+    async fn ensure_sheet(&self, sheet_name: &str) -> StoreResult<()>;
+
+    /// Returns the rows of the range, trailing empty cells and rows are omitted.
+    async fn read(&self, range: &str) -> StoreResult<Vec<Vec<Value>>>;
+
+    /// Inserts the row after the last non-empty row of the range, returning the row (one based) written.
+    async fn append(&self, range: &str, row: Vec<String>) -> StoreResult<usize>;
+
+    /// Inserts the rows one after the other in a single write, as `append` does, returning the first row (one based) written.
+    async fn append_rows(&self, range: &str, rows: Vec<Vec<String>>) -> StoreResult<usize>;
+
+    /// Overwrites the cells of the range starting from its top-left cell.
+    async fn update(&self, range: &str, row: Vec<String>) -> StoreResult<()>;
+
+    /// Removes the content of every cell in the range.
+    async fn clear(&self, range: &str) -> StoreResult<()>;
+}
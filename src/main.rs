@@ -3,58 +3,72 @@ mod model;
 mod service;
 mod util;
 mod interface;
+mod storage;
+mod routes;
+
+#[cfg(test)]
+mod tests;
 
 extern crate dotenv;
 
 use actix_cors::Cors;
-use actix_web::{App, HttpServer, http::header, web};
+use actix_web::{App, HttpServer, http::header::{self, HeaderName}, middleware::from_fn, web};
 use dotenv::dotenv;
 
-use crate::{interface::crudy::CRUD, service::{epic::EpicService, product_backlog::ProductBacklogService, sprint::SprintService}}; 
+use crate::{
+    config::{app_state::AppState, settings::Settings},
+    service::auth::authenticate,
+    storage::build_store,
+};
 
 use rustls::crypto::ring::default_provider;
-use service::{project::ProjectService, user_story::UserStoryService};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
 
+    // Every problem of the settings is reported at once, the server does not start with any of them
+    let settings = Settings::load().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1)
+    });
+
     // Supplier Cryptographic
     default_provider()
         .install_default()
         .expect("Failed to set default crypto provider");
 
-    HttpServer::new(|| {
+    if !settings.auth.is_enabled() {
+        eprintln!("Warning: no API keys nor JWT keys are set, anyone who reaches the server can change the data");
+    }
+
+    // Storage backend shared by every worker, it is connected only once
+    let address = settings.bind_address();
+    let state = web::Data::new(AppState::new(build_store(&settings).await, settings));
+
+    // The sheet of the entities is read before anything is written to it, e.g: `GET /projects`
+    state.ensure_sheet(state.sheet()).await.expect("Could not create the sheet of the entities");
+
+    HttpServer::new(move || {
         let cors = Cors::default()
-            .allowed_origin("http://localhost:1420")
+            .allowed_origin(&state.settings.server.cors_origin)
             .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE"])
-            .allowed_headers(vec![header::ACCEPT, header::CONTENT_TYPE]) // `header` of the actix-web-cors
+            .allowed_headers(vec![
+                header::ACCEPT,
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                HeaderName::from_static("x-api-key"),
+            ]) // `header` of the actix-web-cors
             .max_age(3600);
 
+        // CORS wraps the authentication, so the preflight requests are answered before it
         App::new()
+            .wrap(from_fn(authenticate))
             .wrap(cors)
-            .route("/projects", web::get().to(<ProjectService as CRUD>::read))
-            .route("/project", web::post().to(<ProjectService as CRUD>::create))
-            .route("/project", web::patch().to(<ProjectService as CRUD>::update))
-            .route("/project", web::delete().to(<ProjectService as CRUD>::delete))
-            .route("/epics", web::get().to(<EpicService as CRUD>::read))
-            .route("/epic", web::post().to(<EpicService as CRUD>::create))
-            .route("/epic", web::patch().to(<EpicService as CRUD>::update))
-            .route("/epic", web::delete().to(<EpicService as CRUD>::delete))
-            .route("/uss", web::get().to(<UserStoryService as CRUD>::read))
-            .route("/us", web::post().to(<UserStoryService as CRUD>::create))
-            .route("/us", web::patch().to(<UserStoryService as CRUD>::update))
-            .route("/us", web::delete().to(<UserStoryService as CRUD>::delete))
-            .route("/pbs", web::get().to(<ProductBacklogService as CRUD>::read))
-            .route("/pb", web::post().to(<ProductBacklogService as CRUD>::create))
-            .route("/pb", web::patch().to(<ProductBacklogService as CRUD>::update))
-            .route("/pb", web::delete().to(<ProductBacklogService as CRUD>::delete))
-            .route("/sprints", web::get().to(<SprintService as CRUD>::read))
-            .route("/sprint", web::post().to(<SprintService as CRUD>::create))
-            .route("/sprint", web::post().to(<SprintService as CRUD>::update))
-            .route("/sprint", web::post().to(<SprintService as CRUD>::delete))
+            .app_data(state.clone())
+            .configure(routes::configure)
     })
-    .bind(("127.0.0.1", 8080))?
+    .bind(address)?
     .run()
     .await
 }
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{interface::relation::Relation, util::{error::AppError, row::{parse, text}, util::ItemStatus}};
+
+///
+/// A member assigned to a story or backlog item, stored in the `Assignments` sheet (columns `A:E`, one row per entity).
+///
+/// Assignment attributes:
+///
+/// - `project_id`: _
+/// - `kind`: Kind of the entity, `user_story` or `backlog_item`.
+/// - `entity_id`: Id of the `UserStoryBox` or `ProductBacklogItem`.
+/// - `member_id`: Id of the `TeamMember`, the assignee.
+/// - `assigned_at`: When the member was assigned in unix epoch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Assignment {
+    pub project_id: String,
+    pub kind: String,
+    pub entity_id: String,
+    pub member_id: String,
+    pub assigned_at: i64
+}
+
+impl Relation for Assignment {
+    const COLUMNS: (&'static str, &'static str) = ("A", "E");
+    const FIELDS: &'static [&'static str] = &["projectId", "kind", "entityId", "memberId", "assignedAt"];
+    const KEYS: &'static [usize] = &[0, 1, 2];
+    const KIND: &'static str = "assignment";
+
+    fn from_row(cells: &[Value]) -> Result<Self, AppError> {
+        Ok(Assignment {
+            project_id: text(cells, 0),
+            kind: text(cells, 1),
+            entity_id: text(cells, 2),
+            member_id: text(cells, 3),
+            assigned_at: parse(cells, 4, "assignedAt")?
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.project_id.clone(),
+            self.kind.clone(),
+            self.entity_id.clone(),
+            self.member_id.clone(),
+            self.assigned_at.to_string(),
+        ]
+    }
+}
+
+///
+/// Body of `PUT .../assignee`, the member replaces the one assigned before.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignMember {
+    pub member_id: String
+}
+
+///
+/// A story or backlog item assigned to a member, listed by `GET /members/{id}/work`.
+///
+/// Work attributes:
+///
+/// - `kind`: `user_story` or `backlog_item`.
+/// - `points`: `storyPoints` of the story or `points` of the item.
+/// - `assigned_at`: When the member was assigned in unix epoch.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Work {
+    pub kind: String,
+    pub project_id: String,
+    pub epic_id: String,
+    pub id: String,
+    pub title: String,
+    pub status: ItemStatus,
+    pub points: Option<i64>,
+    pub assigned_at: i64
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    interface::sheet_entity::SheetEntity,
+    model::member::TeamMember,
+    util::{error::AppError, row::{parse, text}},
+};
+
+///
+/// A field changed by a request, stored in the `Audit` sheet (columns `A:H`, one row per field).
+/// Records are only appended, the sheet is the trace of who changed what.
+///
+/// AuditRecord attributes:
+///
+/// - `change`: Id shared by the records of the same request, the cascades it makes included.
+/// - `timestamp`: When the change was made in unix epoch.
+/// - `actor`: Caller who made it (see `Identity`), `anonymous` while the API is open.
+/// - `entity`: Kind of the entity, e.g: `user_story`, or of the relation, e.g: `sprint_item` (see `Relation`).
+/// - `entity_id`: Keys of the entity as they appear in its routes, e.g: `P1/E1/US1`.
+/// - `field`: Name of the field, e.g: `acceptanceCriteria`.
+/// - `old_value`: The cell before the change, empty when the entity was created.
+/// - `new_value`: The cell after the change, empty when the entity was removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    pub change: String,
+    pub timestamp: i64,
+    pub actor: String,
+    pub entity: String,
+    pub entity_id: String,
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String
+}
+
+impl AuditRecord {
+    pub const COLUMNS: (&'static str, &'static str) = ("A", "H");
+
+    pub fn from_row(cells: &[Value]) -> Result<Self, AppError> {
+        Ok(AuditRecord {
+            change: text(cells, 0),
+            timestamp: parse(cells, 1, "timestamp")?,
+            actor: text(cells, 2),
+            entity: text(cells, 3),
+            entity_id: text(cells, 4),
+            field: text(cells, 5),
+            old_value: text(cells, 6),
+            new_value: text(cells, 7)
+        })
+    }
+
+    pub fn to_row(&self) -> Vec<String> {
+        vec![
+            self.change.clone(),
+            self.timestamp.to_string(),
+            self.actor.clone(),
+            self.entity.clone(),
+            self.entity_id.clone(),
+            self.field.clone(),
+            self.old_value.clone(),
+            self.new_value.clone(),
+        ]
+    }
+
+    ///
+    /// Project of the entity, `None` for the entities outside of the projects (the members).
+    pub fn project_id(&self) -> Option<&str> {
+        match self.entity.as_str() {
+            TeamMember::KIND => None,
+            _ => self.entity_id.split('/').next()
+        }
+    }
+}
+
+///
+/// Query of `GET /audit`, the records of the kind `entity` (e.g: `epic`) made from `since` (unix epoch).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditQuery {
+    pub entity: Option<String>,
+    pub since: Option<i64>
+}
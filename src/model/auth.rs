@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// How the caller was identified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuthMethod {
+    ApiKey,
+    Jwt
+}
+
+///
+/// The caller of a request, stored in the extensions of the request by the auth middleware
+/// (handlers receive it as `web::ReqData<Identity>`).
+///
+/// Identity attributes:
+///
+/// - `subject`: Who the caller is, the caller of the API key or the `sub` of the token, e.g: a member id.
+/// - `method`: _
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Identity {
+    pub subject: String,
+    pub method: AuthMethod
+}
+
+///
+/// Claims read from the JWT, `exp` is checked by the decoder.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: u64
+}
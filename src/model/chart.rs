@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// Points of a sprint day by day, from `start_date` to `end_date`.
+///
+/// SprintChart attributes:
+///
+/// - `sprint_id`: _
+/// - `start_date`: The initial date in unix epoch format.
+/// - `end_date`: The end date in unix epoch format.
+/// - `committed_points`: Points of the items added until the end of the first day.
+/// - `days`: One entry per day (`BurndownDay` or `BurnupDay`).
+/// - `scope_changes`: Items added, removed or estimated again after the first day.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintChart<D> {
+    pub sprint_id: String,
+    pub start_date: i64,
+    pub end_date: i64,
+    pub committed_points: i64,
+    pub days: Vec<D>,
+    pub scope_changes: Vec<ScopeChange>
+}
+
+///
+/// BurndownDay attributes:
+///
+/// - `date`: Beginning of the day (UTC) in unix epoch format.
+/// - `ideal`: Remaining points if the committed ones were burnt at a constant pace.
+/// - `remaining`: Points of the items not completed at the end of the day, `None` for the days to come.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BurndownDay {
+    pub date: i64,
+    pub ideal: f64,
+    pub remaining: Option<i64>
+}
+
+///
+/// BurnupDay attributes:
+///
+/// - `date`: Beginning of the day (UTC) in unix epoch format.
+/// - `ideal`: Completed points if the committed ones were burnt at a constant pace.
+/// - `completed`: Points of the items completed at the end of the day, `None` for the days to come.
+/// - `scope`: Points of the sprint at the end of the day, `None` for the days to come.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BurnupDay {
+    pub date: i64,
+    pub ideal: f64,
+    pub completed: Option<i64>,
+    pub scope: Option<i64>
+}
+
+///
+/// A change of the items once the sprint had started.
+///
+/// - `date`: When the item was added, removed or estimated again in unix epoch format.
+/// - `points`: Points gained by the sprint, negative when the item was removed or its points were lowered.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeChange {
+    pub date: i64,
+    pub item_id: String,
+    pub points: i64
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::util::util::Status;
+use crate::{interface::{crudy::ProjectScoped, sheet_entity::SheetEntity}, util::{error::AppError, row::{parse, text}, util::Status}};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -37,3 +38,45 @@ pub struct Epic {
 
     pub row: String
 }
+
+impl SheetEntity for Epic {
+    const COLUMNS: (&'static str, &'static str) = ("I", "P");
+    const FIELDS: &'static [&'static str] = &["id", "projectId", "title", "description", "status", "owner", "createdAt", "updatedAt"];
+    const KEYS: &'static [usize] = &[1, 0];
+    const ID_PREFIX: &'static str = "E";
+    const KIND: &'static str = "epic";
+
+    fn from_row(cells: &[Value], row: String) -> Result<Self, AppError> {
+        Ok(Epic {
+            id: text(cells, 0),
+            project_id: text(cells, 1),
+            title: text(cells, 2),
+            description: text(cells, 3),
+            status: parse(cells, 4, "status")?,
+            owner: text(cells, 5),
+            created_at: parse(cells, 6, "createdAt")?,
+            updated_at: parse(cells, 7, "updatedAt")?,
+
+            row
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.project_id.clone(),
+            self.title.clone(),
+            self.description.clone(),
+            format!("{:?}", self.status),
+            self.owner.clone(),
+            self.created_at.to_string(),
+            self.updated_at.to_string(),
+        ]
+    }
+}
+
+impl ProjectScoped for InsertEpicData {
+    fn project_id(&self) -> Option<&str> {
+        Some(&self.project_id)
+    }
+}
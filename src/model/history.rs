@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// A version of an entity, rebuilt from the records of the `Audit` sheet (see `AuditRecord`).
+///
+/// Version attributes:
+///
+/// - `version`: Position of the version, `0` is the entity as it was before the first change recorded.
+/// - `change`: Change that made the version, `None` for the version `0`.
+/// - `timestamp`: When the change was made in unix epoch, `None` for the version `0`.
+/// - `actor`: Caller who made the change, `None` for the version `0`.
+/// - `fields`: Fields changed by the version.
+/// - `entity`: The entity after the change, `None` when it was removed.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Version<E> {
+    pub version: usize,
+    pub change: Option<String>,
+    pub timestamp: Option<i64>,
+    pub actor: Option<String>,
+    pub fields: Vec<String>,
+    pub entity: Option<E>
+}
+
+///
+/// Query of `POST .../restore`, the version the entity goes back to.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreQuery {
+    pub version: usize
+}
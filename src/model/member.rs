@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{interface::{crudy::ProjectScoped, sheet_entity::SheetEntity}, util::{error::AppError, row::{parse, text}}};
+
+///
+/// Role of a member in the Scrum team.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScrumRole {
+    ProductOwner,
+    ScrumMaster,
+    Developer
+}
+
+impl FromStr for ScrumRole {
+   type Err = ();
+
+   fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "ProductOwner" => Ok(ScrumRole::ProductOwner),
+            "ScrumMaster" => Ok(ScrumRole::ScrumMaster),
+            "Developer" => Ok(ScrumRole::Developer),
+            _ => Err(())
+        }
+   }
+}
+
+///
+/// TeamMember attributes:
+///
+/// - `id`: _
+/// - `name`: Fullname of the member.
+/// - `email`: _
+/// - `role`: Whether the member is **ProductOwner**, **ScrumMaster** or **Developer**.
+/// - `capacity_hours`: Hours the member can work in a sprint.
+/// - `created_at`: Registered date in unix epoch.
+/// - `updated_at`: Updated date in unix epoch.
+///
+/// The `owner` of the projects, epics and sprints is the id of a member.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMember {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub role: ScrumRole,
+    pub capacity_hours: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+
+    pub row: String
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertTeamMember {
+    pub name: String,
+    pub email: String,
+    pub role: ScrumRole,
+    pub capacity_hours: i64,
+    pub created_at: i64
+}
+
+///
+/// Query of `GET /members`, every member (only the ones with `role` when it is given).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeamMemberQuery {
+    pub role: Option<ScrumRole>
+}
+
+impl TeamMember {
+    ///
+    /// Fields that are not valid, e.g: an email without `@`.
+    pub fn problems(&self) -> Vec<(&'static str, &'static str)> {
+        let mut problems = vec![];
+
+        if self.name.trim().is_empty() {
+            problems.push(("name", "It can not be empty"));
+        }
+
+        if !matches!(self.email.split_once('@'), Some((user, domain)) if !user.is_empty() && domain.contains('.')) {
+            problems.push(("email", "It is not an email address"));
+        }
+
+        if self.capacity_hours < 0 {
+            problems.push(("capacityHours", "It can not be negative"));
+        }
+
+        problems
+    }
+}
+
+impl SheetEntity for TeamMember {
+    const COLUMNS: (&'static str, &'static str) = ("BG", "BM");
+    const FIELDS: &'static [&'static str] = &["id", "name", "email", "role", "capacityHours", "createdAt", "updatedAt"];
+    const KEYS: &'static [usize] = &[0];
+    const ID_PREFIX: &'static str = "M";
+    const KIND: &'static str = "member";
+
+    fn from_row(cells: &[Value], row: String) -> Result<Self, AppError> {
+        Ok(TeamMember {
+            id: text(cells, 0),
+            name: text(cells, 1),
+            email: text(cells, 2),
+            role: parse(cells, 3, "role")?,
+            capacity_hours: parse(cells, 4, "capacityHours")?,
+            created_at: parse(cells, 5, "createdAt")?,
+            updated_at: parse(cells, 6, "updatedAt")?,
+
+            row
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.name.clone(),
+            self.email.clone(),
+            format!("{:?}", self.role),
+            self.capacity_hours.to_string(),
+            self.created_at.to_string(),
+            self.updated_at.to_string(),
+        ]
+    }
+}
+
+impl ProjectScoped for InsertTeamMember {}
+impl ProjectScoped for TeamMemberQuery {}
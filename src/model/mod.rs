@@ -4,4 +4,15 @@ pub mod epic;
 pub mod user_story;
 pub mod response;
 pub mod product_backlog;
-pub mod sprint;
\ No newline at end of file
+pub mod sprint;
+pub mod sprint_item;
+pub mod chart;
+pub mod velocity;
+pub mod summary;
+pub mod member;
+pub mod assignment;
+pub mod auth;
+pub mod permission;
+pub mod audit;
+pub mod history;
+pub mod trash;
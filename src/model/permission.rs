@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{interface::relation::Relation, util::{error::AppError, row::{parse, text}}};
+
+///
+/// Role of a caller in a project, it decides what the caller can change:
+///
+/// - `ProductOwner`: The project, its epics, stories and backlog items, and the roles of the project.
+/// - `ScrumMaster`: The sprints.
+/// - `Developer`: The status of the stories and backlog items assigned to it.
+/// - `Viewer`: Nothing, every role reads the project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProjectRole {
+    ProductOwner,
+    ScrumMaster,
+    Developer,
+    Viewer
+}
+
+impl FromStr for ProjectRole {
+   type Err = ();
+
+   fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "ProductOwner" => Ok(ProjectRole::ProductOwner),
+            "ScrumMaster" => Ok(ProjectRole::ScrumMaster),
+            "Developer" => Ok(ProjectRole::Developer),
+            "Viewer" => Ok(ProjectRole::Viewer),
+            _ => Err(())
+        }
+   }
+}
+
+///
+/// A role granted to a caller in a project, stored in the `Permissions` sheet (columns `A:C`, one row per caller and project).
+///
+/// Permission attributes:
+///
+/// - `project_id`: _
+/// - `subject`: The caller as it is identified, e.g: a member id (see `Identity`).
+/// - `role`: _
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    pub project_id: String,
+    pub subject: String,
+    pub role: ProjectRole
+}
+
+impl Relation for Permission {
+    const COLUMNS: (&'static str, &'static str) = ("A", "C");
+    const FIELDS: &'static [&'static str] = &["projectId", "subject", "role"];
+    const KEYS: &'static [usize] = &[0, 1];
+    const KIND: &'static str = "permission";
+
+    fn from_row(cells: &[Value]) -> Result<Self, AppError> {
+        Ok(Permission {
+            project_id: text(cells, 0),
+            subject: text(cells, 1),
+            role: parse(cells, 2, "role")?
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.project_id.clone(),
+            self.subject.clone(),
+            format!("{:?}", self.role),
+        ]
+    }
+}
+
+///
+/// Body of `PUT /projects/{id}/permissions/{subject}`, the role replaces the one granted before.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GrantRole {
+    pub role: ProjectRole
+}
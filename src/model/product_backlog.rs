@@ -1,11 +1,14 @@
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{interface::{crudy::ProjectScoped, sheet_entity::SheetEntity}, util::{error::AppError, row::{parse, text}, util::ItemStatus}};
 
 ///
 /// It identifies which type of item is
 ///
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ItemType {
     Story,
     Bug,
@@ -15,6 +18,8 @@ pub enum ItemType {
 /// 
 /// All items assigned to the product backlog such as stories, bugs and tasks.
 /// 
+/// - `status`: Where the item is in the workflow, stored in the last column (`AQ`).
+/// - `assignee`: Id of the member assigned in the `Assignments` sheet, it is not stored in the row.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProductBacklogItem {
@@ -26,9 +31,12 @@ pub struct ProductBacklogItem {
     pub description: Option<String>,
     pub points: Option<i64>,
     pub kind: ItemType,
+    pub status: ItemStatus,
     pub assigned_at: i64,
     pub created_at: i64,
     pub updated_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
 
     pub row: String
 }
@@ -43,10 +51,20 @@ pub struct InsertProductBacklogItem {
     pub description: Option<String>,
     pub points: Option<i64>,
     pub kind: ItemType,
+    #[serde(default)]
+    pub status: ItemStatus,
     pub assigned_at: i64,
     pub created_at: i64
 }
 
+///
+/// Query of `GET /pbs`, the items of the project `id` (only the ones in `status` when it is given).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductBacklogQuery {
+    pub id: String,
+    pub status: Option<ItemStatus>
+}
+
 impl FromStr for ItemType {
    type Err = ();
 
@@ -58,4 +76,70 @@ impl FromStr for ItemType {
             _ => Err(())
         }
    }
-}
\ No newline at end of file
+}
+
+impl SheetEntity for ProductBacklogItem {
+    const COLUMNS: (&'static str, &'static str) = ("AF", "AQ");
+    const FIELDS: &'static [&'static str] = &[
+        "id", "projectId", "epicId", "title", "priority", "description", "points", "kind", "assignedAt", "createdAt",
+        "updatedAt", "status"
+    ];
+    const KEYS: &'static [usize] = &[1, 0];
+    const ID_PREFIX: &'static str = "PB";
+    const KIND: &'static str = "backlog_item";
+
+    fn from_row(cells: &[Value], row: String) -> Result<Self, AppError> {
+        Ok(ProductBacklogItem {
+            id: text(cells, 0),
+            project_id: text(cells, 1),
+            epic_id: text(cells, 2),
+            title: text(cells, 3),
+            priority: text(cells, 4),
+            description: Some(text(cells, 5)),
+            points: match text(cells, 6).trim() {
+                "" => None,
+                _ => Some(parse(cells, 6, "points")?)
+            },
+            kind: parse(cells, 7, "kind")?,
+            status: match text(cells, 11).trim() {
+                "" => ItemStatus::ToDo,
+                _ => parse(cells, 11, "status")?
+            },
+            assigned_at: parse(cells, 8, "assignedAt")?,
+            created_at: parse(cells, 9, "createdAt")?,
+            updated_at: parse(cells, 10, "updatedAt")?,
+            assignee: None,
+
+            row
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.project_id.clone(),
+            self.epic_id.clone(),
+            self.title.clone(),
+            self.priority.clone(),
+            self.description.clone().unwrap_or_default(),
+            self.points.map(|points| points.to_string()).unwrap_or_default(),
+            format!("{:?}", self.kind),
+            self.assigned_at.to_string(),
+            self.created_at.to_string(),
+            self.updated_at.to_string(),
+            format!("{:?}", self.status),
+        ]
+    }
+}
+
+impl ProjectScoped for InsertProductBacklogItem {
+    fn project_id(&self) -> Option<&str> {
+        Some(&self.project_id)
+    }
+}
+
+impl ProjectScoped for ProductBacklogQuery {
+    fn project_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
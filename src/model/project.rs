@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::util::util::Status;
+use crate::{interface::{crudy::ProjectScoped, sheet_entity::SheetEntity}, util::{error::AppError, row::{parse, text}, util::Status}};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -40,4 +41,40 @@ pub struct Project {
 #[serde(rename_all = "camelCase")]
 pub struct Denomination {
     pub denomination: String
-}
\ No newline at end of file
+}
+
+impl SheetEntity for Project {
+    const COLUMNS: (&'static str, &'static str) = ("A", "G");
+    const FIELDS: &'static [&'static str] = &["id", "denomination", "description", "owner", "status", "createdAt", "updatedAt"];
+    const KEYS: &'static [usize] = &[0];
+    const ID_PREFIX: &'static str = "P";
+    const KIND: &'static str = "project";
+
+    fn from_row(cells: &[Value], row: String) -> Result<Self, AppError> {
+        Ok(Project {
+            id: text(cells, 0),
+            denomination: text(cells, 1),
+            description: text(cells, 2),
+            owner: text(cells, 3),
+            status: parse(cells, 4, "status")?,
+            created_at: parse(cells, 5, "createdAt")?,
+            updated_at: parse(cells, 6, "updatedAt")?,
+
+            row
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.denomination.clone(),
+            self.description.clone(),
+            self.owner.clone(),
+            format!("{:?}", self.status),
+            self.created_at.to_string(),
+            self.updated_at.to_string(),
+        ]
+    }
+}
+
+impl ProjectScoped for InsertProjectData {}
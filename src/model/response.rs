@@ -1,11 +1,41 @@
 use serde::{Deserialize, Serialize};
 
 ///
-/// It is a custom response for fuctions that returns a HttpResponse
+/// It is a custom response for fuctions that returns a HttpResponse,
+/// every endpoint answers with it (including the errors).
+///
+/// Response attributes:
+///
+/// - `content`: Entities read, or the one created/updated.
+/// - `error_bug`: Details of the error.
+/// - `code`: Machine readable kind of error, e.g: `VALIDATION`, `NOT_FOUND`.
+/// - `fields`: Invalid fields of the request.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Response<T> {
     pub message: &'static str,
     pub content: Option<Vec<T>>,
-    pub error_bug: Option<Vec<String>>
-}
\ No newline at end of file
+    pub error_bug: Option<Vec<String>>,
+    pub code: Option<&'static str>,
+    pub fields: Option<Vec<FieldError>>
+}
+
+///
+/// It reports why a field of the request is not valid.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String
+}
+
+impl<T> Response<T> {
+    pub fn ok(message: &'static str, content: Option<Vec<T>>) -> Self {
+        Response {
+            message,
+            content,
+            error_bug: None,
+            code: None,
+            fields: None
+        }
+    }
+}
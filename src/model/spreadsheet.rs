@@ -53,3 +53,7 @@ pub struct SpecificPatch {
 pub struct SheetName {
     pub sheet_name: String
 }
+
+///
+/// Changes of an entity as a JSON Merge Patch (RFC 7396), e.g: `{ "title": "Login", "userStory": { "role": "tester" } }`.
+pub type FieldChanges = serde_json::Map<String, serde_json::Value>;
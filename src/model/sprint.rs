@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::util::util::Status;
+use crate::{interface::{crudy::ProjectScoped, sheet_entity::SheetEntity}, util::{error::AppError, row::{parse, text}, util::Status}};
 
 ///
 /// Sprint attributes:
@@ -10,10 +11,10 @@ use crate::util::util::Status;
 /// - `epic_id`: _
 /// - `denomination`: The name or title of the sprint.
 /// - `description`: _
-/// - `backlog_items`: Only stores the ids.
 /// - `goal`: The target of the sprint.
 /// - `owner`: Who is the leader.
-/// - `estimated_points`: The total of points (story points or points in general like a *task*).
+/// - `estimated_points`: The total of points (story points or points in general like a *task*),
+///   the sum of the points of its backlog items once they are added (see `SprintItem`).
 /// - `status`: Whether the sprint is **Pending**, **InProgress**, **Done**, **Standby**.
 /// - `completed_at`: When the sprint has culminated.
 /// - `start_date`: The initial date in unix epoch format.
@@ -28,7 +29,6 @@ pub struct Sprint {
     pub epic_id: String,
     pub denomination: String,
     pub description: Option<String>,
-    //pub backlog_items: Vec<String>,
     pub goal: String,
     pub owner: String,
     pub estimated_points: i64,
@@ -57,4 +57,61 @@ pub struct InsertSprint {
     pub start_date: i64,
     pub end_date: i64,
     pub created_at: i64
-}
\ No newline at end of file
+}
+
+impl SheetEntity for Sprint {
+    const COLUMNS: (&'static str, &'static str) = ("AR", "BE");
+    const FIELDS: &'static [&'static str] = &[
+        "id", "projectId", "epicId", "denomination", "description", "goal", "owner", "estimatedPoints", "status",
+        "completedAt", "startDate", "endDate", "createdAt", "updatedAt"
+    ];
+    const KEYS: &'static [usize] = &[1, 0];
+    const ID_PREFIX: &'static str = "S";
+    const KIND: &'static str = "sprint";
+
+    fn from_row(cells: &[Value], row: String) -> Result<Self, AppError> {
+        Ok(Sprint {
+            id: text(cells, 0),
+            project_id: text(cells, 1),
+            epic_id: text(cells, 2),
+            denomination: text(cells, 3),
+            description: Some(text(cells, 4)),
+            goal: text(cells, 5),
+            owner: text(cells, 6),
+            estimated_points: parse(cells, 7, "estimatedPoints")?,
+            status: parse(cells, 8, "status")?,
+            completed_at: parse(cells, 9, "completedAt")?,
+            start_date: parse(cells, 10, "startDate")?,
+            end_date: parse(cells, 11, "endDate")?,
+            created_at: parse(cells, 12, "createdAt")?,
+            updated_at: parse(cells, 13, "updatedAt")?,
+
+            row
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.project_id.clone(),
+            self.epic_id.clone(),
+            self.denomination.clone(),
+            self.description.clone().unwrap_or_default(),
+            self.goal.clone(),
+            self.owner.clone(),
+            self.estimated_points.to_string(),
+            format!("{:?}", self.status),
+            self.completed_at.to_string(),
+            self.start_date.to_string(),
+            self.end_date.to_string(),
+            self.created_at.to_string(),
+            self.updated_at.to_string(),
+        ]
+    }
+}
+
+impl ProjectScoped for InsertSprint {
+    fn project_id(&self) -> Option<&str> {
+        Some(&self.project_id)
+    }
+}
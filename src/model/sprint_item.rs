@@ -0,0 +1,163 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{interface::relation::Relation, util::{error::AppError, row::{parse, text}}};
+
+///
+/// A backlog item committed to a sprint, stored in the `SprintItems` sheet (columns `A:F`, one row per item).
+///
+/// SprintItem attributes:
+///
+/// - `project_id`: _
+/// - `sprint_id`: _
+/// - `item_id`: Id of the `ProductBacklogItem`.
+/// - `position`: Order of the item inside the sprint.
+/// - `added_at`: When the item was added to the sprint in unix epoch.
+/// - `completed_at`: When the item was completed in unix epoch, `None` while it is open.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintItem {
+    pub project_id: String,
+    pub sprint_id: String,
+    pub item_id: String,
+    pub position: i64,
+    pub added_at: i64,
+    pub completed_at: Option<i64>
+}
+
+impl Relation for SprintItem {
+    const COLUMNS: (&'static str, &'static str) = ("A", "F");
+    const FIELDS: &'static [&'static str] = &["projectId", "sprintId", "itemId", "position", "addedAt", "completedAt"];
+    const KEYS: &'static [usize] = &[0, 1, 2];
+    const KIND: &'static str = "sprint_item";
+
+    fn from_row(cells: &[Value]) -> Result<Self, AppError> {
+        Ok(SprintItem {
+            project_id: text(cells, 0),
+            sprint_id: text(cells, 1),
+            item_id: text(cells, 2),
+            position: parse(cells, 3, "position")?,
+            added_at: parse(cells, 4, "addedAt")?,
+            completed_at: match text(cells, 5).trim() {
+                "" => None,
+                _ => Some(parse(cells, 5, "completedAt")?)
+            }
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.project_id.clone(),
+            self.sprint_id.clone(),
+            self.item_id.clone(),
+            self.position.to_string(),
+            self.added_at.to_string(),
+            self.completed_at.map(|completed_at| completed_at.to_string()).unwrap_or_default(),
+        ]
+    }
+}
+
+///
+/// What a `ScopeEvent` records.
+///
+/// - `Scope`: The points of the sprint changed, the item was added, removed or estimated again.
+/// - `Completion`: The item was completed with `points`, the burndown keeps them even if it is estimated again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScopeKind {
+    Scope,
+    Completion
+}
+
+impl FromStr for ScopeKind {
+   type Err = ();
+
+   fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "Scope" => Ok(ScopeKind::Scope),
+            "Completion" => Ok(ScopeKind::Completion),
+            _ => Err(())
+        }
+   }
+}
+
+///
+/// A change of the points of a sprint, stored in the `ScopeChanges` sheet (columns `A:F`, one row per change).
+///
+/// ScopeEvent attributes:
+///
+/// - `project_id`: _
+/// - `sprint_id`: _
+/// - `item_id`: Id of the `ProductBacklogItem` added, removed, estimated again or completed.
+/// - `points`: Points gained by the sprint, negative when the item was removed or its points were lowered.
+///   The points of the item when it was completed for a `Completion`.
+/// - `date`: When the sprint changed in unix epoch.
+/// - `kind`: What changed, `Scope` for the rows written before the completions were recorded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeEvent {
+    pub project_id: String,
+    pub sprint_id: String,
+    pub item_id: String,
+    pub points: i64,
+    pub date: i64,
+    pub kind: ScopeKind
+}
+
+impl Relation for ScopeEvent {
+    const COLUMNS: (&'static str, &'static str) = ("A", "F");
+    const FIELDS: &'static [&'static str] = &["projectId", "sprintId", "itemId", "points", "date", "kind"];
+    const KEYS: &'static [usize] = &[0, 1, 2];
+    const KIND: &'static str = "scope_event";
+
+    fn from_row(cells: &[Value]) -> Result<Self, AppError> {
+        Ok(ScopeEvent {
+            project_id: text(cells, 0),
+            sprint_id: text(cells, 1),
+            item_id: text(cells, 2),
+            points: parse(cells, 3, "points")?,
+            date: parse(cells, 4, "date")?,
+            kind: match text(cells, 5).trim() {
+                "" => ScopeKind::Scope,
+                _ => parse(cells, 5, "kind")?
+            }
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.project_id.clone(),
+            self.sprint_id.clone(),
+            self.item_id.clone(),
+            self.points.to_string(),
+            self.date.to_string(),
+            format!("{:?}", self.kind),
+        ]
+    }
+}
+
+///
+/// Body of `POST /projects/{id}/sprints/{sprint_id}/items`, the item is added at the end.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddSprintItem {
+    pub item_id: String
+}
+
+///
+/// Body of `PUT /projects/{id}/sprints/{sprint_id}/items`, every item of the sprint in the new order.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintItemOrder {
+    pub item_ids: Vec<String>
+}
+
+///
+/// Body of `PATCH /projects/{id}/sprints/{sprint_id}/items/{item_id}`,
+/// `null` opens the item again.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintItemCompletion {
+    pub completed_at: Option<i64>
+}
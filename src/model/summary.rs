@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+///
+/// Progress of a project, or of one of its epics when `epic_id` is given.
+///
+/// Summary attributes:
+///
+/// - `project_id`: _
+/// - `epic_id`: _
+/// - `user_stories`: Rollup of the stories (their `storyPoints`).
+/// - `backlog_items`: Rollup of the backlog items (their `points`).
+/// - `backlog_items_by_kind`: Backlog items per kind (`Story`, `Bug`, `Task`).
+/// - `open_bugs`: Backlog items of kind `Bug` that are not `Done`.
+/// - `sprints_in_progress`: Ids of the sprints `InProgress`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub project_id: String,
+    pub epic_id: Option<String>,
+    pub user_stories: Rollup,
+    pub backlog_items: Rollup,
+    pub backlog_items_by_kind: BTreeMap<String, Tally>,
+    pub open_bugs: usize,
+    pub sprints_in_progress: Vec<String>
+}
+
+///
+/// Rollup attributes:
+///
+/// - `count` and `points`: Every story or item.
+/// - `completed_points`: Points of the ones `Done`.
+/// - `percent_complete`: Completed points over the points, the count is used when there are no points.
+/// - `by_status`: Every status, with zero when there is none.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rollup {
+    pub count: usize,
+    pub points: i64,
+    pub completed_points: i64,
+    pub percent_complete: f64,
+    pub by_status: BTreeMap<String, Tally>
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Tally {
+    pub count: usize,
+    pub points: i64
+}
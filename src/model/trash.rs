@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    interface::{relation::Relation, sheet_entity::SheetEntity},
+    model::member::TeamMember,
+    util::{error::AppError, row::{cells, parse, text}},
+};
+
+///
+/// An entity removed, stored in the `Trash` sheet (columns `A:G`) until it is restored or purged.
+/// Its cells are cleared from the sheet of the entities, so the reads do not see it.
+///
+/// TrashItem attributes:
+///
+/// - `id`: Id of the item in the trash, used to restore it.
+/// - `entity`: Kind of the entity, e.g: `epic`.
+/// - `entity_id`: Keys of the entity as they appear in its routes, e.g: `P1/E1`.
+/// - `row`: Row (one based) where the entity was stored, it goes back there when the row is still empty.
+/// - `deleted_at`: When the entity was removed in unix epoch.
+/// - `fields`: The cells of the entity by field, e.g: `{ "id": "E1", "title": "Login", ... }`.
+/// - `relations`: What referred to the entity and was removed with it, it is restored with the entity (see `Relations`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    pub id: String,
+    pub entity: String,
+    pub entity_id: String,
+    pub row: usize,
+    pub deleted_at: i64,
+    pub fields: Map<String, Value>,
+    pub relations: Relations
+}
+
+///
+/// Relations removed with an entity by kind, as the rows of their sheet (see `Relation`),
+/// e.g: `{ "sprint_item": [["P1", "S1", "PB1", "1", "1700000000", ""]] }`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Relations(Map<String, Value>);
+
+impl Relations {
+    pub fn keep<R: Relation>(&mut self, removed: &[R]) {
+        let rows = self.0.entry(R::KIND).or_insert(Value::Array(vec![]));
+
+        if let Value::Array(rows) = rows {
+            rows.extend(removed.iter().map(|relation| Value::from(relation.to_row())));
+        }
+    }
+
+    pub fn kept<R: Relation>(&self) -> Result<Vec<R>, AppError> {
+        let rows = self.0.get(R::KIND).and_then(Value::as_array).cloned().unwrap_or_default();
+
+        rows.iter()
+            .map(|row| {
+                let values = row.as_array().into_iter().flatten().map(|cell| cell.as_str().unwrap_or_default().to_string()).collect::<Vec<String>>();
+
+                R::from_row(&cells(&values))
+            })
+            .collect()
+    }
+}
+
+impl TrashItem {
+    pub const COLUMNS: (&'static str, &'static str) = ("A", "G");
+
+    pub fn from_row(cells: &[Value]) -> Result<Self, AppError> {
+        Ok(TrashItem {
+            id: text(cells, 0),
+            entity: text(cells, 1),
+            entity_id: text(cells, 2),
+            row: parse(cells, 3, "row")?,
+            deleted_at: parse(cells, 4, "deletedAt")?,
+            fields: serde_json::from_str(&text(cells, 5))
+                .map_err(|_| AppError::CorruptRow(format!("`fields` of the trash item {} is not a JSON object", text(cells, 0))))?,
+            // The items removed before the relations were kept have none
+            relations: match text(cells, 6).trim() {
+                "" => Relations::default(),
+                relations => serde_json::from_str(relations)
+                    .map_err(|_| AppError::CorruptRow(format!("`relations` of the trash item {} is not a JSON object", text(cells, 0))))?
+            }
+        })
+    }
+
+    pub fn to_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.entity.clone(),
+            self.entity_id.clone(),
+            self.row.to_string(),
+            self.deleted_at.to_string(),
+            Value::Object(self.fields.clone()).to_string(),
+            serde_json::to_string(&self.relations).unwrap_or_default(),
+        ]
+    }
+
+    ///
+    /// Project of the entity, `None` for the entities outside of the projects (the members).
+    pub fn project_id(&self) -> Option<&str> {
+        match self.entity.as_str() {
+            TeamMember::KIND => None,
+            _ => self.entity_id.split('/').next()
+        }
+    }
+}
+
+///
+/// Query of `GET /trash`, the items of the kind `entity` (e.g: `epic`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrashQuery {
+    pub entity: Option<String>
+}
@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{interface::{crudy::ProjectScoped, sheet_entity::SheetEntity}, util::{error::AppError, row::{parse, text}, util::{convert_pattern_to_vec, ItemStatus}}};
 
 /// It determines whether a scenario is success or not.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -22,6 +25,9 @@ pub struct Scenario {
     pub then: String,
 }
 
+///
+/// - `status`: Where the story is in the workflow, stored in the last column (`AE`).
+/// - `assignee`: Id of the member assigned in the `Assignments` sheet, it is not stored in the row.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserStoryBox {
@@ -33,8 +39,11 @@ pub struct UserStoryBox {
     pub story_points: i32,
     pub user_story: UserStory,
     pub acceptance_criteria: (Vec<Scenario>, DetailsList),
+    pub status: ItemStatus,
     pub created_at: i64,
     pub updated_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
 
     pub row: String
 }
@@ -48,9 +57,21 @@ pub struct InsertUserStoryBox {
     pub story_points: i32,
     pub user_story: UserStory,
     pub acceptance_criteria: (Vec<Scenario>, DetailsList),
+    #[serde(default)]
+    pub status: ItemStatus,
     pub created_at: i64,
 }
 
+///
+/// Query of `GET /uss`, the stories of the epic (only the ones in `status` when it is given).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStoryQuery {
+    pub project_id: String,
+    pub epic_id: String,
+    pub status: Option<ItemStatus>
+}
+
 ///
 /// User Story attributes:
 ///
@@ -85,3 +106,143 @@ impl FromStr for ScenarioType {
         }
    }
 }
+
+///
+/// It stores the scenarios as `index:kind^given^when^then|...`.
+pub fn scenarios_to_pattern(scenarios: &[Scenario]) -> String {
+    scenarios
+        .iter()
+        .enumerate()
+        .map(|(index, scenario)| {
+            format!(
+                "{}:{:?}^{}^{}^{}",
+                index, scenario.kind, scenario.given, scenario.when, scenario.then
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("|")
+}
+
+///
+/// It stores the details as `index:detail|...`.
+pub fn details_to_pattern(details: &[String]) -> String {
+    details
+        .iter()
+        .enumerate()
+        .map(|(index, detail)| format!("{}:{}", index, detail))
+        .collect::<Vec<String>>()
+        .join("|")
+}
+
+impl Scenario {
+    ///
+    /// It parses a scenario stored as `index:kind^given^when^then`.
+    fn from_pattern(pattern: Vec<String>) -> Result<Self, AppError> {
+        let head = pattern.first().cloned().unwrap_or_default();
+
+        let kind = head
+            .split(":")
+            .nth(1)
+            .and_then(|kind| ScenarioType::from_str(kind).ok())
+            .ok_or(AppError::CorruptRow(format!("`scenarios` has an invalid kind: '{}'", head)))?;
+
+        Ok(Scenario {
+            kind,
+            given: pattern.get(1).cloned().unwrap_or_default(),
+            when: pattern.get(2).cloned().unwrap_or_default(),
+            then: pattern.get(3).cloned().unwrap_or_default()
+        })
+    }
+}
+
+impl SheetEntity for UserStoryBox {
+    const COLUMNS: (&'static str, &'static str) = ("R", "AE");
+    const FIELDS: &'static [&'static str] = &[
+        "id", "projectId", "epicId", "title", "priority", "storyPoints", "role", "functionality", "benefit",
+        "scenarios", "details", "createdAt", "updatedAt", "status"
+    ];
+    const KEYS: &'static [usize] = &[1, 2, 0];
+    const ID_PREFIX: &'static str = "US";
+    const KIND: &'static str = "user_story";
+
+    fn from_row(cells: &[Value], row: String) -> Result<Self, AppError> {
+        let scenarios = match text(cells, 9).trim() {
+            "" => vec![],
+            _ => convert_pattern_to_vec(vec![vec![cells[9].clone()]])
+                    .into_iter()
+                    .map(Scenario::from_pattern)
+                    .collect::<Result<Vec<Scenario>, AppError>>()?
+        };
+
+        // Details are stored as `index:detail|...`
+        let details: DetailsList = match text(cells, 10).trim() {
+            "" => vec![],
+            _ => convert_pattern_to_vec(vec![vec![cells[10].clone()]])
+                    .into_iter()
+                    .map(|pattern| {
+                        let detail = pattern.join("^");
+
+                        match detail.split_once(":") {
+                            Some((_, detail)) => detail.to_string(),
+                            None => detail
+                        }
+                    })
+                    .collect()
+        };
+
+        Ok(UserStoryBox {
+            id: text(cells, 0),
+            project_id: text(cells, 1),
+            epic_id: text(cells, 2),
+            title: text(cells, 3),
+            priority: text(cells, 4),
+            story_points: parse(cells, 5, "storyPoints")?,
+            user_story: UserStory {
+                role: text(cells, 6),
+                functionality: text(cells, 7),
+                benefit: text(cells, 8)
+            },
+            acceptance_criteria: (scenarios, details),
+            status: match text(cells, 13).trim() {
+                "" => ItemStatus::ToDo,
+                _ => parse(cells, 13, "status")?
+            },
+            created_at: parse(cells, 11, "createdAt")?,
+            updated_at: parse(cells, 12, "updatedAt")?,
+            assignee: None,
+
+            row
+        })
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.project_id.clone(),
+            self.epic_id.clone(),
+            self.title.clone(),
+            self.priority.clone(),
+            self.story_points.to_string(),
+            self.user_story.role.clone(),
+            self.user_story.functionality.clone(),
+            self.user_story.benefit.clone(),
+            scenarios_to_pattern(&self.acceptance_criteria.0),
+            details_to_pattern(&self.acceptance_criteria.1),
+            self.created_at.to_string(),
+            self.updated_at.to_string(),
+            format!("{:?}", self.status),
+        ]
+    }
+}
+
+impl ProjectScoped for InsertUserStoryBox {
+    fn project_id(&self) -> Option<&str> {
+        Some(&self.project_id)
+    }
+}
+
+impl ProjectScoped for UserStoryQuery {
+    fn project_id(&self) -> Option<&str> {
+        Some(&self.project_id)
+    }
+}
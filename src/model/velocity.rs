@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// Points burnt by the team sprint after sprint, and the sprints needed to burn the product backlog.
+///
+/// Velocity attributes:
+///
+/// - `project_id`: _
+/// - `window`: Done sprints averaged in `rolling_average` and `average`.
+/// - `sprints`: The done sprints in the order they were completed.
+/// - `average`: Completed points of the last `window` sprints on average.
+/// - `remaining_points`: Points of the backlog items not completed yet.
+/// - `forecast`: _
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Velocity {
+    pub project_id: String,
+    pub window: usize,
+    pub sprints: Vec<SprintVelocity>,
+    pub average: f64,
+    pub remaining_points: i64,
+    pub forecast: Forecast
+}
+
+///
+/// SprintVelocity attributes:
+///
+/// - `sprint_id`: _
+/// - `denomination`: The name or title of the sprint.
+/// - `completed_at`: When the sprint has culminated.
+/// - `committed`: `estimatedPoints` of the sprint.
+/// - `completed`: Points of the items completed in the sprint.
+/// - `rolling_average`: Completed points of this sprint and the previous ones in the window on average.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintVelocity {
+    pub sprint_id: String,
+    pub denomination: String,
+    pub completed_at: i64,
+    pub committed: i64,
+    pub completed: i64,
+    pub rolling_average: f64
+}
+
+///
+/// Sprints needed to burn the remaining points, `None` while there is no throughput to forecast from.
+///
+/// - `sprints`: At the `average` velocity.
+/// - `p50`, `p85`, `p95`: Percentiles of a Monte Carlo simulation that draws the completed points
+///   of past sprints at random, e.g: `p85` is enough in 85% of the runs.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Forecast {
+    pub sprints: Option<i64>,
+    pub p50: Option<i64>,
+    pub p85: Option<i64>,
+    pub p95: Option<i64>
+}
+
+///
+/// Query of `GET /projects/{id}/velocity`.
+///
+/// - `window`: Done sprints of the rolling average, 3 by default.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VelocityQuery {
+    pub window: Option<usize>
+}
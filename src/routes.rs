@@ -0,0 +1,131 @@
+use actix_web::{http::Method, middleware::{self, from_fn}, web, Route};
+
+use crate::{
+    interface::{crudy::{ProjectScoped, CRUD}, resource::Resource, sheet_entity::SheetEntity},
+    service::{
+        assignment::AssignmentService, audit::{audit, AuditService}, auth::AuthService, epic::EpicService,
+        history::HistoryService, member::MemberService, permission::{guard, PermissionService},
+        product_backlog::ProductBacklogService, project::ProjectService, sprint::SprintService,
+        sprint_chart::SprintChartService, sprint_item::SprintItemService, summary::SummaryService,
+        trash::TrashService, user_story::UserStoryService, velocity::VelocityService,
+    },
+    util::error::AppError,
+};
+
+///
+/// It registers the routes in the app and keeps the table of (method, path) registered,
+/// so the conflicts can be checked.
+pub struct Router<'a> {
+    cfg: &'a mut web::ServiceConfig,
+    table: Vec<(Method, String)>
+}
+
+impl<'a> Router<'a> {
+    pub fn new(cfg: &'a mut web::ServiceConfig) -> Self {
+        Router { cfg, table: vec![] }
+    }
+
+    ///
+    /// `route`: Handler of the route, e.g: `web::route().to(handler)`, the method is added here.
+    /// Every route opens the change its writes are recorded in (see `audit`).
+    pub fn add(&mut self, method: Method, path: &str, route: Route) -> &mut Self {
+        self.table.push((method.clone(), path.to_string()));
+        self.cfg.route(path, route.method(method).wrap(from_fn(audit)));
+
+        self
+    }
+
+    pub fn table(&self) -> &[(Method, String)] {
+        &self.table
+    }
+}
+
+///
+/// (method, path) registered more than once, the last ones are unreachable.
+pub fn duplicates(table: &[(Method, String)]) -> Vec<(Method, String)> {
+    table
+        .iter()
+        .enumerate()
+        .filter(|(index, route)| table[..*index].contains(route))
+        .map(|(_, route)| route.clone())
+        .collect()
+}
+
+///
+/// Registers the `CRUD` of a service:
+/// `GET list_path` (read), `POST path` (create), `PATCH path` (update) and `DELETE path` (delete).
+///
+/// `update` and `delete` address the cells directly, they are deprecated in favor of `register_resource`.
+/// The routes are `guarded`.
+pub fn register_crud<S: CRUD<Entity = E> + 'static, E: SheetEntity>(router: &mut Router, list_path: &str, path: &str) {
+    let deprecated = || middleware::DefaultHeaders::new().add(("Deprecation", "true"));
+
+    router
+        .add(Method::GET, list_path, guarded_by::<E, S::ReadPayload>(web::route().to(S::read)))
+        .add(Method::POST, path, guarded_by::<E, S::CreatePayload>(web::route().to(S::create)))
+        .add(Method::PATCH, path, guarded::<E>(web::route().to(S::update)).wrap(deprecated()))
+        .add(Method::DELETE, path, guarded::<E>(web::route().to(S::delete)).wrap(deprecated()));
+}
+
+///
+/// Registers `GET`, `PATCH` and `DELETE` of an entity addressed by its id, the routes are `guarded`.
+pub fn register_resource<R: Resource>(router: &mut Router, path: &str) {
+    router
+        .add(Method::GET, path, guarded::<R::Entity>(web::route().to(R::get)))
+        .add(Method::PATCH, path, guarded::<R::Entity>(web::route().to(R::patch)))
+        .add(Method::DELETE, path, guarded::<R::Entity>(web::route().to(R::remove)));
+}
+
+///
+/// It wraps a route that reads or changes the entities of `E`, the roles of the caller are enforced (`guard`).
+pub fn guarded<E: SheetEntity>(route: Route) -> Route {
+    guarded_by::<E, ()>(route)
+}
+
+///
+/// The same as `guarded` for the routes without the project in their path,
+/// it is taken from the payload `P` (the query or the body the handler receives).
+pub fn guarded_by<E: SheetEntity, P: ProjectScoped + 'static>(route: Route) -> Route {
+    route.wrap(from_fn(guard::<E, P, _>))
+}
+
+///
+/// Registers the routes of every service, returning the table of routes.
+pub fn register(cfg: &mut web::ServiceConfig) -> Vec<(Method, String)> {
+    // Malformed bodies are reported with the same JSON as the rest of the errors
+    let json_config = web::JsonConfig::default()
+        .error_handler(|err, _| AppError::from(err).into());
+
+    cfg.app_data(json_config);
+
+    let mut router = Router::new(cfg);
+
+    ProjectService::configure(&mut router);
+    EpicService::configure(&mut router);
+    UserStoryService::configure(&mut router);
+    ProductBacklogService::configure(&mut router);
+    SprintService::configure(&mut router);
+    SprintItemService::configure(&mut router);
+    SprintChartService::configure(&mut router);
+    VelocityService::configure(&mut router);
+    SummaryService::configure(&mut router);
+    MemberService::configure(&mut router);
+    AssignmentService::configure(&mut router);
+    AuthService::configure(&mut router);
+    PermissionService::configure(&mut router);
+    AuditService::configure(&mut router);
+    HistoryService::configure(&mut router);
+    TrashService::configure(&mut router);
+
+    router.table().to_vec()
+}
+
+///
+/// Registers the routes of every service, a route registered twice is a programming error
+/// (the second one would never be reached), so the app does not start.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    let table = register(cfg);
+    let duplicates = duplicates(&table);
+
+    assert!(duplicates.is_empty(), "Routes registered more than once: {:?}", duplicates);
+}
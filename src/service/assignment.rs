@@ -0,0 +1,329 @@
+use std::collections::{BTreeSet, HashMap};
+
+use actix_web::{http::Method, web};
+
+use crate::{
+    config::app_state::AppState,
+    interface::{crudy::ApiResult, relation::Relation, resource::PathKeys, sheet_entity::SheetEntity, store::StoreResult},
+    model::{
+        assignment::{AssignMember, Assignment, Work},
+        auth::Identity,
+        member::TeamMember,
+        product_backlog::ProductBacklogItem,
+        response::{FieldError, Response},
+        user_story::UserStoryBox,
+    },
+    routes::{guarded, Router},
+    util::{
+        error::AppError,
+        row::is_empty,
+        util::unix_now,
+    },
+};
+
+use super::{
+    audit::AuditService,
+    member::MemberService,
+    permission::PermissionService,
+    spreadsheet::{entities_in, find_row},
+};
+
+/// The stories and backlog items are read at once.
+const WORK_RANGE: (&str, &str) = ("R", "AQ");
+
+///
+/// Members assigned to the stories and backlog items, the relation is kept in the `Assignments` sheet
+/// (one member per entity).
+pub struct AssignmentService;
+
+impl AssignmentService {
+    pub fn configure(router: &mut Router) {
+        let item_path = "/projects/{id}/backlog/{item_id}/assignee";
+        let story_path = "/projects/{id}/epics/{epic_id}/stories/{us_id}/assignee";
+
+        router
+            .add(Method::PUT, item_path, guarded::<ProductBacklogItem>(web::route().to(Self::assign_item)))
+            .add(Method::DELETE, item_path, guarded::<ProductBacklogItem>(web::route().to(Self::unassign_item)))
+            .add(Method::PUT, story_path, guarded::<UserStoryBox>(web::route().to(Self::assign::<UserStoryBox, (String, String, String)>)))
+            .add(Method::DELETE, story_path, guarded::<UserStoryBox>(web::route().to(Self::unassign::<UserStoryBox, (String, String, String)>)))
+            .add(Method::GET, "/members/{id}/work", web::route().to(Self::work));
+    }
+
+    ///
+    /// It assigns the member to the backlog item and sets its `assignedAt`.
+    pub async fn assign_item(state: web::Data<AppState>, path: web::Path<(String, String)>, data: web::Json<AssignMember>) -> ApiResult<Assignment> {
+        let keys = path.into_inner().keys();
+
+        let (row, cells) = find_row::<ProductBacklogItem>(state.store.as_ref(), state.sheet(), &keys).await?;
+
+        let assignment = Self::assign_to::<ProductBacklogItem>(&state, keys, data.into_inner()).await?;
+
+        let current = ProductBacklogItem::from_row(&cells, ProductBacklogItem::row_label(row))?;
+        let mut item = ProductBacklogItem::from_row(&cells, ProductBacklogItem::row_label(row))?;
+        item.assigned_at = assignment.assigned_at;
+        item.updated_at = assignment.assigned_at;
+
+        let (first_col, last_col) = ProductBacklogItem::COLUMNS;
+        let full_range = format!("{}!{}{}:{}{}", state.sheet(), first_col, row, last_col, row);
+
+        state.store.update(full_range.as_str(), item.to_row()).await?;
+
+        AuditService::entity(&state, Some(&current), Some(&item)).await?;
+
+        Ok(web::Json(Response::ok("The member was assigned successfully!", Some(vec![assignment]))))
+    }
+
+    pub async fn assign<E: SheetEntity, P: PathKeys>(state: web::Data<AppState>, path: web::Path<P>, data: web::Json<AssignMember>) -> ApiResult<Assignment> {
+        let keys = path.into_inner().keys();
+
+        find_row::<E>(state.store.as_ref(), state.sheet(), &keys).await?;
+
+        let assignment = Self::assign_to::<E>(&state, keys, data.into_inner()).await?;
+
+        Ok(web::Json(Response::ok("The member was assigned successfully!", Some(vec![assignment]))))
+    }
+
+    ///
+    /// It unassigns the member from the backlog item and resets its `assignedAt`.
+    pub async fn unassign_item(state: web::Data<AppState>, path: web::Path<(String, String)>) -> ApiResult<Assignment> {
+        let keys = path.into_inner().keys();
+
+        let (row, cells) = find_row::<ProductBacklogItem>(state.store.as_ref(), state.sheet(), &keys).await?;
+
+        let removed = Self::unassign_from::<ProductBacklogItem>(&state, &keys).await?;
+
+        let current = ProductBacklogItem::from_row(&cells, ProductBacklogItem::row_label(row))?;
+        let mut item = ProductBacklogItem::from_row(&cells, ProductBacklogItem::row_label(row))?;
+        item.assigned_at = 0;
+        item.updated_at = unix_now();
+
+        let (first_col, last_col) = ProductBacklogItem::COLUMNS;
+        let full_range = format!("{}!{}{}:{}{}", state.sheet(), first_col, row, last_col, row);
+
+        state.store.update(full_range.as_str(), item.to_row()).await?;
+
+        AuditService::entity(&state, Some(&current), Some(&item)).await?;
+
+        Ok(web::Json(Response::ok("The member was unassigned successfully!", Some(removed))))
+    }
+
+    pub async fn unassign<E: SheetEntity, P: PathKeys>(state: web::Data<AppState>, path: web::Path<P>) -> ApiResult<Assignment> {
+        let keys = path.into_inner().keys();
+
+        find_row::<E>(state.store.as_ref(), state.sheet(), &keys).await?;
+
+        let removed = Self::unassign_from::<E>(&state, &keys).await?;
+
+        Ok(web::Json(Response::ok("The member was unassigned successfully!", Some(removed))))
+    }
+
+    ///
+    /// The stories and backlog items assigned to the member across the projects, the last assigned first.
+    /// Only the projects where the caller has a role are listed.
+    pub async fn work(state: web::Data<AppState>, path: web::Path<String>, identity: Option<web::ReqData<Identity>>) -> ApiResult<Work> {
+        let member_id = path.into_inner();
+
+        find_row::<TeamMember>(state.store.as_ref(), state.sheet(), std::slice::from_ref(&member_id)).await?;
+
+        let mut assignments = Self::of_member(&state, &member_id).await?;
+
+        if let Some(identity) = identity {
+            let projects = PermissionService::projects_of(&state, &identity.subject).await?;
+
+            assignments.retain(|assignment| projects.contains(&assignment.project_id));
+        }
+
+        let (first_col, last_col) = WORK_RANGE;
+        let values = state.store.read(format!("{}!{}:{}", state.sheet(), first_col, last_col).as_str()).await?;
+
+        let mut work = vec![];
+
+        for project_id in assignments.iter().map(|assignment| assignment.project_id.clone()).collect::<BTreeSet<String>>() {
+            let assigned = |kind: &str, id: &str| assignments.iter().find(|assignment| {
+                assignment.project_id == project_id && assignment.kind == kind && assignment.entity_id == id
+            }).map(|assignment| assignment.assigned_at);
+
+            for story in entities_in::<UserStoryBox>(&values, first_col, &project_id)? {
+                let Some(assigned_at) = assigned(UserStoryBox::KIND, &story.id) else { continue };
+
+                work.push(Work {
+                    kind: UserStoryBox::KIND.to_string(),
+                    project_id: story.project_id,
+                    epic_id: story.epic_id,
+                    id: story.id,
+                    title: story.title,
+                    status: story.status,
+                    points: Some(story.story_points as i64),
+                    assigned_at
+                });
+            }
+
+            for item in entities_in::<ProductBacklogItem>(&values, first_col, &project_id)? {
+                let Some(assigned_at) = assigned(ProductBacklogItem::KIND, &item.id) else { continue };
+
+                work.push(Work {
+                    kind: ProductBacklogItem::KIND.to_string(),
+                    project_id: item.project_id,
+                    epic_id: item.epic_id,
+                    id: item.id,
+                    title: item.title,
+                    status: item.status,
+                    points: item.points,
+                    assigned_at
+                });
+            }
+        }
+
+        work.sort_by_key(|work| std::cmp::Reverse(work.assigned_at));
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(work))))
+    }
+
+    ///
+    /// It replaces the member assigned to the entity.
+    ///
+    /// `keys`: Keys of the entity, the project id first and its id last.
+    async fn assign_to<E: SheetEntity>(state: &AppState, keys: Vec<String>, data: AssignMember) -> StoreResult<Assignment> {
+        let AssignMember { member_id } = data;
+
+        if member_id.trim().is_empty() {
+            return Err(AppError::InvalidFields(vec![FieldError {
+                field: "memberId".to_string(),
+                message: "It can not be empty".to_string()
+            }]))
+        }
+
+        MemberService::check_member(state, "memberId", &member_id).await?;
+
+        let assignment = Assignment {
+            project_id: keys[0].clone(),
+            kind: E::KIND.to_string(),
+            entity_id: keys[keys.len() - 1].clone(),
+            member_id,
+            assigned_at: unix_now()
+        };
+
+        Self::unlink(state, |other| {
+            other.project_id == assignment.project_id && other.kind == assignment.kind && other.entity_id == assignment.entity_id
+        }).await?;
+
+        let sheet = &state.settings.sheets.assignments;
+        let (first_col, last_col) = Assignment::COLUMNS;
+
+        state.store.append(format!("{}!{}1:{}1", sheet, first_col, last_col).as_str(), assignment.to_row()).await?;
+
+        AuditService::relation(state, None, Some(&assignment)).await?;
+
+        Ok(assignment)
+    }
+
+    ///
+    /// It removes the member assigned to the entity, `NotFound` when it has no assignee.
+    ///
+    /// `keys`: Keys of the entity, the project id first and its id last.
+    async fn unassign_from<E: SheetEntity>(state: &AppState, keys: &[String]) -> StoreResult<Vec<Assignment>> {
+        let (project_id, entity_id) = (keys[0].as_str(), keys[keys.len() - 1].as_str());
+
+        let removed = Self::unlink(state, |assignment| {
+            assignment.project_id == project_id && assignment.kind == E::KIND && assignment.entity_id == entity_id
+        }).await?;
+
+        if removed.is_empty() {
+            return Err(AppError::NotFound(format!("There is no member assigned to {}", keys.join("/"))))
+        }
+
+        Ok(removed)
+    }
+
+    ///
+    /// Every assignment with its row (one based).
+    async fn assignments(state: &AppState) -> StoreResult<Vec<(usize, Assignment)>> {
+        let sheet = &state.settings.sheets.assignments;
+        let (first_col, last_col) = Assignment::COLUMNS;
+
+        state.ensure_sheet(sheet).await?;
+
+        let values = state.store.read(format!("{}!{}:{}", sheet, first_col, last_col).as_str()).await?;
+
+        values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row))
+            .map(|(index, row)| Assignment::from_row(&row).map(|assignment| (index + 1, assignment)))
+            .collect()
+    }
+
+    ///
+    /// Member assigned to the entity of the project, `None` when it has no assignee.
+    pub async fn assignee(state: &AppState, project_id: &str, kind: &str, entity_id: &str) -> StoreResult<Option<String>> {
+        let assignments = Self::assignments(state).await?;
+
+        Ok(assignments
+            .into_iter()
+            .find(|(_, assignment)| assignment.project_id == project_id && assignment.kind == kind && assignment.entity_id == entity_id)
+            .map(|(_, assignment)| assignment.member_id))
+    }
+
+    ///
+    /// Members assigned to the entities of the kind in the project, by the id of the entity.
+    pub async fn assignees(state: &AppState, project_id: &str, kind: &str) -> StoreResult<HashMap<String, String>> {
+        let assignments = Self::assignments(state).await?;
+
+        Ok(assignments
+            .into_iter()
+            .map(|(_, assignment)| assignment)
+            .filter(|assignment| assignment.project_id == project_id && assignment.kind == kind)
+            .map(|assignment| (assignment.entity_id, assignment.member_id))
+            .collect())
+    }
+
+    pub async fn of_member(state: &AppState, member_id: &str) -> StoreResult<Vec<Assignment>> {
+        let assignments = Self::assignments(state).await?;
+
+        Ok(assignments.into_iter().map(|(_, assignment)| assignment).filter(|assignment| assignment.member_id == member_id).collect())
+    }
+
+    ///
+    /// It puts back the assignments removed with their entity (see `unlink`),
+    /// the ones of the members removed since then are dropped.
+    pub async fn relink(state: &AppState, assignments: Vec<Assignment>) -> StoreResult<()> {
+        let sheet = &state.settings.sheets.assignments;
+        let (first_col, last_col) = Assignment::COLUMNS;
+
+        for assignment in assignments {
+            match find_row::<TeamMember>(state.store.as_ref(), state.sheet(), std::slice::from_ref(&assignment.member_id)).await {
+                Ok(_) => {},
+                Err(AppError::NotFound(_)) => continue,
+                Err(err) => return Err(err)
+            }
+
+            state.store.append(format!("{}!{}1:{}1", sheet, first_col, last_col).as_str(), assignment.to_row()).await?;
+
+            AuditService::relation(state, None, Some(&assignment)).await?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// It removes the assignments that match, returning them.
+    pub async fn unlink(state: &AppState, matches: impl Fn(&Assignment) -> bool) -> StoreResult<Vec<Assignment>> {
+        let sheet = &state.settings.sheets.assignments;
+        let (first_col, last_col) = Assignment::COLUMNS;
+
+        let mut removed = vec![];
+
+        for (row, assignment) in Self::assignments(state).await? {
+            if !matches(&assignment) { continue }
+
+            let full_range = format!("{}!{}{}:{}{}", sheet, first_col, row, last_col, row);
+            state.store.clear(full_range.as_str()).await?;
+
+            AuditService::relation(state, Some(&assignment), None).await?;
+
+            removed.push(assignment);
+        }
+
+        Ok(removed)
+    }
+}
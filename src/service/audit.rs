@@ -0,0 +1,180 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::Method,
+    middleware::Next,
+    web, Error, HttpMessage,
+};
+
+use crate::{
+    config::app_state::AppState,
+    interface::{crudy::ApiResult, relation::Relation, sheet_entity::SheetEntity, store::StoreResult},
+    model::{
+        audit::{AuditQuery, AuditRecord},
+        auth::Identity,
+        response::Response,
+    },
+    routes::Router,
+    util::{row::is_empty, util::unix_now},
+};
+
+use super::permission::PermissionService;
+
+/// Actor of the changes made while the API is open.
+pub const ANONYMOUS: &str = "anonymous";
+
+///
+/// Trace of the changes made to the entities and their relations, kept in the `Audit` sheet (see `audit`).
+pub struct AuditService;
+
+impl AuditService {
+    pub fn configure(router: &mut Router) {
+        router.add(Method::GET, "/audit", web::route().to(Self::list));
+    }
+
+    ///
+    /// The records in the order they were made, only the ones of `entity` made from `since` when they are given.
+    /// An identified caller only sees the records of the projects where it has a role.
+    pub async fn list(state: web::Data<AppState>, param: Option<web::Query<AuditQuery>>, identity: Option<web::ReqData<Identity>>) -> ApiResult<AuditRecord> {
+        let AuditQuery { entity, since } = param.map(|param| param.into_inner()).unwrap_or(AuditQuery { entity: None, since: None });
+
+        let projects = match identity.as_deref() {
+            Some(identity) => Some(PermissionService::projects_of(&state, &identity.subject).await?),
+            None => None
+        };
+
+        let records = Self::records(&state)
+            .await?
+            .into_iter()
+            .filter(|record| entity.as_ref().is_none_or(|entity| record.entity == *entity))
+            .filter(|record| since.is_none_or(|since| record.timestamp >= since))
+            .filter(|record| match (projects.as_ref(), record.project_id()) {
+                (Some(projects), Some(project_id)) => projects.iter().any(|project| project == project_id),
+                _ => true
+            })
+            .collect::<Vec<AuditRecord>>();
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(records))))
+    }
+
+    pub async fn records(state: &AppState) -> StoreResult<Vec<AuditRecord>> {
+        let sheet = &state.settings.sheets.audit;
+        let (first_col, last_col) = AuditRecord::COLUMNS;
+
+        state.ensure_sheet(sheet).await?;
+
+        let values = state.store.read(format!("{}!{}:{}", sheet, first_col, last_col).as_str()).await?;
+
+        values.iter().filter(|row| !is_empty(row)).map(|row| AuditRecord::from_row(row)).collect()
+    }
+
+    ///
+    /// It records the fields of the entity that differ, as part of the change of the request being handled.
+    /// `before` is `None` when the entity is created and `after` when it is removed.
+    pub async fn entity<E: SheetEntity>(state: &AppState, before: Option<&E>, after: Option<&E>) -> StoreResult<()> {
+        let row = |entity: Option<&E>| entity.map(E::to_row).unwrap_or_default();
+
+        Self::trace(state, (E::KIND, E::FIELDS, E::KEYS), &row(before), &row(after)).await
+    }
+
+    ///
+    /// The same as `entity` for the relations kept in their own sheet, e.g: the items of the sprints.
+    pub async fn relation<R: Relation>(state: &AppState, before: Option<&R>, after: Option<&R>) -> StoreResult<()> {
+        let row = |relation: Option<&R>| relation.map(R::to_row).unwrap_or_default();
+
+        Self::trace(state, (R::KIND, R::FIELDS, R::KEYS), &row(before), &row(after)).await
+    }
+
+    ///
+    /// It records the cells of a row that differ, empty when the row is created or cleared.
+    /// A change that can not be recorded fails the request, so it is not made silently.
+    ///
+    /// `kind`: Kind, fields and keys of what the row stores (see `SheetEntity`).
+    pub async fn trace(state: &AppState, kind: (&str, &[&str], &[usize]), before: &[String], after: &[String]) -> StoreResult<()> {
+        let (kind, fields, keys) = kind;
+        let Change { id, actor, timestamp } = Change::current();
+
+        let cell = |row: &[String], col: usize| row.get(col).cloned().unwrap_or_default();
+
+        let keyed = match after.iter().all(|value| value.trim().is_empty()) {
+            true => before,
+            false => after
+        };
+
+        let entity_id = keys.iter().map(|key| cell(keyed, *key)).collect::<Vec<String>>().join("/");
+
+        let records = fields
+            .iter()
+            .enumerate()
+            .filter(|(col, _)| cell(before, *col) != cell(after, *col))
+            .map(|(col, field)| AuditRecord {
+                change: id.clone(),
+                timestamp,
+                actor: actor.clone(),
+                entity: kind.to_string(),
+                entity_id: entity_id.clone(),
+                field: field.to_string(),
+                old_value: cell(before, col),
+                new_value: cell(after, col)
+            })
+            .collect::<Vec<AuditRecord>>();
+
+        Self::append(state, &records).await
+    }
+
+    ///
+    /// It appends the records at the end of the `Audit` sheet, in a single write.
+    pub async fn append(state: &AppState, records: &[AuditRecord]) -> StoreResult<()> {
+        let sheet = &state.settings.sheets.audit;
+        let (first_col, last_col) = AuditRecord::COLUMNS;
+
+        if records.is_empty() { return Ok(()) }
+
+        state.ensure_sheet(sheet).await?;
+
+        let rows = records.iter().map(AuditRecord::to_row).collect();
+
+        state.store.append_rows(format!("{}!{}1:{}1", sheet, first_col, last_col).as_str(), rows).await?;
+
+        Ok(())
+    }
+}
+
+///
+/// The change made by a request, every write of the request is recorded with its id, actor and timestamp.
+#[derive(Debug, Clone)]
+struct Change {
+    id: String,
+    actor: String,
+    timestamp: i64
+}
+
+tokio::task_local! {
+    static CHANGE: Change;
+}
+
+impl Change {
+    fn new(actor: String) -> Self {
+        Change { id: format!("{:016x}", rand::random::<u64>()), actor, timestamp: unix_now() }
+    }
+
+    ///
+    /// The change of the request being handled, a new one (by `anonymous`) for the writes made outside of the requests.
+    fn current() -> Self {
+        CHANGE.try_with(Change::clone).unwrap_or_else(|_| Change::new(ANONYMOUS.to_string()))
+    }
+}
+
+///
+/// Middleware that opens the change of a request (see `AuditRecord`), the services record their writes in it
+/// with the rows they know before and after (see `AuditService::entity`), so the cascades share the change
+/// and the concurrent requests do not mix their records.
+pub async fn audit<B: MessageBody>(request: ServiceRequest, next: Next<B>) -> Result<ServiceResponse<B>, Error> {
+    let actor = request
+        .extensions()
+        .get::<Identity>()
+        .map(|identity| identity.subject.clone())
+        .unwrap_or(ANONYMOUS.to_string());
+
+    CHANGE.scope(Change::new(actor), next.call(request)).await
+}
@@ -0,0 +1,154 @@
+use std::{collections::BTreeMap, fs};
+
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{header::{HeaderMap, AUTHORIZATION}, Method},
+    middleware::Next,
+    web, Error, HttpMessage, ResponseError,
+};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+
+use crate::{
+    config::{app_state::AppState, settings::AuthSettings},
+    interface::crudy::ApiResult,
+    model::{auth::{AuthMethod, Claims, Identity}, response::Response},
+    routes::Router,
+    util::error::AppError,
+};
+
+/// Header of the static API keys.
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+///
+/// It identifies the callers with the API keys and the JWT keys of the settings.
+pub struct Authenticator {
+    api_keys: BTreeMap<String, String>,
+    hs256: Option<DecodingKey>,
+    rs256: Option<DecodingKey>,
+    issuer: Option<String>,
+    audience: Option<String>
+}
+
+impl Authenticator {
+    ///
+    /// The keys were validated with the settings, a key that can not be loaded verifies no token.
+    pub fn new(settings: &AuthSettings) -> Self {
+        Authenticator {
+            api_keys: settings.api_keys.clone(),
+            hs256: settings.jwt_hs256_secret.as_ref().map(|secret| DecodingKey::from_secret(secret.as_bytes())),
+            rs256: settings.jwt_rs256_public_key
+                .as_ref()
+                .and_then(|path| fs::read(path).ok())
+                .and_then(|pem| DecodingKey::from_rsa_pem(&pem).ok()),
+            issuer: settings.jwt_issuer.clone(),
+            audience: settings.jwt_audience.clone()
+        }
+    }
+
+    ///
+    /// Whether the callers must be identified, the API is open without keys.
+    pub fn is_enabled(&self) -> bool {
+        !self.api_keys.is_empty() || self.hs256.is_some() || self.rs256.is_some()
+    }
+
+    ///
+    /// It identifies the caller with the `X-API-Key` header or a bearer token in `Authorization`.
+    pub fn identify(&self, headers: &HeaderMap) -> Result<Identity, AppError> {
+        if let Some(key) = headers.get(API_KEY_HEADER) {
+            let key = key.to_str().map_err(|_| AppError::Auth("The API key is not valid".to_string()))?;
+
+            return self.api_key(key)
+        }
+
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(AppError::Auth(format!("The request has no credentials, send `{}` or a bearer token", API_KEY_HEADER)))?;
+
+        self.token(token.trim())
+    }
+
+    fn api_key(&self, key: &str) -> Result<Identity, AppError> {
+        // Every key is compared in constant time, so the time does not tell how much of a key matched
+        let subject = self.api_keys
+            .iter()
+            .fold(None, |found, (known, subject)| match constant_time_eq(known.as_bytes(), key.as_bytes()) {
+                true => Some(subject.clone()),
+                false => found
+            })
+            .ok_or(AppError::Auth("The API key is not valid".to_string()))?;
+
+        Ok(Identity { subject, method: AuthMethod::ApiKey })
+    }
+
+    fn token(&self, token: &str) -> Result<Identity, AppError> {
+        let rejected = |err: jsonwebtoken::errors::Error| AppError::Auth(format!("The token was rejected: {}", err));
+
+        let header = decode_header(token).map_err(rejected)?;
+
+        let key = match header.alg {
+            Algorithm::HS256 => self.hs256.as_ref(),
+            Algorithm::RS256 => self.rs256.as_ref(),
+            _ => None
+        }
+        .ok_or(AppError::Auth(format!("The tokens signed with {:?} are not accepted", header.alg)))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.validate_aud = self.audience.is_some();
+
+        // The claims checked must be in the token
+        let required = [Some("exp"), self.issuer.as_ref().map(|_| "iss"), self.audience.as_ref().map(|_| "aud")];
+        validation.set_required_spec_claims(&required.into_iter().flatten().collect::<Vec<&str>>());
+
+        if let Some(issuer) = &self.issuer { validation.set_issuer(&[issuer]) }
+        if let Some(audience) = &self.audience { validation.set_audience(&[audience]) }
+
+        let claims = decode::<Claims>(token, key, &validation).map_err(rejected)?.claims;
+
+        if claims.sub.trim().is_empty() {
+            return Err(AppError::Auth("The token has no subject".to_string()))
+        }
+
+        Ok(Identity { subject: claims.sub, method: AuthMethod::Jwt })
+    }
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+///
+/// Middleware that identifies the caller of every request (see `Authenticator`),
+/// the identity is stored in the extensions of the request.
+/// The preflight requests of CORS are not identified.
+pub async fn authenticate<B: MessageBody>(request: ServiceRequest, next: Next<B>) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    let state = request.app_data::<web::Data<AppState>>().cloned();
+
+    if let Some(state) = state && state.auth.is_enabled() && request.method() != Method::OPTIONS {
+        // The rejection is answered here, with the same JSON as the rest of the errors
+        match state.auth.identify(request.headers()) {
+            Ok(identity) => { request.extensions_mut().insert(identity); }
+            Err(err) => return Ok(request.into_response(err.error_response()).map_into_right_body())
+        }
+    }
+
+    next.call(request).await.map(ServiceResponse::map_into_left_body)
+}
+
+pub struct AuthService;
+
+impl AuthService {
+    pub fn configure(router: &mut Router) {
+        router.add(Method::GET, "/me", web::route().to(Self::me));
+    }
+
+    ///
+    /// The caller of the request, as it was identified.
+    pub async fn me(identity: Option<web::ReqData<Identity>>) -> ApiResult<Identity> {
+        let identity = identity.ok_or(AppError::Auth("The API is open, the callers are not identified".to_string()))?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(vec![identity.into_inner()]))))
+    }
+}
@@ -1,36 +1,40 @@
-use std::str::FromStr;
-
-use actix_web::{HttpResponse, web};
+use actix_web::web;
 use async_trait::async_trait;
-use google_sheets4::api::{ClearValuesRequest, ValueRange};
 
 use crate::{
-    config::google_sheet_authenticator::get_credentials,
-    interface::crudy::CRUD,
+    config::app_state::AppState,
+    interface::{crudy::{ApiResult, CRUD}, resource::Resource, sheet_entity::SheetEntity, store::StoreResult},
     model::{
-        self, epic::{Epic, InsertEpicData}, spreadsheet::{DataRange, Patch}
+        response::Response,
+        epic::{Epic, InsertEpicData}, spreadsheet::{DataRange, Patch}
     },
-    service::spreadsheet::create_id,
+    service::{audit::AuditService, member::MemberService, spreadsheet::{locate_cell, read_row}, trash::TrashService, workflow::check_transition},
+    routes::{register_crud, register_resource, Router},
     util::{
-        cons::{PROJECTS_SHEET_NAME, SPREADSHEET_ID},
-        util::{extract_num, Identificator, Status},
+        error::AppError,
+        row::{cells, is_empty, set, text},
+        util::Identificator,
     },
 };
 
 pub struct EpicService;
 
+impl EpicService {
+    pub fn configure(router: &mut Router) {
+        register_crud::<Self, _>(router, "/epics", "/epic");
+        register_resource::<Self>(router, "/projects/{id}/epics/{epic_id}");
+    }
+}
+
 #[async_trait]
 impl CRUD for EpicService {
+    type Entity = Epic;
     type CreatePayload = InsertEpicData;
     type ReadPayload = Identificator;
     type UpdatePayload = Patch;
     type DeletePayload = DataRange;
 
-    async fn create(data: web::Json<Self::CreatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
-
+    async fn create(state: web::Data<AppState>, data: web::Json<Self::CreatePayload>) -> ApiResult<Self::Entity> {
         let InsertEpicData {
             project_id,
             title,
@@ -40,18 +44,12 @@ impl CRUD for EpicService {
             created_at,
         } = data.into_inner();
 
-        let mut epic_id = create_id(
-            &PROJECTS_SHEET_NAME,
-            "I",
-            Some(("J", project_id.clone().as_str())),
-        )
-        .await
-        .unwrap();
+        MemberService::check_member(&state, "owner", &owner).await?;
 
-        if epic_id == "null" { epic_id = "E1".to_string() }
-        let index = extract_num(epic_id.clone().as_str());
+        let epic_id = state.ids.next::<Epic>(state.store.as_ref(), Some(&project_id)).await?;
 
-        let full_range = format!("{}!I{}:P{}", &PROJECTS_SHEET_NAME.to_string(), index, index);
+        // The row is chosen by the store, after the last row of the entity
+        let full_range = format!("{}!I1:P1", state.sheet());
 
         let updated_at = created_at.clone();
 
@@ -66,143 +64,76 @@ impl CRUD for EpicService {
             updated_at.to_string(),
         ];
 
-        let request = ValueRange {
-            major_dimension: Some("ROWS".to_string()),
-            range: Some(full_range.clone()),
-            values: Some(vec![
-                epic_data_vector
-                    .iter()
-                    .map(|element| Into::into(element.as_str()))
-                    .collect(),
-            ]),
-        };
-
-        sheet_credentials
-            .spreadsheets()
-            .values_append(request, &SPREADSHEET_ID, full_range.as_str())
-            .value_input_option("USER_ENTERED")
-            .doit()
-            .await
-            .expect("Could not insert the data in the sheet");
-
-        HttpResponse::Ok().json(serde_json::json!({
-            "response": "Data was inserted in the sheet successfully!"
-        }))
-    }
+        let row = state.store.append(full_range.as_str(), epic_data_vector.clone()).await?;
 
-    async fn read(param: Option<web::Query<Self::ReadPayload>>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
-
-        let Identificator { id } = param.unwrap().into_inner();
-
-        let full_range = format!("{}!I:R", &PROJECTS_SHEET_NAME.to_string());
-
-        match sheet_credentials
-            .spreadsheets()
-            .values_get(&SPREADSHEET_ID, full_range.as_ref())
-            .doit()
-            .await
-        {
-            Ok((_, value_range)) => {
-                let values = value_range.values.unwrap_or_default();
-
-                // Filter empty rows
-                let rows: Vec<Epic> = values
-                    .into_iter()
-                    .enumerate()
-                    .filter(|(_, row)| row[1].to_string().replace("\"", "") == id.replace("\"", ""))
-                    .filter_map(|(index, row)| {
-                        Some(Epic {
-                            id: row[0].as_str()?.to_string(),
-                            project_id: row[1].as_str()?.to_string(),
-                            title: row[2].as_str()?.to_string(),
-                            description: row[3].as_str()?.to_string(),
-                            status: Status::from_str(row[4].as_str()?).ok()?,
-                            owner: row[5].as_str()?.to_string(),
-                            created_at: row[6].as_str()?.parse().ok()?,
-                            updated_at: row[7].as_str()?.parse().ok()?,
-
-                            row: format!("I{}:R{}", index, index)
-                        })
-                    })
-                    .collect();
-
-                return HttpResponse::Ok().json(
-                    model::response::Response {
-                        message: "Data was obtained successfully",
-                        content: Some(rows),
-                        error_bug: None
-                    }
-                );
-            }
-            Err(err) => {
-                return HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Error reading the sheet: {}", err)
-                }));
-            }
-        }
+        let epic = Epic::from_row(&cells(&epic_data_vector), Epic::row_label(row))?;
+
+        AuditService::entity(&state, None, Some(&epic)).await?;
+
+        Ok(web::Json(Response::ok("Data was inserted in the sheet successfully!", Some(vec![epic]))))
     }
 
-    async fn update(data: web::Json<Self::UpdatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+    async fn read(state: web::Data<AppState>, param: Option<web::Query<Self::ReadPayload>>) -> ApiResult<Self::Entity> {
+        let Identificator { id } = param
+            .ok_or(AppError::Validation("The query parameters are missing or invalid".to_string()))?
+            .into_inner();
 
-        let Patch { content, cell } = data.into_inner();
+        let full_range = format!("{}!I:R", state.sheet());
+
+        let values = state.store.read(full_range.as_str()).await?;
 
-        let full_range = format!("{}!{}:{}", &PROJECTS_SHEET_NAME.to_string(), cell, cell);
-
-        let epic_data = vec![content];
-
-        let request = ValueRange {
-            major_dimension: Some("ROWS".to_string()),
-            range: Some(full_range.clone()),
-            values: Some(vec![
-                epic_data
-                    .iter()
-                    .map(|element| Into::into(element.as_str()))
-                    .collect(),
-            ]),
-        };
-
-        sheet_credentials
-            .spreadsheets()
-            .values_update(request, &SPREADSHEET_ID, full_range.as_str())
-            .value_input_option("USER_ENTERED")
-            .doit()
-            .await
-            .expect("Could not update the data in the sheet");
-
-        HttpResponse::Ok().json(serde_json::json!({
-            "response": "Data was updated in the sheet successfully!"
-        }))
+        // Filter empty rows
+        let rows: Vec<Epic> = values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row) && text(row, 1) == id.replace("\"", ""))
+            .map(|(index, row)| Epic::from_row(&row, Epic::row_label(index + 1)))
+            .collect::<Result<Vec<Epic>, AppError>>()?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(rows))))
     }
 
-    async fn delete(data: web::Json<Self::DeletePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+    async fn update(state: web::Data<AppState>, data: web::Json<Self::UpdatePayload>) -> ApiResult<Self::Entity> {
+        let Patch { content, cell } = data.into_inner();
+
+        let (row, offset) = locate_cell(&cell, ("I", "P"))?;
+        let mut values = read_row(state.store.as_ref(), state.sheet(), ("I", "P"), row).await?;
+        let current = Epic::from_row(&values, Epic::row_label(row))?;
 
+        // The row is validated before writing the cell
+        set(&mut values, offset, content.clone());
+        let epic = Epic::from_row(&values, Epic::row_label(row)).map_err(AppError::into_validation)?;
+
+        check_transition(&state.settings.workflow, &current, &epic)?;
+        <Self as Resource>::check(&state, Some(&current), &epic).await?;
+
+        let full_range = format!("{}!{}:{}", state.sheet(), cell, cell);
+
+        state.store.update(full_range.as_str(), vec![content]).await?;
+
+        AuditService::entity(&state, Some(&current), Some(&epic)).await?;
+
+        Ok(web::Json(Response::ok("Data was updated in the sheet successfully!", Some(vec![epic]))))
+    }
+
+    async fn delete(state: web::Data<AppState>, data: web::Json<Self::DeletePayload>) -> ApiResult<Self::Entity> {
         let DataRange { range } = data.into_inner();
 
-        let range_str = format!(
-            "{}!{}:{}",
-            &PROJECTS_SHEET_NAME.to_string(),
-            range.0,
-            range.1
-        );
-
-        sheet_credentials
-            .spreadsheets()
-            .values_clear(ClearValuesRequest::default(), &SPREADSHEET_ID, &range_str)
-            .doit()
-            .await
-            .expect("Could not removed the data in the sheet");
-
-        HttpResponse::Ok().json(serde_json::json!({
-            "response": "Epic was removed successfully!"
-        }))
+        TrashService::clear::<Self>(&state, &range.0, &range.1).await?;
+
+        Ok(web::Json(Response::ok("Epic was removed successfully!", None)))
+    }
+}
+
+#[async_trait]
+impl Resource for EpicService {
+    type Entity = Epic;
+    type Path = (String, String);
+
+    async fn check(state: &AppState, current: Option<&Epic>, epic: &Epic) -> StoreResult<()> {
+        match current {
+            Some(current) if current.owner == epic.owner => Ok(()),
+            _ => MemberService::check_member(state, "owner", &epic.owner).await
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,163 @@
+use actix_web::{http::Method, web};
+
+use crate::{
+    config::app_state::AppState,
+    interface::{crudy::ApiResult, resource::{PathKeys, Resource}, sheet_entity::SheetEntity, store::StoreResult},
+    model::{
+        audit::AuditRecord,
+        history::{RestoreQuery, Version},
+        response::Response,
+        spreadsheet::FieldChanges,
+    },
+    routes::{guarded, Router},
+    util::{error::AppError, row::{cells, text}, util::unix_now},
+};
+use serde_json::json;
+
+use super::{
+    audit::AuditService, epic::EpicService, project::ProjectService, spreadsheet::find_row, user_story::UserStoryService,
+};
+
+///
+/// Versions of the projects, epics and stories, rebuilt from the `Audit` sheet.
+pub struct HistoryService;
+
+impl HistoryService {
+    pub fn configure(router: &mut Router) {
+        Self::register::<ProjectService>(router, "/projects/{id}");
+        Self::register::<EpicService>(router, "/projects/{id}/epics/{epic_id}");
+        Self::register::<UserStoryService>(router, "/projects/{id}/epics/{epic_id}/stories/{us_id}");
+    }
+
+    ///
+    /// Registers `GET path/history` and `POST path/restore` of the resource addressed by `path`.
+    fn register<R: Resource>(router: &mut Router, path: &str) {
+        router
+            .add(Method::GET, &format!("{}/history", path), guarded::<R::Entity>(web::route().to(Self::history::<R>)))
+            .add(Method::POST, &format!("{}/restore", path), guarded::<R::Entity>(web::route().to(Self::restore::<R>)));
+    }
+
+    ///
+    /// The versions of the entity, the oldest first.
+    pub async fn history<R: Resource>(state: web::Data<AppState>, path: web::Path<R::Path>) -> ApiResult<Version<R::Entity>> {
+        let keys = path.into_inner().keys();
+
+        let versions = Self::versions::<R::Entity>(&state, &keys).await?;
+
+        if versions.is_empty() {
+            return Err(AppError::NotFound(format!("There is no history of {}", keys.join("/"))))
+        }
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(versions))))
+    }
+
+    ///
+    /// It rewrites the row of the entity as it was in `version`, with a new `updatedAt`, as `patch` would do
+    /// with the fields that differ. The restore is recorded as one more version, so it can be undone too.
+    pub async fn restore<R: Resource>(
+        state: web::Data<AppState>,
+        path: web::Path<R::Path>,
+        query: web::Query<RestoreQuery>
+    ) -> ApiResult<R::Entity> {
+        let keys = path.into_inner().keys();
+        let RestoreQuery { version } = query.into_inner();
+
+        let (row, stored) = find_row::<R::Entity>(state.store.as_ref(), state.sheet(), &keys).await?;
+        let current = R::Entity::from_row(&stored, R::Entity::row_label(row))?;
+
+        let restored = Self::versions::<R::Entity>(&state, &keys)
+            .await?
+            .into_iter()
+            .find(|candidate| candidate.version == version)
+            .ok_or(AppError::NotFound(format!("There is no version {} of {}", version, keys.join("/"))))?
+            .entity
+            .ok_or(AppError::Conflict(format!("The version {} of {} is removed, it can not be restored", version, keys.join("/"))))?;
+
+        let as_document = |entity: &R::Entity| serde_json::to_value(entity).map_err(|err| AppError::CorruptRow(err.to_string()));
+
+        let current_document = as_document(&current)?;
+        let mut document = as_document(&restored)?;
+
+        let changes = document
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(field, value)| current_document.get(field.as_str()) != Some(value))
+            .map(|(field, value)| (field.clone(), value.clone()))
+            .collect::<FieldChanges>();
+
+        document["updatedAt"] = json!(unix_now());
+
+        let restored = serde_json::from_value(document).map_err(|err| AppError::CorruptRow(err.to_string()))?;
+
+        let entity = R::save(&state, row, &current, restored, &changes).await?;
+
+        Ok(web::Json(Response::ok("The version was restored successfully!", Some(vec![entity]))))
+    }
+
+    ///
+    /// The versions are rebuilt backwards from the row as it is stored: the old values of every change are
+    /// applied in reverse, so the entities changed before the audit started keep their first version (`0`).
+    async fn versions<E: SheetEntity>(state: &AppState, keys: &[String]) -> StoreResult<Vec<Version<E>>> {
+        let entity_id = keys.join("/");
+
+        let (label, mut state_cells) = match find_row::<E>(state.store.as_ref(), state.sheet(), keys).await {
+            Ok((row, stored)) => (E::row_label(row), (0..E::FIELDS.len()).map(|col| text(&stored, col)).collect::<Vec<String>>()),
+            // Removed, the history tells what it was but not where
+            Err(AppError::NotFound(_)) => (String::new(), vec![String::new(); E::FIELDS.len()]),
+            Err(err) => return Err(err)
+        };
+
+        // The records of every change, in the order the changes were made
+        let mut changes: Vec<(String, Vec<AuditRecord>)> = vec![];
+
+        for record in AuditService::records(state).await? {
+            if record.entity != E::KIND || record.entity_id != entity_id { continue }
+
+            match changes.iter_mut().find(|(change, _)| *change == record.change) {
+                Some((_, records)) => records.push(record),
+                None => changes.push((record.change.clone(), vec![record]))
+            }
+        }
+
+        let mut after_each = vec![];
+
+        for (_, records) in changes.iter().rev() {
+            after_each.push(state_cells.clone());
+
+            for record in records.iter().rev() {
+                if let Some(col) = E::FIELDS.iter().position(|field| *field == record.field) {
+                    state_cells[col] = record.old_value.clone();
+                }
+            }
+        }
+
+        after_each.reverse();
+
+        let entity_of = |values: &[String]| -> StoreResult<Option<E>> {
+            match values.iter().all(|value| value.trim().is_empty()) {
+                true => Ok(None),
+                false => E::from_row(&cells(values), label.clone()).map(Some)
+            }
+        };
+
+        let mut versions = vec![];
+
+        if let Some(entity) = entity_of(&state_cells)? {
+            versions.push(Version { version: 0, change: None, timestamp: None, actor: None, fields: vec![], entity: Some(entity) });
+        }
+
+        for (index, ((change, records), values)) in changes.into_iter().zip(after_each).enumerate() {
+            versions.push(Version {
+                version: index + 1,
+                timestamp: records.first().map(|record| record.timestamp),
+                actor: records.first().map(|record| record.actor.clone()),
+                fields: records.iter().map(|record| record.field.clone()).collect(),
+                change: Some(change),
+                entity: entity_of(&values)?
+            });
+        }
+
+        Ok(versions)
+    }
+}
@@ -0,0 +1,139 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::Mutex;
+
+use crate::{
+    config::settings::{IdMode, Settings},
+    interface::{sheet_entity::SheetEntity, store::{Store, StoreResult}},
+    util::row::{is_empty, parse, text},
+};
+
+/// Crockford's base 32, used by the ULIDs.
+const ULID_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+#[derive(Default)]
+struct AllocatorState {
+    metadata_ready: bool,
+    /// Milliseconds and random part of the last ULID.
+    last_ulid: (u64, u128)
+}
+
+///
+/// It allocates the ids of new entities, one at a time, so concurrent requests never get the same id.
+///
+/// In `sequential` mode the last number of every entity is kept in the metadata sheet,
+/// one row per counter: the key in `A` (e.g: `P`, or `E/P1` for the epics of `P1`) and the number in `B`.
+/// A counter that does not exist yet starts from the ids already stored.
+pub struct IdAllocator {
+    mode: IdMode,
+    projects_sheet: String,
+    metadata_sheet: String,
+    state: Mutex<AllocatorState>
+}
+
+impl IdAllocator {
+    pub fn new(settings: &Settings) -> Self {
+        IdAllocator {
+            mode: settings.ids.mode,
+            projects_sheet: settings.sheets.projects.clone(),
+            metadata_sheet: settings.sheets.metadata.clone(),
+            state: Mutex::new(AllocatorState::default())
+        }
+    }
+
+    ///
+    /// It returns the id of a new entity, e.g: `E3`.
+    ///
+    /// `project_id`: Project of the entity, the epics, stories, backlog items and sprints are counted per project.
+    pub async fn next<E: SheetEntity>(&self, store: &dyn Store, project_id: Option<&str>) -> StoreResult<String> {
+        // The lock is held until the counter is written, the next request reads the new value
+        let mut state = self.state.lock().await;
+
+        if self.mode == IdMode::Ulid {
+            return Ok(format!("{}{}", E::ID_PREFIX, next_ulid(&mut state.last_ulid)))
+        }
+
+        if !state.metadata_ready {
+            store.ensure_sheet(&self.metadata_sheet).await?;
+            state.metadata_ready = true;
+        }
+
+        let counter = match project_id {
+            Some(project_id) => format!("{}/{}", E::ID_PREFIX, project_id),
+            None => E::ID_PREFIX.to_string()
+        };
+
+        let counters = store.read(&format!("{}!A:B", self.metadata_sheet)).await?;
+
+        let number = match counters.iter().position(|row| text(row, 0) == counter) {
+            Some(index) => {
+                let number = parse::<u64>(&counters[index], 1, &counter)? + 1;
+                let full_range = format!("{}!B{}:B{}", self.metadata_sheet, index + 1, index + 1);
+
+                store.update(full_range.as_str(), vec![number.to_string()]).await?;
+
+                number
+            }
+            None => {
+                let number = self.last_stored::<E>(store, project_id).await? + 1;
+                let full_range = format!("{}!A1:B1", self.metadata_sheet);
+
+                store.append(full_range.as_str(), vec![counter, number.to_string()]).await?;
+
+                number
+            }
+        };
+
+        Ok(format!("{}{}", E::ID_PREFIX, number))
+    }
+
+    ///
+    /// Highest number of the ids stored, e.g: 3 when `E1` and `E3` exist.
+    async fn last_stored<E: SheetEntity>(&self, store: &dyn Store, project_id: Option<&str>) -> StoreResult<u64> {
+        let (first_col, last_col) = E::COLUMNS;
+        let full_range = format!("{}!{}:{}", self.projects_sheet, first_col, last_col);
+
+        // The first id can be allocated before anything is written to the sheet
+        store.ensure_sheet(&self.projects_sheet).await?;
+
+        let rows = store.read(full_range.as_str()).await?;
+
+        // The id is the last key, the project the first one
+        let id_col = E::KEYS[E::KEYS.len() - 1];
+
+        let last = rows
+            .iter()
+            .filter(|row| !is_empty(row))
+            .filter(|row| project_id.is_none_or(|project_id| text(row, E::KEYS[0]) == project_id))
+            .filter_map(|row| text(row, id_col).strip_prefix(E::ID_PREFIX).and_then(|number| number.parse::<u64>().ok()))
+            .max()
+            .unwrap_or(0);
+
+        Ok(last)
+    }
+}
+
+///
+/// It returns a ULID: 48 bits of milliseconds and 80 random bits, encoded in 26 characters.
+/// Within the same millisecond the random part is incremented, so the ids keep the order they were created.
+fn next_ulid(last: &mut (u64, u128)) -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default();
+
+    let (millis, random) = if millis <= last.0 {
+        (last.0, last.1 + 1)
+    } else {
+        (millis, rand::random::<u128>() >> 48)
+    };
+
+    *last = (millis, random);
+
+    let value = ((millis as u128) << 80) | (random & ((1 << 80) - 1));
+
+    (0..26)
+        .rev()
+        .map(|index| ULID_ALPHABET[((value >> (index * 5)) & 31) as usize] as char)
+        .collect()
+}
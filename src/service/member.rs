@@ -0,0 +1,218 @@
+use actix_web::web;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{
+    config::app_state::AppState,
+    interface::{crudy::{ApiResult, CRUD}, resource::Resource, sheet_entity::SheetEntity, store::StoreResult},
+    model::{
+        epic::Epic,
+        member::{InsertTeamMember, TeamMember, TeamMemberQuery},
+        project::Project,
+        response::{FieldError, Response},
+        spreadsheet::{DataRange, Patch},
+        sprint::Sprint,
+    },
+    routes::{register_crud, register_resource, Router},
+    util::{
+        error::AppError,
+        range::column_to_index,
+        row::{cells, is_empty, set, text},
+    },
+};
+
+use super::{assignment::AssignmentService, audit::AuditService, spreadsheet::{find_row, locate_cell, read_row}, trash::TrashService};
+
+///
+/// Members of the team, the owners of the projects, epics and sprints refer to them by id.
+pub struct MemberService;
+
+impl MemberService {
+    pub fn configure(router: &mut Router) {
+        register_crud::<Self, _>(router, "/members", "/member");
+        register_resource::<Self>(router, "/members/{id}");
+    }
+
+    ///
+    /// It checks `member_id` is a member of the team, an empty one refers to nobody.
+    ///
+    /// `field`: Name of the field reported when the member does not exist, e.g: `owner`.
+    pub async fn check_member(state: &AppState, field: &str, member_id: &str) -> StoreResult<()> {
+        if member_id.trim().is_empty() { return Ok(()) }
+
+        match find_row::<TeamMember>(state.store.as_ref(), state.sheet(), &[member_id.to_string()]).await {
+            Ok(_) => Ok(()),
+            Err(AppError::NotFound(_)) => Err(AppError::InvalidFields(vec![FieldError {
+                field: field.to_string(),
+                message: format!("There is no team member {}", member_id)
+            }])),
+            Err(err) => Err(err)
+        }
+    }
+
+    ///
+    /// Keys of the entities whose `field` is the member, e.g: `P1/E2` for the epic `E2` of `P1`.
+    ///
+    /// `values`: Rows read from the column `A`.
+    fn referring<E: SheetEntity>(values: &[Vec<Value>], field: &str, member_id: &str) -> Vec<String> {
+        let first_col = column_to_index(E::COLUMNS.0).unwrap_or_default();
+
+        let Some(offset) = E::FIELDS.iter().position(|name| *name == field) else { return vec![] };
+
+        values
+            .iter()
+            .filter(|row| text(row, first_col + offset) == member_id)
+            .map(|row| E::KEYS.iter().map(|key| text(row, first_col + key)).collect::<Vec<String>>().join("/"))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl CRUD for MemberService {
+    type Entity = TeamMember;
+    type CreatePayload = InsertTeamMember;
+    type ReadPayload = TeamMemberQuery;
+    type UpdatePayload = Patch;
+    type DeletePayload = DataRange;
+
+    async fn create(state: web::Data<AppState>, data: web::Json<Self::CreatePayload>) -> ApiResult<Self::Entity> {
+        let InsertTeamMember {
+            name,
+            email,
+            role,
+            capacity_hours,
+            created_at,
+        } = data.into_inner();
+
+        let member_data_vector = |member_id: String| vec![
+            member_id,
+            name.clone(),
+            email.clone(),
+            format!("{role:?}"),
+            capacity_hours.to_string(),
+            created_at.to_string(),
+            created_at.to_string(),
+        ];
+
+        // It is checked before an id is allocated
+        let member = TeamMember::from_row(&cells(&member_data_vector(String::new())), String::new())?;
+        <Self as Resource>::check(&state, None, &member).await?;
+
+        state.store
+            .ensure_sheet(state.sheet())
+            .await?;
+
+        let member_id = state.ids.next::<TeamMember>(state.store.as_ref(), None).await?;
+
+        // The row is chosen by the store, after the last row of the entity
+        let full_range = format!("{}!BG1:BM1", state.sheet());
+
+        let values = member_data_vector(member_id);
+
+        let row = state.store.append(full_range.as_str(), values.clone()).await?;
+
+        let member = TeamMember::from_row(&cells(&values), TeamMember::row_label(row))?;
+
+        AuditService::entity(&state, None, Some(&member)).await?;
+
+        Ok(web::Json(Response::ok("Data was inserted in the sheet successfully!", Some(vec![member]))))
+    }
+
+    async fn read(state: web::Data<AppState>, param: Option<web::Query<Self::ReadPayload>>) -> ApiResult<Self::Entity> {
+        let TeamMemberQuery { role } = param
+            .ok_or(AppError::Validation("The query parameters are missing or invalid".to_string()))?
+            .into_inner();
+
+        let full_range = format!("{}!BG:BM", state.sheet());
+
+        let values = state.store.read(full_range.as_str()).await?;
+
+        // Filter empty rows
+        let rows: Vec<TeamMember> = values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row))
+            .map(|(index, row)| TeamMember::from_row(&row, TeamMember::row_label(index + 1)))
+            .filter(|member| !matches!((member, role), (Ok(member), Some(role)) if member.role != role))
+            .collect::<Result<Vec<TeamMember>, AppError>>()?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(rows))))
+    }
+
+    async fn update(state: web::Data<AppState>, data: web::Json<Self::UpdatePayload>) -> ApiResult<Self::Entity> {
+        let Patch { content, cell } = data.into_inner();
+
+        let (row, offset) = locate_cell(&cell, ("BG", "BM"))?;
+        let mut values = read_row(state.store.as_ref(), state.sheet(), ("BG", "BM"), row).await?;
+        let current = TeamMember::from_row(&values, TeamMember::row_label(row))?;
+
+        // The row is validated before writing the cell
+        set(&mut values, offset, content.clone());
+        let member = TeamMember::from_row(&values, TeamMember::row_label(row)).map_err(AppError::into_validation)?;
+
+        <Self as Resource>::check(&state, Some(&current), &member).await?;
+
+        let full_range = format!("{}!{}:{}", state.sheet(), cell, cell);
+
+        state.store.update(full_range.as_str(), vec![content]).await?;
+
+        AuditService::entity(&state, Some(&current), Some(&member)).await?;
+
+        Ok(web::Json(Response::ok("Data was updated in the sheet successfully!", Some(vec![member]))))
+    }
+
+    async fn delete(state: web::Data<AppState>, data: web::Json<Self::DeletePayload>) -> ApiResult<Self::Entity> {
+        let DataRange { range } = data.into_inner();
+
+        TrashService::clear::<Self>(&state, &range.0, &range.1).await?;
+
+        Ok(web::Json(Response::ok("It was removed successfully", None)))
+    }
+}
+
+#[async_trait]
+impl Resource for MemberService {
+    type Entity = TeamMember;
+    type Path = String;
+
+    async fn check(_state: &AppState, _current: Option<&TeamMember>, member: &TeamMember) -> StoreResult<()> {
+        let problems = member.problems();
+
+        if problems.is_empty() { return Ok(()) }
+
+        Err(AppError::InvalidFields(
+            problems
+                .into_iter()
+                .map(|(field, message)| FieldError { field: field.to_string(), message: message.to_string() })
+                .collect()
+        ))
+    }
+
+    // A member can not be removed while something refers to it
+    async fn removing(state: &AppState, member: &TeamMember) -> StoreResult<()> {
+        let values = state.store.read(format!("{}!A:BE", state.sheet()).as_str()).await?;
+
+        let owned = [
+            Self::referring::<Project>(&values, "owner", &member.id),
+            Self::referring::<Epic>(&values, "owner", &member.id),
+            Self::referring::<Sprint>(&values, "owner", &member.id),
+        ]
+        .concat();
+
+        let assigned = AssignmentService::of_member(state, &member.id)
+            .await?
+            .into_iter()
+            .map(|assignment| format!("{}/{}", assignment.project_id, assignment.entity_id))
+            .collect::<Vec<String>>();
+
+        let references = [("the owner of", owned), ("assigned to", assigned)]
+            .into_iter()
+            .filter(|(_, keys)| !keys.is_empty())
+            .map(|(relation, keys)| format!("{} {}", relation, keys.join(", ")))
+            .collect::<Vec<String>>();
+
+        if references.is_empty() { return Ok(()) }
+
+        Err(AppError::Conflict(format!("The member {} is {}", member.id, references.join(" and "))))
+    }
+}
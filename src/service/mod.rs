@@ -1,6 +1,19 @@
 pub mod project;
 pub mod epic;
 pub mod spreadsheet;
+pub mod id_allocator;
 pub mod user_story;
 pub mod product_backlog;
-pub mod sprint;
\ No newline at end of file
+pub mod sprint;
+pub mod sprint_item;
+pub mod sprint_chart;
+pub mod velocity;
+pub mod workflow;
+pub mod summary;
+pub mod member;
+pub mod assignment;
+pub mod auth;
+pub mod permission;
+pub mod audit;
+pub mod history;
+pub mod trash;
@@ -0,0 +1,465 @@
+use std::collections::HashMap;
+
+use actix_web::{
+    body::{to_bytes, BoxBody, EitherBody, MessageBody},
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    http::Method,
+    middleware::Next,
+    web, Error, HttpMessage, ResponseError,
+};
+use serde_json::Value;
+
+use crate::{
+    config::app_state::AppState,
+    interface::{crudy::{ApiResult, ProjectScoped}, relation::Relation, sheet_entity::SheetEntity, store::StoreResult},
+    model::{
+        auth::Identity,
+        epic::Epic,
+        member::TeamMember,
+        permission::{GrantRole, Permission, ProjectRole},
+        product_backlog::ProductBacklogItem,
+        project::Project,
+        response::Response,
+        sprint::Sprint,
+        user_story::UserStoryBox,
+    },
+    routes::Router,
+    util::{error::AppError, row::{is_empty, text}},
+};
+
+use super::{assignment::AssignmentService, audit::AuditService, spreadsheet::{find_row, locate_cell}};
+
+///
+/// Roles of the callers in every project, the relation is kept in the `Permissions` sheet
+/// (one role per caller and project). The roles are enforced by `guard`.
+pub struct PermissionService;
+
+impl PermissionService {
+    pub fn configure(router: &mut Router) {
+        let path = "/projects/{id}/permissions/{subject}";
+
+        router
+            .add(Method::GET, "/projects/{id}/permissions", web::route().to(Self::list))
+            .add(Method::PUT, path, web::route().to(Self::grant))
+            .add(Method::DELETE, path, web::route().to(Self::revoke));
+    }
+
+    ///
+    /// Roles granted in the project, every role can read them.
+    pub async fn list(state: web::Data<AppState>, path: web::Path<String>, identity: Option<web::ReqData<Identity>>) -> ApiResult<Permission> {
+        let project_id = path.into_inner();
+
+        Self::require(&state, identity.as_deref(), &project_id, None).await?;
+
+        let permissions = Self::of_project(&state, &project_id).await?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(permissions))))
+    }
+
+    ///
+    /// It grants the role to the caller, replacing the one granted before. Only a `ProductOwner` can grant roles.
+    pub async fn grant(
+        state: web::Data<AppState>,
+        path: web::Path<(String, String)>,
+        identity: Option<web::ReqData<Identity>>,
+        data: web::Json<GrantRole>
+    ) -> ApiResult<Permission> {
+        let (project_id, subject) = path.into_inner();
+        let GrantRole { role } = data.into_inner();
+
+        find_row::<Project>(state.store.as_ref(), state.sheet(), std::slice::from_ref(&project_id)).await?;
+
+        Self::require(&state, identity.as_deref(), &project_id, Some(&[ProjectRole::ProductOwner])).await?;
+
+        if role != ProjectRole::ProductOwner {
+            Self::keep_an_owner(&state, &project_id, &subject).await?;
+        }
+
+        let permission = Self::set_role(&state, &project_id, &subject, role).await?;
+
+        Ok(web::Json(Response::ok("The role was granted successfully!", Some(vec![permission]))))
+    }
+
+    ///
+    /// It removes the role of the caller in the project. Only a `ProductOwner` can remove roles.
+    pub async fn revoke(state: web::Data<AppState>, path: web::Path<(String, String)>, identity: Option<web::ReqData<Identity>>) -> ApiResult<Permission> {
+        let (project_id, subject) = path.into_inner();
+
+        Self::require(&state, identity.as_deref(), &project_id, Some(&[ProjectRole::ProductOwner])).await?;
+        Self::keep_an_owner(&state, &project_id, &subject).await?;
+
+        let removed = Self::unlink(&state, |permission| permission.project_id == project_id && permission.subject == subject).await?;
+
+        if removed.is_empty() {
+            return Err(AppError::NotFound(format!("{} has no role in the project {}", subject, project_id)))
+        }
+
+        Ok(web::Json(Response::ok("The role was removed successfully!", Some(removed))))
+    }
+
+    ///
+    /// Role of the caller in the project, `None` when it has none.
+    pub async fn role_of(state: &AppState, project_id: &str, subject: &str) -> StoreResult<Option<ProjectRole>> {
+        let permissions = Self::permissions(state).await?;
+
+        Ok(permissions
+            .into_iter()
+            .find(|(_, permission)| permission.project_id == project_id && permission.subject == subject)
+            .map(|(_, permission)| permission.role))
+    }
+
+    ///
+    /// Projects where the caller has a role.
+    pub async fn projects_of(state: &AppState, subject: &str) -> StoreResult<Vec<String>> {
+        let permissions = Self::permissions(state).await?;
+
+        Ok(permissions
+            .into_iter()
+            .filter(|(_, permission)| permission.subject == subject)
+            .map(|(_, permission)| permission.project_id)
+            .collect())
+    }
+
+    ///
+    /// It checks the role of the caller in the project is one of `roles` (any role when it is `None`),
+    /// nothing is checked when the API is open.
+    pub async fn require(state: &AppState, identity: Option<&Identity>, project_id: &str, roles: Option<&[ProjectRole]>) -> StoreResult<()> {
+        let Some(identity) = identity else { return Ok(()) };
+
+        match (Self::role_of(state, project_id, &identity.subject).await?, roles) {
+            (None, _) => Err(AppError::Forbidden(format!("{} has no role in the project {}", identity.subject, project_id))),
+            (Some(role), Some(roles)) if !roles.contains(&role) => Err(AppError::Forbidden(format!(
+                "The role {:?} in the project {} does not allow it, it requires {}",
+                role,
+                project_id,
+                roles.iter().map(|role| format!("{:?}", role)).collect::<Vec<String>>().join(" or ")
+            ))),
+            _ => Ok(())
+        }
+    }
+
+    ///
+    /// It checks the caller is `ProductOwner` of any project, the members belong to the whole team
+    /// so only the owners of the projects change them. Nothing is checked when the API is open.
+    ///
+    /// Until a role is granted in any project every caller manages them, the owners of the first projects are members.
+    pub async fn manage_members(state: &AppState, identity: Option<&Identity>) -> StoreResult<()> {
+        let Some(identity) = identity else { return Ok(()) };
+
+        let permissions = Self::permissions(state).await?;
+
+        if permissions.is_empty() { return Ok(()) }
+
+        let is_owner = permissions
+            .iter()
+            .any(|(_, permission)| permission.subject == identity.subject && permission.role == ProjectRole::ProductOwner);
+
+        if is_owner { return Ok(()) }
+
+        Err(AppError::Forbidden(format!("{} is not ProductOwner of any project, it can not manage the members", identity.subject)))
+    }
+
+    ///
+    /// It sets the role of the caller in the project.
+    pub async fn set_role(state: &AppState, project_id: &str, subject: &str, role: ProjectRole) -> StoreResult<Permission> {
+        let permission = Permission { project_id: project_id.to_string(), subject: subject.to_string(), role };
+
+        Self::unlink(state, |other| other.project_id == project_id && other.subject == subject).await?;
+
+        let sheet = &state.settings.sheets.permissions;
+        let (first_col, last_col) = Permission::COLUMNS;
+
+        state.store.append(format!("{}!{}1:{}1", sheet, first_col, last_col).as_str(), permission.to_row()).await?;
+
+        AuditService::relation(state, None, Some(&permission)).await?;
+
+        Ok(permission)
+    }
+
+    ///
+    /// A project always keeps a `ProductOwner`, who can grant the roles.
+    async fn keep_an_owner(state: &AppState, project_id: &str, subject: &str) -> StoreResult<()> {
+        let owners = Self::of_project(state, project_id)
+            .await?
+            .into_iter()
+            .filter(|permission| permission.role == ProjectRole::ProductOwner && permission.subject != subject)
+            .count();
+
+        match owners {
+            0 => Err(AppError::Conflict(format!("{} is the last ProductOwner of the project {}", subject, project_id))),
+            _ => Ok(())
+        }
+    }
+
+    async fn of_project(state: &AppState, project_id: &str) -> StoreResult<Vec<Permission>> {
+        let permissions = Self::permissions(state).await?;
+
+        Ok(permissions.into_iter().map(|(_, permission)| permission).filter(|permission| permission.project_id == project_id).collect())
+    }
+
+    ///
+    /// Every permission with its row (one based).
+    async fn permissions(state: &AppState) -> StoreResult<Vec<(usize, Permission)>> {
+        let sheet = &state.settings.sheets.permissions;
+        let (first_col, last_col) = Permission::COLUMNS;
+
+        state.ensure_sheet(sheet).await?;
+
+        let values = state.store.read(format!("{}!{}:{}", sheet, first_col, last_col).as_str()).await?;
+
+        values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row))
+            .map(|(index, row)| Permission::from_row(&row).map(|permission| (index + 1, permission)))
+            .collect()
+    }
+
+    ///
+    /// It removes the permissions that match, returning them.
+    async fn unlink(state: &AppState, matches: impl Fn(&Permission) -> bool) -> StoreResult<Vec<Permission>> {
+        let sheet = &state.settings.sheets.permissions;
+        let (first_col, last_col) = Permission::COLUMNS;
+
+        let mut removed = vec![];
+
+        for (row, permission) in Self::permissions(state).await? {
+            if !matches(&permission) { continue }
+
+            let full_range = format!("{}!{}{}:{}{}", sheet, first_col, row, last_col, row);
+            state.store.clear(full_range.as_str()).await?;
+
+            AuditService::relation(state, Some(&permission), None).await?;
+
+            removed.push(permission);
+        }
+
+        Ok(removed)
+    }
+}
+
+///
+/// What a request does with the entities of a project, see `guard`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Read,
+    Create,
+    Update,
+    Delete
+}
+
+impl Action {
+    fn verb(self) -> &'static str {
+        match self {
+            Action::Read => "read",
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete"
+        }
+    }
+}
+
+///
+/// Middleware that enforces the roles of the caller (see `ProjectRole`) on the routes of an entity,
+/// the project is taken from the path, the payload `P` (as the handler reads it) or the cells of the legacy routes.
+/// Nothing is checked when the API is open, the members are changed only by the owners of the projects (see `manage_members`).
+///
+/// The creator of a project is granted `ProductOwner` in it.
+pub async fn guard<E: SheetEntity, P: ProjectScoped, B: MessageBody + 'static>(
+    mut request: ServiceRequest,
+    next: Next<B>
+) -> Result<ServiceResponse<EitherBody<B, BoxBody>>, Error> {
+    let identity = request.extensions().get::<Identity>().cloned();
+    let state = request.app_data::<web::Data<AppState>>().cloned();
+
+    let (Some(identity), Some(state)) = (identity, state) else {
+        return next.call(request).await.map(ServiceResponse::map_into_left_body)
+    };
+
+    // A `POST` under the path of an entity changes it, e.g: the items of a sprint
+    let action = match *request.method() {
+        Method::POST if request.match_info().iter().next().is_none() => Action::Create,
+        Method::POST | Method::PATCH | Method::PUT => Action::Update,
+        Method::DELETE => Action::Delete,
+        _ => Action::Read
+    };
+
+    // The members do not belong to a project, every caller reads them
+    if E::KIND == TeamMember::KIND && action != Action::Read
+        && let Err(err) = PermissionService::manage_members(&state, Some(&identity)).await {
+        return Ok(request.into_response(err.error_response()).map_into_right_body())
+    }
+
+    if ![Project::KIND, Epic::KIND, UserStoryBox::KIND, ProductBacklogItem::KIND, Sprint::KIND].contains(&E::KIND) {
+        return next.call(request).await.map(ServiceResponse::map_into_left_body)
+    }
+
+    // The body tells the project of the legacy routes, it is put back for the handler
+    let body = request.extract::<web::Bytes>().await?;
+    request.set_payload(Payload::from(body.clone()));
+
+    if let Err(err) = authorize::<E, P>(&state, &identity, action, &request, &body).await {
+        return Ok(request.into_response(err.error_response()).map_into_right_body())
+    }
+
+    let response = next.call(request).await?;
+
+    if E::KIND != Project::KIND || action != Action::Create || !response.status().is_success() {
+        return Ok(response.map_into_left_body())
+    }
+
+    let (http_request, response) = response.into_parts();
+    let (response, body) = response.into_parts();
+
+    let body = to_bytes(body).await.map_err(|err| {
+        let err: Box<dyn std::error::Error> = err.into();
+        AppError::Upstream(err.to_string())
+    })?;
+
+    let created = serde_json::from_slice::<Value>(&body).unwrap_or_default();
+
+    if let Some(project_id) = created["content"][0]["id"].as_str() {
+        PermissionService::set_role(&state, project_id, &identity.subject, ProjectRole::ProductOwner).await?;
+    }
+
+    Ok(ServiceResponse::new(http_request, response.set_body(body).map_into_boxed_body()).map_into_right_body())
+}
+
+///
+/// It checks the role of the caller in the projects of the request.
+async fn authorize<E: SheetEntity, P: ProjectScoped>(
+    state: &AppState,
+    identity: &Identity,
+    action: Action,
+    request: &ServiceRequest,
+    body: &[u8]
+) -> StoreResult<()> {
+    let json = serde_json::from_slice::<Value>(body).unwrap_or_default();
+    let keys = request.match_info().iter().map(|(_, value)| value.to_string()).collect::<Vec<String>>();
+
+    // (project id, entity id) of every entity touched and the fields changed
+    let (targets, fields) = match (keys.first(), action) {
+        (Some(project_id), _) => (vec![(project_id.clone(), keys.last().cloned())], changed_fields(&json)),
+        (None, Action::Read | Action::Create) => {
+            if E::KIND == Project::KIND && action == Action::Create { return Ok(()) }
+
+            match payload_project::<P>(action, request, &json, body)? {
+                Some(project_id) => (vec![(project_id, None)], vec![]),
+                // Every project is listed (each caller sees them) or the handler rejects the payload
+                None => return Ok(())
+            }
+        },
+        (None, _) => legacy_targets::<E>(state, &json).await?
+    };
+
+    let subject = &identity.subject;
+
+    for (project_id, entity_id) in targets {
+        let Some(role) = PermissionService::role_of(state, &project_id, subject).await? else {
+            return Err(AppError::Forbidden(format!("{} has no role in the project {}", subject, project_id)))
+        };
+
+        let allowed = match (E::KIND, action, role) {
+            (_, Action::Read, _) => true,
+            (Sprint::KIND, _, role) => role == ProjectRole::ScrumMaster,
+            (_, _, ProjectRole::ProductOwner) => true,
+            // A developer moves the stories and backlog items assigned to it
+            (kind, Action::Update, ProjectRole::Developer) if kind == UserStoryBox::KIND || kind == ProductBacklogItem::KIND => {
+                let assignee = match &entity_id {
+                    Some(entity_id) => AssignmentService::assignee(state, &project_id, kind, entity_id).await?,
+                    None => None
+                };
+
+                !fields.is_empty() && fields.iter().all(|field| field == "status") && assignee.as_ref() == Some(subject)
+            },
+            _ => false
+        };
+
+        if !allowed {
+            return Err(AppError::Forbidden(format!(
+                "The role {:?} in the project {} can not {} the {}",
+                role,
+                project_id,
+                action.verb(),
+                E::KIND.replace('_', " ")
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Project of the query (`Read`) or the body (`Create`), read with the payload of the handler.
+/// The other fields that name a project must be the same one, otherwise the handler could read another.
+fn payload_project<P: ProjectScoped>(action: Action, request: &ServiceRequest, json: &Value, body: &[u8]) -> StoreResult<Option<String>> {
+    let (payload, candidates) = match action {
+        Action::Read => {
+            let query = web::Query::<HashMap<String, String>>::from_query(request.query_string())
+                .map(|query| query.into_inner())
+                .unwrap_or_default();
+
+            (
+                web::Query::<P>::from_query(request.query_string()).ok().map(|query| query.into_inner()),
+                ["id", "projectId", "project_id"].iter().filter_map(|key| query.get(*key).cloned()).collect::<Vec<String>>()
+            )
+        },
+        _ => (
+            serde_json::from_slice::<P>(body).ok(),
+            ["projectId", "project_id"].iter().filter_map(|key| json[*key].as_str().map(str::to_string)).collect()
+        )
+    };
+
+    let Some(project_id) = payload.as_ref().and_then(P::project_id).map(str::to_string) else { return Ok(None) };
+
+    match candidates.into_iter().find(|candidate| *candidate != project_id) {
+        Some(other) => Err(AppError::Validation(format!("The request names two projects: {} and {}", project_id, other))),
+        None => Ok(Some(project_id))
+    }
+}
+
+///
+/// Fields changed by a JSON Merge Patch.
+fn changed_fields(json: &Value) -> Vec<String> {
+    json.as_object().map(|changes| changes.keys().cloned().collect()).unwrap_or_default()
+}
+
+///
+/// Entities addressed by the cells of the legacy `PATCH` (`cell`) and `DELETE` (`range`) routes.
+/// A body the handler would reject has no entities.
+async fn legacy_targets<E: SheetEntity>(state: &AppState, json: &Value) -> StoreResult<(Vec<(String, Option<String>)>, Vec<String>)> {
+    let (rows, fields) = match (json["cell"].as_str(), json["range"][0].as_str(), json["range"][1].as_str()) {
+        (Some(cell), _, _) => match locate_cell(cell, E::COLUMNS) {
+            Ok((row, offset)) => ((row, row), E::FIELDS.get(offset).map(|field| vec![field.to_string()]).unwrap_or_default()),
+            Err(_) => return Ok((vec![], vec![]))
+        },
+        (None, Some(start), Some(end)) => {
+            // The range can not reach the cells of other entities
+            let rows = match (locate_cell(start, E::COLUMNS), locate_cell(end, E::COLUMNS)) {
+                (Ok((first, _)), Ok((last, _))) => (first.min(last), first.max(last)),
+                _ => return Err(AppError::Forbidden(format!(
+                    "The range {}:{} is outside of the rows of the {}",
+                    start,
+                    end,
+                    E::KIND.replace('_', " ")
+                )))
+            };
+
+            (rows, vec![])
+        },
+        _ => return Ok((vec![], vec![]))
+    };
+
+    let (first_col, last_col) = E::COLUMNS;
+    let full_range = format!("{}!{}{}:{}{}", state.sheet(), first_col, rows.0, last_col, rows.1);
+
+    let targets = state.store
+        .read(full_range.as_str())
+        .await?
+        .into_iter()
+        .filter(|row| !is_empty(row))
+        .map(|row| (text(&row, E::KEYS[0]), E::KEYS.last().map(|key| text(&row, *key))))
+        .collect();
+
+    Ok((targets, fields))
+}
@@ -1,38 +1,44 @@
-use std::str::FromStr;
-
-use actix_web::{HttpResponse, web};
+use actix_web::web;
 use async_trait::async_trait;
-use google_sheets4::api::{ClearValuesRequest, ValueRange};
 
 use crate::{
-    config::google_sheet_authenticator::get_credentials,
-    interface::crudy::CRUD,
+    config::app_state::AppState,
+    interface::{crudy::{ApiResult, CRUD}, resource::Resource, sheet_entity::SheetEntity, store::StoreResult},
     model::{
-        self,
-        product_backlog::{InsertProductBacklogItem, ItemType, ProductBacklogItem},
+        response::Response,
+        product_backlog::{InsertProductBacklogItem, ProductBacklogItem, ProductBacklogQuery},
         spreadsheet::{DataRange, Patch},
+        trash::Relations,
+    },
+    service::{
+        assignment::AssignmentService, audit::AuditService, spreadsheet::{locate_cell, read_row}, sprint_item::SprintItemService,
+        trash::TrashService, workflow::check_transition,
     },
-    service::spreadsheet::create_id,
+    routes::{register_crud, register_resource, Router},
     util::{
-        cons::{PROJECTS_SHEET_NAME, SPREADSHEET_ID},
-        util::{extract_num, Identificator},
+        error::AppError,
+        row::{cells, is_empty, set, text},
     },
 };
 
 pub struct ProductBacklogService;
 
+impl ProductBacklogService {
+    pub fn configure(router: &mut Router) {
+        register_crud::<Self, _>(router, "/pbs", "/pb");
+        register_resource::<Self>(router, "/projects/{id}/backlog/{item_id}");
+    }
+}
+
 #[async_trait]
 impl CRUD for ProductBacklogService {
+    type Entity = ProductBacklogItem;
     type CreatePayload = InsertProductBacklogItem;
-    type ReadPayload = Identificator;
+    type ReadPayload = ProductBacklogQuery;
     type UpdatePayload = Patch;
     type DeletePayload = DataRange;
 
-    async fn create(data: web::Json<Self::CreatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
-
+    async fn create(state: web::Data<AppState>, data: web::Json<Self::CreatePayload>) -> ApiResult<Self::Entity> {
         let InsertProductBacklogItem {
             project_id,
             epic_id,
@@ -41,31 +47,17 @@ impl CRUD for ProductBacklogService {
             description,
             points,
             kind,
+            status,
             assigned_at,
             created_at,
         } = data.into_inner();
 
         let updated_at = created_at.clone();
 
-        let mut item_id = create_id(
-            &PROJECTS_SHEET_NAME,
-            "AF",
-            Some(("AG", project_id.clone().as_str())),
-        )
-        .await
-        .unwrap();
-
-        if item_id == "null" {
-            item_id = "PB1".to_string()
-        }
-        let index = extract_num(item_id.clone().as_str());
+        let item_id = state.ids.next::<ProductBacklogItem>(state.store.as_ref(), Some(&project_id)).await?;
 
-        let full_range = format!(
-            "{}!AF{}:AP{}",
-            &PROJECTS_SHEET_NAME.to_string(),
-            index,
-            index
-        );
+        // The row is chosen by the store, after the last row of the entity
+        let full_range = format!("{}!AF1:AQ1", state.sheet());
 
         let item_data_vector = vec![
             item_id,
@@ -79,153 +71,124 @@ impl CRUD for ProductBacklogService {
             assigned_at.to_string(),
             created_at.to_string(),
             updated_at.to_string(),
+            format!("{status:?}"),
         ];
 
-        let request = ValueRange {
-            major_dimension: Some("ROWS".to_string()),
-            range: Some(full_range.clone()),
-            values: Some(vec![
-                item_data_vector
-                    .iter()
-                    .map(|element| Into::into(element.as_str()))
-                    .collect(),
-            ]),
-        };
-
-        sheet_credentials
-            .spreadsheets()
-            .values_append(request, &SPREADSHEET_ID, full_range.as_str())
-            .value_input_option("USER_ENTERED")
-            .doit()
-            .await
-            .expect("Could not insert the data in the sheet");
-
-        HttpResponse::Ok().json(model::response::Response::<ProductBacklogItem> {
-            message: "Data was inserted in the sheet successfully!",
-            content: None,
-            error_bug: None,
-        })
+        let row = state.store.append(full_range.as_str(), item_data_vector.clone()).await?;
+
+        let item = ProductBacklogItem::from_row(&cells(&item_data_vector), ProductBacklogItem::row_label(row))?;
+
+        AuditService::entity(&state, None, Some(&item)).await?;
+
+        Ok(web::Json(Response::ok("Data was inserted in the sheet successfully!", Some(vec![item]))))
     }
 
-    async fn read(param: Option<web::Query<Self::ReadPayload>>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
-
-        let Identificator { id } = param.unwrap().into_inner();
-
-        let full_range = format!("{}!AF:AP", &PROJECTS_SHEET_NAME.to_string());
-
-        match sheet_credentials
-            .spreadsheets()
-            .values_get(&SPREADSHEET_ID, full_range.as_ref())
-            .doit()
-            .await
-        {
-            Ok((_, value_range)) => {
-                let values = value_range.values.unwrap_or_default();
-
-                let rows: Vec<ProductBacklogItem> = values
-                    .into_iter()
-                    .enumerate()
-                    .filter(|(_, row)| row[1].to_string().replace("\"", "") == id.replace("\"", ""))
-                    .filter_map(|(index, row)| {
-                        Some(ProductBacklogItem {
-                            id: row[0].as_str()?.to_string(),
-                            project_id: row[1].as_str()?.to_string(),
-                            epic_id: row[2].as_str()?.to_string(),
-                            title: row[3].as_str()?.to_string(),
-                            priority: row[4].as_str()?.to_string(),
-                            description: Some(row[5].as_str()?.to_string()),
-                            points: Some(row[6].as_str()?.parse::<i64>().ok()?),
-                            kind: ItemType::from_str(row[7].as_str()?).ok().unwrap(),
-                            assigned_at: row[8].as_str()?.parse::<i64>().ok()?,
-                            created_at: row[9].as_str()?.parse::<i64>().ok()?,
-                            updated_at: row[10].as_str()?.parse::<i64>().ok()?,
-
-                            row: format!("AF{}:AP{}", index, index)
-                        })
-                    })
-                    .collect();
-
-                return HttpResponse::Ok().json(model::response::Response::<ProductBacklogItem> {
-                    message: "Data was obtained successfully",
-                    content: Some(rows),
-                    error_bug: None,
-                });
-            }
-            Err(err) => {
-                return HttpResponse::InternalServerError().json(model::response::Response::<ProductBacklogItem> {
-                    message: "Error reading the sheet",
-                    content: None,
-                    error_bug: Some(vec![err.to_string()]),
-                });
-            }
+    async fn read(state: web::Data<AppState>, param: Option<web::Query<Self::ReadPayload>>) -> ApiResult<Self::Entity> {
+        let ProductBacklogQuery { id, status } = param
+            .ok_or(AppError::Validation("The query parameters are missing or invalid".to_string()))?
+            .into_inner();
+
+        let full_range = format!("{}!AF:AQ", state.sheet());
+
+        let values = state.store.read(full_range.as_str()).await?;
+
+        // Filter empty rows
+        let mut rows: Vec<ProductBacklogItem> = values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row) && text(row, 1) == id.replace("\"", ""))
+            .map(|(index, row)| ProductBacklogItem::from_row(&row, ProductBacklogItem::row_label(index + 1)))
+            .filter(|item| !matches!((item, status), (Ok(item), Some(status)) if item.status != status))
+            .collect::<Result<Vec<ProductBacklogItem>, AppError>>()?;
+
+        let mut assignees = AssignmentService::assignees(&state, &id.replace("\"", ""), ProductBacklogItem::KIND).await?;
+
+        for item in rows.iter_mut() {
+            item.assignee = assignees.remove(&item.id);
         }
-    }
 
-    async fn update(data: web::Json<Self::UpdatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(rows))))
+    }
 
+    async fn update(state: web::Data<AppState>, data: web::Json<Self::UpdatePayload>) -> ApiResult<Self::Entity> {
         let Patch { content, cell } = data.into_inner();
 
-        let full_range = format!("{}!{}:{}", &PROJECTS_SHEET_NAME.to_string(), cell, cell);
-
-        let item_partial_data = vec![content];
-
-        let request = ValueRange {
-            major_dimension: Some("ROWS".to_string()),
-            range: Some(full_range.clone()),
-            values: Some(vec![
-                item_partial_data
-                    .iter()
-                    .map(|element| Into::into(element.as_str()))
-                    .collect(),
-            ]),
-        };
-
-        sheet_credentials
-            .spreadsheets()
-            .values_update(request, &SPREADSHEET_ID, full_range.as_str())
-            .value_input_option("USER_ENTERED")
-            .doit()
-            .await
-            .expect("Could not update the data in the sheet");
-
-        HttpResponse::Ok().json(model::response::Response::<ProductBacklogItem> {
-            message: "Data was updated in the sheet successfully!",
-            content: None,
-            error_bug: None,
-        })
-    }
+        let (row, offset) = locate_cell(&cell, ("AF", "AQ"))?;
+        let mut values = read_row(state.store.as_ref(), state.sheet(), ("AF", "AQ"), row).await?;
+        let current = ProductBacklogItem::from_row(&values, ProductBacklogItem::row_label(row))?;
+
+        // The row is validated before writing the cell
+        set(&mut values, offset, content.clone());
+        let item = ProductBacklogItem::from_row(&values, ProductBacklogItem::row_label(row)).map_err(AppError::into_validation)?;
+
+        check_transition(&state.settings.workflow, &current, &item)?;
+        <Self as Resource>::check(&state, Some(&current), &item).await?;
+
+        let full_range = format!("{}!{}:{}", state.sheet(), cell, cell);
+
+        state.store.update(full_range.as_str(), vec![content]).await?;
+
+        AuditService::entity(&state, Some(&current), Some(&item)).await?;
 
-    async fn delete(data: web::Json<Self::DeletePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+        <Self as Resource>::patched(&state, &item).await?;
 
+        Ok(web::Json(Response::ok("Data was updated in the sheet successfully!", Some(vec![item]))))
+    }
+
+    async fn delete(state: web::Data<AppState>, data: web::Json<Self::DeletePayload>) -> ApiResult<Self::Entity> {
         let DataRange { range } = data.into_inner();
 
-        let range_str = format!(
-            "{}!{}:{}",
-            &PROJECTS_SHEET_NAME.to_string(),
-            range.0,
-            range.1
-        );
-
-        sheet_credentials
-            .spreadsheets()
-            .values_clear(ClearValuesRequest::default(), &SPREADSHEET_ID, &range_str)
-            .doit()
-            .await
-            .expect("Could not removed the data in the sheet");
-
-        HttpResponse::Ok().json(model::response::Response::<ProductBacklogItem> {
-            message: "It was removed successfully",
-            content: None,
-            error_bug: None
-        })
+        TrashService::clear::<Self>(&state, &range.0, &range.1).await?;
+
+        Ok(web::Json(Response::ok("It was removed successfully", None)))
+    }
+}
+
+#[async_trait]
+impl Resource for ProductBacklogService {
+    type Entity = ProductBacklogItem;
+    type Path = (String, String);
+
+    async fn loaded(state: &AppState, item: &mut ProductBacklogItem) -> StoreResult<()> {
+        item.assignee = AssignmentService::assignee(state, &item.project_id, ProductBacklogItem::KIND, &item.id).await?;
+
+        Ok(())
+    }
+
+    // The points of the sprints are the sum of the points of their items,
+    // and the burndown follows when the items are done
+    async fn patched(state: &AppState, item: &ProductBacklogItem) -> StoreResult<()> {
+        SprintItemService::track(state, item).await?;
+
+        for sprint_id in SprintItemService::sprints_of(state, &item.project_id, &item.id).await? {
+            SprintItemService::rescope(state, &item.project_id, &sprint_id, &item.id, item.points.unwrap_or(0)).await?;
+            SprintItemService::recompute_points(state, &item.project_id, &sprint_id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn detach(state: &AppState, item: &ProductBacklogItem) -> StoreResult<Relations> {
+        let mut relations = Relations::default();
+
+        relations.keep(&AssignmentService::unlink(state, |assignment| {
+            assignment.project_id == item.project_id && assignment.kind == ProductBacklogItem::KIND && assignment.entity_id == item.id
+        }).await?);
+
+        let links = SprintItemService::unlink(state, &item.project_id, |link| link.item_id == item.id).await?;
+
+        for link in &links {
+            SprintItemService::rescope(state, &item.project_id, &link.sprint_id, &item.id, 0).await?;
+            SprintItemService::recompute_points(state, &item.project_id, &link.sprint_id).await?;
+        }
+
+        relations.keep(&links);
+
+        Ok(relations)
+    }
+
+    async fn attach(state: &AppState, _item: &ProductBacklogItem, relations: &Relations) -> StoreResult<()> {
+        AssignmentService::relink(state, relations.kept()?).await?;
+        SprintItemService::relink(state, relations.kept()?).await
     }
-}
\ No newline at end of file
+}
@@ -1,43 +1,47 @@
-use std::str::FromStr;
-
-use actix_web::{HttpResponse, web};
+use actix_web::web;
 use async_trait::async_trait;
-use google_sheets4::api::{ClearValuesRequest, ValueRange};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    config::google_sheet_authenticator::get_credentials,
-    interface::crudy::CRUD,
+    config::app_state::AppState,
+    interface::{crudy::{ApiResult, ProjectScoped, CRUD}, resource::Resource, sheet_entity::SheetEntity, store::StoreResult},
     model::{
-        self,
+        response::Response,
         project::{InsertProjectData, Project},
         spreadsheet::{DataRange, Patch},
     },
+    routes::{register_crud, register_resource, Router},
     util::{
-        cons::{PROJECTS_SHEET_NAME, SPREADSHEET_ID},
-        util::{extract_num, Status},
+        error::AppError,
+        row::{cells, is_empty, set},
     },
 };
 
-use super::spreadsheet::{create_id, create_sheet};
+use super::{audit::AuditService, member::MemberService, spreadsheet::{locate_cell, read_row}, trash::TrashService, workflow::check_transition};
 
 pub struct ProjectService;
 
+impl ProjectService {
+    pub fn configure(router: &mut Router) {
+        register_crud::<Self, _>(router, "/projects", "/project");
+        register_resource::<Self>(router, "/projects/{id}");
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct EmptyParams;
 
+impl ProjectScoped for EmptyParams {}
+
 #[async_trait]
 impl CRUD for ProjectService {
+    type Entity = Project;
     type CreatePayload = InsertProjectData;
     type ReadPayload = EmptyParams;
     type UpdatePayload = Patch;
     type DeletePayload = DataRange;
 
-    async fn create(data: web::Json<Self::CreatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
-
+    async fn create(state: web::Data<AppState>, data: web::Json<Self::CreatePayload>) -> ApiResult<Self::Entity> {
         let InsertProjectData {
             denomination,
             description,
@@ -46,15 +50,16 @@ impl CRUD for ProjectService {
             created_at,
         } = data.into_inner();
 
-        let mut new_id = create_id(&PROJECTS_SHEET_NAME, "A", None).await.unwrap();
+        MemberService::check_member(&state, "owner", &owner).await?;
 
-        if new_id == "null" {
-            new_id = "P1".to_string()
-        }
+        state.store
+            .ensure_sheet(state.sheet())
+            .await?;
 
-        let index = extract_num(new_id.as_str());
+        let new_id = state.ids.next::<Project>(state.store.as_ref(), None).await?;
 
-        let full_range = format!("{}!A{}:G{}", &PROJECTS_SHEET_NAME.to_string(), index, index);
+        // The row is chosen by the store, after the last row of the entity
+        let full_range = format!("{}!A1:G1", state.sheet());
 
         let updated_at = created_at.clone();
 
@@ -68,147 +73,72 @@ impl CRUD for ProjectService {
             updated_at.to_string(),
         ];
 
-        let request = ValueRange {
-            major_dimension: Some("ROWS".to_string()),
-            range: Some(full_range.clone()),
-            values: Some(vec![
-                project_data_vector
-                    .iter()
-                    .map(|element| Into::into(element.as_str()))
-                    .collect(),
-            ]),
-        };
-
-        create_sheet(&PROJECTS_SHEET_NAME).await;
-
-        sheet_credentials
-            .spreadsheets()
-            .values_append(request, &SPREADSHEET_ID, full_range.as_str())
-            .value_input_option("USER_ENTERED")
-            .doit()
-            .await
-            .expect("Could not insert the data in the sheet");
-
-        HttpResponse::Ok().json(model::response::Response::<String> {
-            message: "Data was inserted in the sheet successfully!",
-            content: None,
-            error_bug: None
-        })
-    }
+        let row = state.store.append(full_range.as_str(), project_data_vector.clone()).await?;
 
-    async fn read(_param: Option<web::Query<Self::ReadPayload>>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
-
-        let full_range = format!("{}!A:G", &PROJECTS_SHEET_NAME.to_string());
-
-        match sheet_credentials
-            .spreadsheets()
-            .values_get(&SPREADSHEET_ID, full_range.as_ref())
-            .doit()
-            .await
-        {
-            Ok((_, value_range)) => {
-                let values = value_range.values.unwrap_or_default();
-                
-                // Filter empty rows
-                let rows: Vec<Project> = values
-                    .into_iter()
-                    .enumerate()
-                    .filter_map(|(index, row)| {
-                        Some(Project {
-                            id: row[0].as_str()?.to_string(),
-                            denomination: row[1].as_str()?.to_string(),
-                            description: row[2].as_str()?.to_string(),
-                            owner: row[3].as_str()?.to_string(),
-                            status: Status::from_str(row[4].as_str()?).ok()?,
-                            created_at: row[5].as_str()?.parse().ok()?,
-                            updated_at: row[6].as_str()?.parse().ok()?,
-
-                            row: (format!("A{}:G{}", index + 1, index + 1))
-                        })
-                    })
-                    .collect();
-                
-                return HttpResponse::Ok().json(model::response::Response {
-                    message: "Data was obtained successfully",
-                    content: Some(rows),
-                    error_bug: None,
-                });
-            }
-            Err(err) => {
-                return HttpResponse::InternalServerError().json(model::response::Response::<String> {
-                    message: "Error reading the sheet",
-                    content: None,
-                    error_bug: Some(vec![err.to_string()])
-                });
-            }
-        }
+        let project = Project::from_row(&cells(&project_data_vector), Project::row_label(row))?;
+
+        AuditService::entity(&state, None, Some(&project)).await?;
+
+        Ok(web::Json(Response::ok("Data was inserted in the sheet successfully!", Some(vec![project]))))
     }
 
-    async fn update(data: web::Json<Self::UpdatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+    async fn read(state: web::Data<AppState>, _param: Option<web::Query<Self::ReadPayload>>) -> ApiResult<Self::Entity> {
+        let full_range = format!("{}!A:G", state.sheet());
 
-        let Patch { content, cell } = data.into_inner();
+        let values = state.store.read(full_range.as_str()).await?;
+
+        // Filter empty rows
+        let rows: Vec<Project> = values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row))
+            .map(|(index, row)| Project::from_row(&row, Project::row_label(index + 1)))
+            .collect::<Result<Vec<Project>, AppError>>()?;
 
-        let full_range = format!("{}!{}:{}", &PROJECTS_SHEET_NAME.to_string(), cell, cell);
-
-        let project_data_vector = vec![content];
-
-        let request = ValueRange {
-            major_dimension: Some("ROWS".to_string()),
-            range: Some(full_range.clone()),
-            values: Some(vec![
-                project_data_vector
-                    .iter()
-                    .map(|element| Into::into(element.as_str()))
-                    .collect(),
-            ]),
-        };
-
-        sheet_credentials
-            .spreadsheets()
-            .values_update(request, &SPREADSHEET_ID, full_range.as_str())
-            .value_input_option("USER_ENTERED")
-            .doit()
-            .await
-            .expect("Could not update the data in the sheet");
-
-        HttpResponse::Ok().json(model::response::Response::<String> {
-            message: "Data was updated in the sheet successfully!",
-            content: None,
-            error_bug: None
-        })
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(rows))))
     }
 
-    async fn delete(data: web::Json<Self::DeletePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+    async fn update(state: web::Data<AppState>, data: web::Json<Self::UpdatePayload>) -> ApiResult<Self::Entity> {
+        let Patch { content, cell } = data.into_inner();
+
+        let (row, offset) = locate_cell(&cell, ("A", "G"))?;
+        let mut values = read_row(state.store.as_ref(), state.sheet(), ("A", "G"), row).await?;
+        let current = Project::from_row(&values, Project::row_label(row))?;
+
+        // The row is validated before writing the cell
+        set(&mut values, offset, content.clone());
+        let project = Project::from_row(&values, Project::row_label(row)).map_err(AppError::into_validation)?;
+
+        check_transition(&state.settings.workflow, &current, &project)?;
+        <Self as Resource>::check(&state, Some(&current), &project).await?;
+
+        let full_range = format!("{}!{}:{}", state.sheet(), cell, cell);
+
+        state.store.update(full_range.as_str(), vec![content]).await?;
+
+        AuditService::entity(&state, Some(&current), Some(&project)).await?;
+
+        Ok(web::Json(Response::ok("Data was updated in the sheet successfully!", Some(vec![project]))))
+    }
 
+    async fn delete(state: web::Data<AppState>, data: web::Json<Self::DeletePayload>) -> ApiResult<Self::Entity> {
         let DataRange { range } = data.into_inner();
 
-        let range_str = format!(
-            "{}!{}:{}",
-            &PROJECTS_SHEET_NAME.to_string(),
-            range.0,
-            range.1
-        );
-
-        sheet_credentials
-            .spreadsheets()
-            .values_clear(ClearValuesRequest::default(), &SPREADSHEET_ID, &range_str)
-            .doit()
-            .await
-            .expect("Could not removed the data in the sheet");
-
-        HttpResponse::Ok().json(model::response::Response::<String> {
-            message: "Project was removed successfully!",
-            content: None,
-            error_bug: None
-        })
+        TrashService::clear::<Self>(&state, &range.0, &range.1).await?;
+
+        Ok(web::Json(Response::ok("Project was removed successfully!", None)))
+    }
+}
+
+#[async_trait]
+impl Resource for ProjectService {
+    type Entity = Project;
+    type Path = String;
+
+    async fn check(state: &AppState, current: Option<&Project>, project: &Project) -> StoreResult<()> {
+        match current {
+            Some(current) if current.owner == project.owner => Ok(()),
+            _ => MemberService::check_member(state, "owner", &project.owner).await
+        }
     }
 }
@@ -1,38 +1,55 @@
-use std::str::FromStr;
-
-use actix_web::{HttpResponse, web};
+use actix_web::web;
 use async_trait::async_trait;
-use google_sheets4::api::{ClearValuesRequest, ValueRange};
 
 use crate::{
-    config::google_sheet_authenticator::get_credentials,
-    interface::crudy::CRUD,
+    config::app_state::AppState,
+    interface::{crudy::{ApiResult, CRUD}, resource::Resource, sheet_entity::SheetEntity, store::StoreResult},
     model::{
-        self,
-        spreadsheet::{DataRange, Patch},
+        response::Response,
+        spreadsheet::{DataRange, FieldChanges, Patch},
         sprint::{InsertSprint, Sprint},
+        trash::Relations,
     },
-    service::spreadsheet::create_id,
+    service::{audit::AuditService, member::MemberService, spreadsheet::{locate_cell, read_row}, sprint_item::SprintItemService, trash::TrashService, workflow::check_transition},
+    routes::{register_crud, register_resource, Router},
     util::{
-        cons::{PROJECTS_SHEET_NAME, SPREADSHEET_ID},
-        util::{extract_num, Identificator, Status},
+        error::AppError,
+        row::{cells, is_empty, set, text},
+        util::{unix_now, Identificator, Status},
     },
 };
 
 pub struct SprintService;
 
+impl SprintService {
+    pub fn configure(router: &mut Router) {
+        register_crud::<Self, _>(router, "/sprints", "/sprint");
+        register_resource::<Self>(router, "/projects/{id}/sprints/{sprint_id}");
+    }
+
+    ///
+    /// It stamps `completedAt` when the sprint gets done and clears it when the sprint is reopened,
+    /// `given` keeps the date sent by the client.
+    fn stamp(current: &Sprint, sprint: &mut Sprint, given: bool) {
+        if given || current.status == sprint.status { return }
+
+        if sprint.status == Status::Done {
+            sprint.completed_at = unix_now();
+        } else if current.status == Status::Done {
+            sprint.completed_at = 0;
+        }
+    }
+}
+
 #[async_trait]
 impl CRUD for SprintService {
+    type Entity = Sprint;
     type CreatePayload = InsertSprint;
     type ReadPayload = Identificator;
     type UpdatePayload = Patch;
     type DeletePayload = DataRange;
 
-    async fn create(data: web::Json<Self::CreatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
-
+    async fn create(state: web::Data<AppState>, data: web::Json<Self::CreatePayload>) -> ApiResult<Self::Entity> {
         let InsertSprint {
             project_id,
             epic_id,
@@ -48,27 +65,14 @@ impl CRUD for SprintService {
             created_at,
         } = data.into_inner();
 
-        let updated_at = created_at.clone();
+        MemberService::check_member(&state, "owner", &owner).await?;
 
-        let mut sprint_id = create_id(
-            &PROJECTS_SHEET_NAME,
-            "AR",
-            Some(("AS", project_id.clone().as_str())),
-        )
-        .await
-        .unwrap();
+        let updated_at = created_at.clone();
 
-        if sprint_id == "null" {
-            sprint_id = "S".to_string()
-        }
-        let index = extract_num(sprint_id.clone().as_str());
+        let sprint_id = state.ids.next::<Sprint>(state.store.as_ref(), Some(&project_id)).await?;
 
-        let full_range = format!(
-            "{}!AR{}:BE{}",
-            &PROJECTS_SHEET_NAME.to_string(),
-            index,
-            index
-        );
+        // The row is chosen by the store, after the last row of the entity
+        let full_range = format!("{}!AR1:BE1", state.sheet());
 
         let sprint_data_vector = vec![
             sprint_id,
@@ -87,154 +91,111 @@ impl CRUD for SprintService {
             updated_at.to_string(),
         ];
 
-        let request = ValueRange {
-            major_dimension: Some("ROWS".to_string()),
-            range: Some(full_range.clone()),
-            values: Some(vec![
-                sprint_data_vector
-                    .iter()
-                    .map(|element| Into::into(element.as_str()))
-                    .collect(),
-            ]),
-        };
-
-        sheet_credentials
-            .spreadsheets()
-            .values_append(request, &SPREADSHEET_ID, full_range.as_str())
-            .value_input_option("USER_ENTERED")
-            .doit()
-            .await
-            .expect("Could not insert the data in the sheet");
-
-        HttpResponse::Ok().json(model::response::Response::<InsertSprint> {
-            message: "Data was inserted in the sheet successfully!",
-            content: None,
-            error_bug: None,
-        })
+        let row = state.store.append(full_range.as_str(), sprint_data_vector.clone()).await?;
+
+        let sprint = Sprint::from_row(&cells(&sprint_data_vector), Sprint::row_label(row))?;
+
+        AuditService::entity(&state, None, Some(&sprint)).await?;
+
+        Ok(web::Json(Response::ok("Data was inserted in the sheet successfully!", Some(vec![sprint]))))
     }
 
-    async fn read(param: Option<web::Query<Self::ReadPayload>>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+    async fn read(state: web::Data<AppState>, param: Option<web::Query<Self::ReadPayload>>) -> ApiResult<Self::Entity> {
+        let Identificator { id } = param
+            .ok_or(AppError::Validation("The query parameters are missing or invalid".to_string()))?
+            .into_inner();
 
-        let Identificator { id } = param.unwrap().into_inner();
+        let full_range = format!("{}!AR:BE", state.sheet());
 
-        let full_range = format!("{}!AR:BE", &PROJECTS_SHEET_NAME.to_string());
+        let values = state.store.read(full_range.as_str()).await?;
 
-        match sheet_credentials
-            .spreadsheets()
-            .values_get(&SPREADSHEET_ID, full_range.as_ref())
-            .doit()
-            .await
-        {
-            Ok((_, value_range)) => {
-                let values = value_range.values.unwrap_or_default();
-
-                let rows: Vec<Sprint> = values
-                    .into_iter()
-                    .enumerate()
-                    .filter(|(_, row)| row[1].to_string().replace("\"", "") == id.replace("\"", ""))
-                    .filter_map(|(index, row)| {
-                        Some(Sprint {
-                            id: row[0].as_str()?.to_string(),
-                            project_id: row[1].as_str()?.to_string(),
-                            epic_id: row[2].as_str()?.to_string(),
-                            denomination: row[3].as_str()?.to_string(),
-                            description: Some(row[4].as_str()?.to_string()),
-                            goal: row[5].as_str()?.to_string(),
-                            owner: row[6].as_str()?.to_string(),
-                            estimated_points: row[7].as_str()?.parse::<i64>().ok()?,
-                            status: Status::from_str(row[8].as_str()?).ok().unwrap(),
-                            completed_at: row[9].as_str()?.parse::<i64>().ok()?,
-                            start_date: row[10].as_str()?.parse::<i64>().ok()?,
-                            end_date: row[11].as_str()?.parse::<i64>().ok()?,
-                            created_at: row[12].as_str()?.parse::<i64>().ok()?,
-                            updated_at: row[13].as_str()?.parse::<i64>().ok()?,
-
-                            row: format!("AR{}:BE{}", index, index)
-                        })
-                    })
-                    .collect();
-
-                return HttpResponse::Ok().json(model::response::Response::<Sprint> {
-                    message: "Data was obtained successfully",
-                    content: Some(rows),
-                    error_bug: None,
-                });
+        // Filter empty rows
+        let rows: Vec<Sprint> = values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row) && text(row, 1) == id.replace("\"", ""))
+            .map(|(index, row)| Sprint::from_row(&row, Sprint::row_label(index + 1)))
+            .collect::<Result<Vec<Sprint>, AppError>>()?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(rows))))
+    }
+
+    async fn update(state: web::Data<AppState>, data: web::Json<Self::UpdatePayload>) -> ApiResult<Self::Entity> {
+        let Patch { content, cell } = data.into_inner();
+
+        let (row, offset) = locate_cell(&cell, ("AR", "BE"))?;
+        let mut values = read_row(state.store.as_ref(), state.sheet(), ("AR", "BE"), row).await?;
+        let current = Sprint::from_row(&values, Sprint::row_label(row))?;
+
+        // The row is validated before writing the cell
+        set(&mut values, offset, content.clone());
+        let mut sprint = Sprint::from_row(&values, Sprint::row_label(row)).map_err(AppError::into_validation)?;
+
+        check_transition(&state.settings.workflow, &current, &sprint)?;
+        <Self as Resource>::check(&state, Some(&current), &sprint).await?;
+        Self::stamp(&current, &mut sprint, false);
+
+        // `completedAt` is written with the status
+        match sprint.completed_at == current.completed_at {
+            true => {
+                let full_range = format!("{}!{}:{}", state.sheet(), cell, cell);
+                state.store.update(full_range.as_str(), vec![content]).await?;
             }
-            Err(err) => {
-                return HttpResponse::InternalServerError().json(model::response::Response::<Sprint> {
-                    message: "Error reading the sheet",
-                    content: None,
-                    error_bug: Some(vec![err.to_string()]),
-                });
+            false => {
+                let full_range = format!("{}!AR{}:BE{}", state.sheet(), row, row);
+                state.store.update(full_range.as_str(), sprint.to_row()).await?;
             }
         }
+
+        AuditService::entity(&state, Some(&current), Some(&sprint)).await?;
+
+        Ok(web::Json(Response::ok("Data was updated in the sheet successfully!", Some(vec![sprint]))))
     }
 
-    async fn update(data: web::Json<Self::UpdatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+    async fn delete(state: web::Data<AppState>, data: web::Json<Self::DeletePayload>) -> ApiResult<Self::Entity> {
+        let DataRange { range } = data.into_inner();
 
-        let Patch { content, cell } = data.into_inner();
+        TrashService::clear::<Self>(&state, &range.0, &range.1).await?;
 
-        let full_range = format!("{}!{}:{}", &PROJECTS_SHEET_NAME.to_string(), cell, cell);
-
-        let item_partial_data = vec![content];
-
-        let request = ValueRange {
-            major_dimension: Some("ROWS".to_string()),
-            range: Some(full_range.clone()),
-            values: Some(vec![
-                item_partial_data
-                    .iter()
-                    .map(|element| Into::into(element.as_str()))
-                    .collect(),
-            ]),
-        };
-
-        sheet_credentials
-            .spreadsheets()
-            .values_update(request, &SPREADSHEET_ID, full_range.as_str())
-            .value_input_option("USER_ENTERED")
-            .doit()
-            .await
-            .expect("Could not update the data in the sheet");
-
-        HttpResponse::Ok().json(model::response::Response::<Sprint> {
-            message: "Data was updated in the sheet successfully!",
-            content: None,
-            error_bug: None,
-        })
+        Ok(web::Json(Response::ok("It was removed successfully", None)))
     }
+}
 
-    async fn delete(data: web::Json<Self::DeletePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+#[async_trait]
+impl Resource for SprintService {
+    type Entity = Sprint;
+    type Path = (String, String);
+
+    // `estimatedPoints` is the sum of the points of the items, once the sprint has some
+    async fn check(state: &AppState, current: Option<&Sprint>, sprint: &Sprint) -> StoreResult<()> {
+        if current.is_some_and(|current| current.estimated_points != sprint.estimated_points)
+            && !SprintItemService::committed(state, &sprint.project_id, &sprint.id).await?.is_empty()
+        {
+            return Err(AppError::Conflict(format!(
+                "The estimated points of the sprint {} are the sum of the points of its items",
+                sprint.id
+            )))
+        }
 
-        let DataRange { range } = data.into_inner();
+        match current {
+            Some(current) if current.owner == sprint.owner => Ok(()),
+            _ => MemberService::check_member(state, "owner", &sprint.owner).await
+        }
+    }
+
+    fn prepare(current: &Sprint, sprint: &mut Sprint, changes: &FieldChanges) {
+        Self::stamp(current, sprint, changes.contains_key("completedAt"));
+    }
+
+    async fn detach(state: &AppState, sprint: &Sprint) -> StoreResult<Relations> {
+        let mut relations = Relations::default();
+
+        relations.keep(&SprintItemService::unlink(state, &sprint.project_id, |link| link.sprint_id == sprint.id).await?);
+
+        Ok(relations)
+    }
 
-        let range_str = format!(
-            "{}!{}:{}",
-            &PROJECTS_SHEET_NAME.to_string(),
-            range.0,
-            range.1
-        );
-
-        sheet_credentials
-            .spreadsheets()
-            .values_clear(ClearValuesRequest::default(), &SPREADSHEET_ID, &range_str)
-            .doit()
-            .await
-            .expect("Could not removed the data in the sheet");
-
-        HttpResponse::Ok().json(model::response::Response::<Sprint> {
-            message: "It was removed successfully",
-            content: None,
-            error_bug: None,
-        })
+    async fn attach(state: &AppState, _sprint: &Sprint, relations: &Relations) -> StoreResult<()> {
+        SprintItemService::relink(state, relations.kept()?).await
     }
-}
\ No newline at end of file
+}
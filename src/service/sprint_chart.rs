@@ -0,0 +1,178 @@
+use actix_web::{http::Method, web};
+
+use crate::{
+    config::app_state::AppState,
+    interface::{crudy::ApiResult, sheet_entity::SheetEntity},
+    model::{
+        chart::{BurndownDay, BurnupDay, ScopeChange, SprintChart},
+        product_backlog::ProductBacklogItem,
+        response::Response,
+        sprint::Sprint,
+        sprint_item::{ScopeEvent, ScopeKind, SprintItem},
+    },
+    routes::{guarded, Router},
+    util::{error::AppError, util::unix_now},
+};
+
+use super::{spreadsheet::find_row, sprint_item::SprintItemService};
+
+const DAY: i64 = 86_400;
+
+/// Longest sprint charted, a longer one is most likely a wrong date.
+const MAX_DAYS: i64 = 366;
+
+///
+/// Points of a day, `None` for the days to come.
+///
+/// - `ideal_remaining` and `ideal_completed`: The committed points burnt at a constant pace.
+struct DayPoints {
+    date: i64,
+    ideal_remaining: f64,
+    ideal_completed: f64,
+    scope: Option<i64>,
+    completed: Option<i64>
+}
+
+///
+/// Burndown and burnup charts of a sprint, computed from the changes of its scope and the items committed to it
+/// (see `SprintItemService`).
+pub struct SprintChartService;
+
+impl SprintChartService {
+    pub fn configure(router: &mut Router) {
+        router
+            .add(Method::GET, "/projects/{id}/sprints/{sprint_id}/burndown", guarded::<Sprint>(web::route().to(Self::burndown)))
+            .add(Method::GET, "/projects/{id}/sprints/{sprint_id}/burnup", guarded::<Sprint>(web::route().to(Self::burnup)));
+    }
+
+    pub async fn burndown(state: web::Data<AppState>, path: web::Path<(String, String)>) -> ApiResult<SprintChart<BurndownDay>> {
+        let (project_id, sprint_id) = path.into_inner();
+
+        let chart = Self::chart(&state, &project_id, &sprint_id, |day| BurndownDay {
+            date: day.date,
+            ideal: day.ideal_remaining,
+            remaining: day.scope.zip(day.completed).map(|(scope, completed)| scope - completed)
+        }).await?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(vec![chart]))))
+    }
+
+    pub async fn burnup(state: web::Data<AppState>, path: web::Path<(String, String)>) -> ApiResult<SprintChart<BurnupDay>> {
+        let (project_id, sprint_id) = path.into_inner();
+
+        let chart = Self::chart(&state, &project_id, &sprint_id, |day| BurnupDay {
+            date: day.date,
+            ideal: day.ideal_completed,
+            completed: day.completed,
+            scope: day.scope
+        }).await?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(vec![chart]))))
+    }
+
+    async fn chart<D>(state: &AppState, project_id: &str, sprint_id: &str, day: impl Fn(DayPoints) -> D) -> Result<SprintChart<D>, AppError> {
+        let (row, cells) = find_row::<Sprint>(state.store.as_ref(), state.sheet(), &[project_id.to_string(), sprint_id.to_string()]).await?;
+        let sprint = Sprint::from_row(&cells, Sprint::row_label(row))?;
+
+        let committed = SprintItemService::committed(state, project_id, sprint_id).await?;
+        let events = SprintItemService::scope_events(state, project_id, sprint_id).await?;
+
+        Self::compute(&sprint, &committed, &events, unix_now(), day)
+    }
+
+    ///
+    /// It computes the points of every day of the sprint, `now` decides which days are to come.
+    ///
+    /// The scope is replayed from `events`, the completed points are the ones recorded when the items `committed` now
+    /// were completed (their current points for the items completed before they were recorded).
+    fn compute<D>(
+        sprint: &Sprint,
+        committed: &[(SprintItem, ProductBacklogItem)],
+        events: &[ScopeEvent],
+        now: i64,
+        day: impl Fn(DayPoints) -> D
+    ) -> Result<SprintChart<D>, AppError> {
+        let first_day = sprint.start_date.div_euclid(DAY) * DAY;
+        let last_day = sprint.end_date.div_euclid(DAY) * DAY;
+
+        if last_day < first_day {
+            return Err(AppError::Validation(format!("The sprint {} ends before it starts", sprint.id)))
+        }
+
+        if (last_day - first_day) / DAY >= MAX_DAYS {
+            return Err(AppError::Validation(format!("The sprint {} is longer than {} days", sprint.id, MAX_DAYS)))
+        }
+
+        let (scope_events, completions): (Vec<&ScopeEvent>, Vec<&ScopeEvent>) = events
+            .iter()
+            .partition(|event| event.kind == ScopeKind::Scope);
+
+        // The last completion of the item is the one of its relation
+        let points = |item: &ProductBacklogItem| completions
+            .iter()
+            .rev()
+            .find(|event| event.item_id == item.id)
+            .map_or(item.points.unwrap_or(0), |event| event.points);
+
+        let scope_until = |date: i64| scope_events.iter().filter(|event| event.date < date).map(|event| event.points).sum::<i64>();
+
+        // Changes during the first day are the commitment, the later ones change the scope
+        let committed_points = scope_until(first_day + DAY);
+
+        let mut scope_changes = scope_events
+            .iter()
+            .filter(|event| event.date >= first_day + DAY)
+            .map(|event| ScopeChange { date: event.date, item_id: event.item_id.clone(), points: event.points })
+            .collect::<Vec<ScopeChange>>();
+
+        scope_changes.sort_by_key(|change| change.date);
+
+        let day_count = (last_day - first_day) / DAY + 1;
+
+        let days = (0..day_count)
+            .map(|index| {
+                let date = first_day + index * DAY;
+
+                let ideal_remaining = match day_count {
+                    1 => 0.0,
+                    _ => committed_points as f64 * (day_count - 1 - index) as f64 / (day_count - 1) as f64
+                };
+
+                let end_of_day = date + DAY;
+
+                let completed_until = |date: i64| committed
+                    .iter()
+                    .filter(|(link, _)| link.completed_at.is_some_and(|completed_at| completed_at < date))
+                    .map(|(_, item)| points(item))
+                    .sum::<i64>();
+
+                let (scope, completed) = match date > now {
+                    true => (None, None),
+                    false => (Some(scope_until(end_of_day)), Some(completed_until(end_of_day)))
+                };
+
+                day(DayPoints {
+                    date,
+                    ideal_remaining: round(ideal_remaining),
+                    ideal_completed: round(committed_points as f64 - ideal_remaining),
+                    scope,
+                    completed
+                })
+            })
+            .collect();
+
+        Ok(SprintChart {
+            sprint_id: sprint.id.clone(),
+            start_date: sprint.start_date,
+            end_date: sprint.end_date,
+            committed_points,
+            days,
+            scope_changes
+        })
+    }
+}
+
+/// Rounded to two decimals.
+fn round(points: f64) -> f64 {
+    (points * 100.0).round() / 100.0
+}
@@ -0,0 +1,500 @@
+use std::collections::HashMap;
+
+use actix_web::{http::Method, web};
+
+use crate::{
+    config::app_state::AppState,
+    interface::{crudy::ApiResult, relation::Relation, sheet_entity::SheetEntity, store::StoreResult},
+    model::{
+        product_backlog::ProductBacklogItem,
+        response::Response,
+        sprint::Sprint,
+        sprint_item::{AddSprintItem, ScopeEvent, ScopeKind, SprintItem, SprintItemCompletion, SprintItemOrder},
+    },
+    routes::{guarded, Router},
+    util::{
+        error::AppError,
+        row::{is_empty, text},
+        util::{unix_now, ItemStatus, Status},
+    },
+};
+
+use super::{audit::AuditService, spreadsheet::{find_row, rows_of}};
+
+///
+/// Backlog items committed to the sprints, the relation is kept in the `SprintItems` sheet
+/// and `estimatedPoints` of the sprint is the sum of the points of its items.
+///
+/// Every change of the points of a sprint is recorded in the `ScopeChanges` sheet, the charts are computed from them.
+pub struct SprintItemService;
+
+impl SprintItemService {
+    pub fn configure(router: &mut Router) {
+        let path = "/projects/{id}/sprints/{sprint_id}/items";
+
+        router
+            .add(Method::GET, path, guarded::<Sprint>(web::route().to(Self::list)))
+            .add(Method::POST, path, guarded::<Sprint>(web::route().to(Self::add)))
+            .add(Method::PUT, path, guarded::<Sprint>(web::route().to(Self::reorder)))
+            .add(Method::PATCH, "/projects/{id}/sprints/{sprint_id}/items/{item_id}", guarded::<Sprint>(web::route().to(Self::complete)))
+            .add(Method::DELETE, "/projects/{id}/sprints/{sprint_id}/items/{item_id}", guarded::<Sprint>(web::route().to(Self::remove)));
+    }
+
+    ///
+    /// It returns the backlog items of the sprint in order.
+    pub async fn list(state: web::Data<AppState>, path: web::Path<(String, String)>) -> ApiResult<ProductBacklogItem> {
+        let (project_id, sprint_id) = path.into_inner();
+
+        find_row::<Sprint>(state.store.as_ref(), state.sheet(), &[project_id.clone(), sprint_id.clone()]).await?;
+
+        let items = Self::items(&state, &project_id, &sprint_id).await?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(items))))
+    }
+
+    ///
+    /// It adds the item at the end of the sprint, an item can only be in one sprint that is not done.
+    pub async fn add(state: web::Data<AppState>, path: web::Path<(String, String)>, data: web::Json<AddSprintItem>) -> ApiResult<ProductBacklogItem> {
+        let (project_id, sprint_id) = path.into_inner();
+        let AddSprintItem { item_id } = data.into_inner();
+
+        let sprint = Self::sprint(&state, &project_id, &sprint_id).await?;
+
+        if sprint.status == Status::Done {
+            return Err(AppError::Conflict(format!("The sprint {} is done, its items can not be changed", sprint_id)))
+        }
+
+        let (item_row, cells) = find_row::<ProductBacklogItem>(state.store.as_ref(), state.sheet(), &[project_id.clone(), item_id.clone()]).await?;
+        let item = ProductBacklogItem::from_row(&cells, ProductBacklogItem::row_label(item_row))?;
+
+        match Self::holder(&state, &project_id, &item_id).await? {
+            Some(holder) if holder == sprint_id => {
+                return Err(AppError::Conflict(format!("The item {} is already in the sprint {}", item_id, sprint_id)))
+            },
+            Some(holder) => {
+                return Err(AppError::Conflict(format!("The item {} is already in the sprint {}, which is not done", item_id, holder)))
+            },
+            None => {}
+        }
+
+        let links = Self::links(&state, &project_id).await?;
+
+        let position = links
+            .iter()
+            .filter(|(_, link)| link.sprint_id == sprint_id)
+            .map(|(_, link)| link.position)
+            .max()
+            .unwrap_or(0) + 1;
+
+        let link = SprintItem {
+            project_id: project_id.clone(),
+            sprint_id: sprint_id.clone(),
+            item_id,
+            position,
+            added_at: unix_now(),
+            completed_at: None
+        };
+
+        let sheet = &state.settings.sheets.sprint_items;
+        let (first_col, last_col) = SprintItem::COLUMNS;
+
+        state.ensure_sheet(sheet).await?;
+        state.store.append(format!("{}!{}1:{}1", sheet, first_col, last_col).as_str(), link.to_row()).await?;
+
+        AuditService::relation(&state, None, Some(&link)).await?;
+
+        Self::rescope(&state, &project_id, &sprint_id, &item.id, item.points.unwrap_or(0)).await?;
+        Self::recompute_points(&state, &project_id, &sprint_id).await?;
+
+        let items = Self::items(&state, &project_id, &sprint_id).await?;
+
+        Ok(web::Json(Response::ok("The item was added to the sprint successfully!", Some(items))))
+    }
+
+    ///
+    /// It sorts the items of the sprint, the body must contain each of them once.
+    pub async fn reorder(state: web::Data<AppState>, path: web::Path<(String, String)>, data: web::Json<SprintItemOrder>) -> ApiResult<ProductBacklogItem> {
+        let (project_id, sprint_id) = path.into_inner();
+        let SprintItemOrder { item_ids } = data.into_inner();
+
+        Self::sprint(&state, &project_id, &sprint_id).await?;
+
+        let links = Self::links(&state, &project_id)
+            .await?
+            .into_iter()
+            .filter(|(_, link)| link.sprint_id == sprint_id)
+            .collect::<Vec<(usize, SprintItem)>>();
+
+        let mut current = links.iter().map(|(_, link)| link.item_id.clone()).collect::<Vec<String>>();
+        let mut requested = item_ids.clone();
+        current.sort();
+        requested.sort();
+
+        if current != requested {
+            return Err(AppError::Validation(format!(
+                "The order must contain every item of the sprint once: {}",
+                current.join(", ")
+            )))
+        }
+
+        let sheet = &state.settings.sheets.sprint_items;
+
+        for (index, item_id) in item_ids.iter().enumerate() {
+            let position = index as i64 + 1;
+
+            let Some((row, link)) = links.iter().find(|(_, link)| link.item_id == *item_id) else { continue };
+
+            if link.position != position {
+                let full_range = format!("{}!D{}:D{}", sheet, row, row);
+                state.store.update(full_range.as_str(), vec![position.to_string()]).await?;
+
+                AuditService::relation(&state, Some(link), Some(&SprintItem { position, ..link.clone() })).await?;
+            }
+        }
+
+        let items = Self::items(&state, &project_id, &sprint_id).await?;
+
+        Ok(web::Json(Response::ok("The items of the sprint were sorted successfully!", Some(items))))
+    }
+
+    ///
+    /// It sets when the item was completed, the burndown of the sprint is computed from it.
+    pub async fn complete(state: web::Data<AppState>, path: web::Path<(String, String, String)>, data: web::Json<SprintItemCompletion>) -> ApiResult<ProductBacklogItem> {
+        let (project_id, sprint_id, item_id) = path.into_inner();
+        let SprintItemCompletion { completed_at } = data.into_inner();
+
+        let sprint = Self::sprint(&state, &project_id, &sprint_id).await?;
+
+        if sprint.status == Status::Done {
+            return Err(AppError::Conflict(format!("The sprint {} is done, its items can not be changed", sprint_id)))
+        }
+
+        let (row, current) = Self::links(&state, &project_id)
+            .await?
+            .into_iter()
+            .find(|(_, link)| link.sprint_id == sprint_id && link.item_id == item_id)
+            .ok_or(AppError::NotFound(format!("The item {} is not in the sprint {}", item_id, sprint_id)))?;
+
+        let link = SprintItem { completed_at, ..current.clone() };
+
+        let (first_col, last_col) = SprintItem::COLUMNS;
+        let full_range = format!("{}!{}{}:{}{}", state.settings.sheets.sprint_items, first_col, row, last_col, row);
+
+        state.store.update(full_range.as_str(), link.to_row()).await?;
+
+        AuditService::relation(&state, Some(&current), Some(&link)).await?;
+
+        let items = Self::items(&state, &project_id, &sprint_id).await?;
+
+        if link.completed_at != current.completed_at {
+            let points = items.iter().find(|item| item.id == item_id).and_then(|item| item.points).unwrap_or(0);
+
+            Self::record_completion(&state, &link, points).await?;
+        }
+
+        Ok(web::Json(Response::ok("Data was updated in the sheet successfully!", Some(items))))
+    }
+
+    ///
+    /// It removes the item from the sprint, the item stays in the product backlog.
+    pub async fn remove(state: web::Data<AppState>, path: web::Path<(String, String, String)>) -> ApiResult<ProductBacklogItem> {
+        let (project_id, sprint_id, item_id) = path.into_inner();
+
+        let sprint = Self::sprint(&state, &project_id, &sprint_id).await?;
+
+        if sprint.status == Status::Done {
+            return Err(AppError::Conflict(format!("The sprint {} is done, its items can not be changed", sprint_id)))
+        }
+
+        let removed = Self::unlink(&state, &project_id, |link| link.sprint_id == sprint_id && link.item_id == item_id).await?;
+
+        if removed.is_empty() {
+            return Err(AppError::NotFound(format!("The item {} is not in the sprint {}", item_id, sprint_id)))
+        }
+
+        Self::rescope(&state, &project_id, &sprint_id, &item_id, 0).await?;
+        Self::recompute_points(&state, &project_id, &sprint_id).await?;
+
+        let items = Self::items(&state, &project_id, &sprint_id).await?;
+
+        Ok(web::Json(Response::ok("The item was removed from the sprint successfully!", Some(items))))
+    }
+
+    ///
+    /// Relations of the project with their row (one based).
+    pub async fn links(state: &AppState, project_id: &str) -> StoreResult<Vec<(usize, SprintItem)>> {
+        let sheet = &state.settings.sheets.sprint_items;
+        let (first_col, last_col) = SprintItem::COLUMNS;
+
+        state.ensure_sheet(sheet).await?;
+
+        let values = state.store.read(format!("{}!{}:{}", sheet, first_col, last_col).as_str()).await?;
+
+        values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row) && text(row, 0) == project_id)
+            .map(|(index, row)| SprintItem::from_row(&row).map(|link| (index + 1, link)))
+            .collect()
+    }
+
+    async fn sprint(state: &AppState, project_id: &str, sprint_id: &str) -> StoreResult<Sprint> {
+        let (row, cells) = find_row::<Sprint>(state.store.as_ref(), state.sheet(), &[project_id.to_string(), sprint_id.to_string()]).await?;
+
+        Sprint::from_row(&cells, Sprint::row_label(row))
+    }
+
+    ///
+    /// Backlog items of the sprint in order.
+    async fn items(state: &AppState, project_id: &str, sprint_id: &str) -> StoreResult<Vec<ProductBacklogItem>> {
+        let committed = Self::committed(state, project_id, sprint_id).await?;
+
+        Ok(committed.into_iter().map(|(_, item)| item).collect())
+    }
+
+    ///
+    /// Relations of the sprint in order with their backlog item, the ones removed from the product backlog are skipped.
+    pub async fn committed(state: &AppState, project_id: &str, sprint_id: &str) -> StoreResult<Vec<(SprintItem, ProductBacklogItem)>> {
+        let mut links = Self::links(state, project_id)
+            .await?
+            .into_iter()
+            .map(|(_, link)| link)
+            .filter(|link| link.sprint_id == sprint_id)
+            .collect::<Vec<SprintItem>>();
+
+        links.sort_by_key(|link| link.position);
+
+        let mut backlog = rows_of::<ProductBacklogItem>(state.store.as_ref(), state.sheet(), project_id)
+            .await?
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect::<HashMap<String, ProductBacklogItem>>();
+
+        Ok(links.into_iter().filter_map(|link| backlog.remove(&link.item_id).map(|item| (link, item))).collect())
+    }
+
+    ///
+    /// It removes the relations that match, returning them.
+    pub async fn unlink(state: &AppState, project_id: &str, matches: impl Fn(&SprintItem) -> bool) -> StoreResult<Vec<SprintItem>> {
+        let sheet = &state.settings.sheets.sprint_items;
+        let (first_col, last_col) = SprintItem::COLUMNS;
+
+        let mut removed = vec![];
+
+        for (row, link) in Self::links(state, project_id).await? {
+            if !matches(&link) { continue }
+
+            let full_range = format!("{}!{}{}:{}{}", sheet, first_col, row, last_col, row);
+            state.store.clear(full_range.as_str()).await?;
+
+            AuditService::relation(state, Some(&link), None).await?;
+
+            removed.push(link);
+        }
+
+        Ok(removed)
+    }
+
+    ///
+    /// Sprint that is not done and contains the item, the one that holds it.
+    async fn holder(state: &AppState, project_id: &str, item_id: &str) -> StoreResult<Option<String>> {
+        for (_, link) in Self::links(state, project_id).await? {
+            if link.item_id != item_id { continue }
+
+            // A relation of a removed sprint does not hold the item
+            match Self::sprint(state, project_id, &link.sprint_id).await {
+                Ok(sprint) if sprint.status != Status::Done => return Ok(Some(link.sprint_id)),
+                Ok(_) | Err(AppError::NotFound(_)) => continue,
+                Err(err) => return Err(err)
+            }
+        }
+
+        Ok(None)
+    }
+
+    ///
+    /// It puts back the relations removed with their item or sprint (see `unlink`) and updates the points of the sprints,
+    /// the ones whose item or sprint is missing, or whose item is held by another sprint since then, are dropped.
+    pub async fn relink(state: &AppState, links: Vec<SprintItem>) -> StoreResult<()> {
+        let sheet = &state.settings.sheets.sprint_items;
+        let (first_col, last_col) = SprintItem::COLUMNS;
+
+        for link in links {
+            let keys = [link.project_id.clone(), link.item_id.clone()];
+
+            let item = match find_row::<ProductBacklogItem>(state.store.as_ref(), state.sheet(), &keys).await {
+                Ok((row, cells)) => ProductBacklogItem::from_row(&cells, ProductBacklogItem::row_label(row))?,
+                Err(AppError::NotFound(_)) => continue,
+                Err(err) => return Err(err)
+            };
+
+            let sprint = match Self::sprint(state, &link.project_id, &link.sprint_id).await {
+                Ok(sprint) => sprint,
+                Err(AppError::NotFound(_)) => continue,
+                Err(err) => return Err(err)
+            };
+
+            // The sprints that are done keep their items, an item is only in one of the others
+            if sprint.status != Status::Done && Self::holder(state, &link.project_id, &link.item_id).await?.is_some() { continue }
+
+            state.ensure_sheet(sheet).await?;
+            state.store.append(format!("{}!{}1:{}1", sheet, first_col, last_col).as_str(), link.to_row()).await?;
+
+            AuditService::relation(state, None, Some(&link)).await?;
+
+            Self::rescope(state, &link.project_id, &link.sprint_id, &item.id, item.points.unwrap_or(0)).await?;
+            Self::recompute_points(state, &link.project_id, &link.sprint_id).await?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Sprints that contain the item.
+    pub async fn sprints_of(state: &AppState, project_id: &str, item_id: &str) -> StoreResult<Vec<String>> {
+        let links = Self::links(state, project_id).await?;
+
+        Ok(links.into_iter().filter(|(_, link)| link.item_id == item_id).map(|(_, link)| link.sprint_id).collect())
+    }
+
+    ///
+    /// It stamps `completedAt` of the relations of the item when it gets done and clears it when it is reopened,
+    /// the relations of the sprints that are done are kept as they were.
+    pub async fn track(state: &AppState, item: &ProductBacklogItem) -> StoreResult<()> {
+        let sheet = &state.settings.sheets.sprint_items;
+        let (first_col, last_col) = SprintItem::COLUMNS;
+        let done = item.status == ItemStatus::Done;
+
+        for (row, link) in Self::links(state, &item.project_id).await? {
+            if link.item_id != item.id || link.completed_at.is_some() == done { continue }
+
+            match Self::sprint(state, &item.project_id, &link.sprint_id).await {
+                Ok(sprint) if sprint.status != Status::Done => {},
+                Ok(_) | Err(AppError::NotFound(_)) => continue,
+                Err(err) => return Err(err)
+            }
+
+            let tracked = SprintItem { completed_at: done.then(unix_now), ..link.clone() };
+
+            let full_range = format!("{}!{}{}:{}{}", sheet, first_col, row, last_col, row);
+            state.store.update(full_range.as_str(), tracked.to_row()).await?;
+
+            AuditService::relation(state, Some(&link), Some(&tracked)).await?;
+
+            Self::record_completion(state, &tracked, item.points.unwrap_or(0)).await?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Changes of the points of the sprint, in the order they were recorded.
+    pub async fn scope_events(state: &AppState, project_id: &str, sprint_id: &str) -> StoreResult<Vec<ScopeEvent>> {
+        let sheet = &state.settings.sheets.scope_changes;
+        let (first_col, last_col) = ScopeEvent::COLUMNS;
+
+        state.ensure_sheet(sheet).await?;
+
+        let values = state.store.read(format!("{}!{}:{}", sheet, first_col, last_col).as_str()).await?;
+
+        values
+            .into_iter()
+            .filter(|row| !is_empty(row) && text(row, 0) == project_id && text(row, 1) == sprint_id)
+            .map(|row| ScopeEvent::from_row(&row))
+            .collect()
+    }
+
+    ///
+    /// It records the change when the points of the item in the sprint are not the ones recorded,
+    /// the sprints that are done are kept as they were.
+    ///
+    /// `points`: Points of the item in the sprint now, `0` once it is removed.
+    pub async fn rescope(state: &AppState, project_id: &str, sprint_id: &str, item_id: &str, points: i64) -> StoreResult<()> {
+        match Self::sprint(state, project_id, sprint_id).await {
+            Ok(sprint) if sprint.status != Status::Done => {},
+            Ok(_) | Err(AppError::NotFound(_)) => return Ok(()),
+            Err(err) => return Err(err)
+        }
+
+        let recorded = Self::scope_events(state, project_id, sprint_id)
+            .await?
+            .iter()
+            .filter(|event| event.item_id == item_id && event.kind == ScopeKind::Scope)
+            .map(|event| event.points)
+            .sum::<i64>();
+
+        if recorded == points { return Ok(()) }
+
+        let event = ScopeEvent {
+            project_id: project_id.to_string(),
+            sprint_id: sprint_id.to_string(),
+            item_id: item_id.to_string(),
+            points: points - recorded,
+            date: unix_now(),
+            kind: ScopeKind::Scope
+        };
+
+        Self::record(state, &event).await
+    }
+
+    ///
+    /// It records the points of the item when the relation is completed, the burndown keeps them
+    /// even if the item is estimated again. Nothing is recorded when it is reopened.
+    async fn record_completion(state: &AppState, link: &SprintItem, points: i64) -> StoreResult<()> {
+        let Some(completed_at) = link.completed_at else { return Ok(()) };
+
+        let event = ScopeEvent {
+            project_id: link.project_id.clone(),
+            sprint_id: link.sprint_id.clone(),
+            item_id: link.item_id.clone(),
+            points,
+            date: completed_at,
+            kind: ScopeKind::Completion
+        };
+
+        Self::record(state, &event).await
+    }
+
+    async fn record(state: &AppState, event: &ScopeEvent) -> StoreResult<()> {
+        let sheet = &state.settings.sheets.scope_changes;
+        let (first_col, last_col) = ScopeEvent::COLUMNS;
+
+        state.ensure_sheet(sheet).await?;
+        state.store.append(format!("{}!{}1:{}1", sheet, first_col, last_col).as_str(), event.to_row()).await?;
+
+        AuditService::relation(state, None, Some(event)).await
+    }
+
+    ///
+    /// It sets `estimatedPoints` of the sprint to the sum of the points of its items.
+    pub async fn recompute_points(state: &AppState, project_id: &str, sprint_id: &str) -> StoreResult<()> {
+        let (row, cells) = match find_row::<Sprint>(state.store.as_ref(), state.sheet(), &[project_id.to_string(), sprint_id.to_string()]).await {
+            Ok(found) => found,
+            Err(AppError::NotFound(_)) => return Ok(()),
+            Err(err) => return Err(err)
+        };
+
+        let current = Sprint::from_row(&cells, Sprint::row_label(row))?;
+        let mut sprint = Sprint::from_row(&cells, Sprint::row_label(row))?;
+
+        let points = Self::items(state, project_id, sprint_id)
+            .await?
+            .iter()
+            .filter_map(|item| item.points)
+            .sum::<i64>();
+
+        if sprint.estimated_points == points { return Ok(()) }
+
+        sprint.estimated_points = points;
+        sprint.updated_at = unix_now();
+
+        let (first_col, last_col) = Sprint::COLUMNS;
+        let full_range = format!("{}!{}{}:{}{}", state.sheet(), first_col, row, last_col, row);
+
+        state.store.update(full_range.as_str(), sprint.to_row()).await?;
+
+        AuditService::entity(state, Some(&current), Some(&sprint)).await?;
+
+        Ok(())
+    }
+}
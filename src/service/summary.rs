@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use actix_web::{http::Method, web};
+
+use crate::{
+    config::app_state::AppState,
+    interface::crudy::ApiResult,
+    model::{
+        epic::Epic,
+        product_backlog::{ItemType, ProductBacklogItem},
+        project::Project,
+        response::Response,
+        sprint::Sprint,
+        summary::{Rollup, Summary, Tally},
+        user_story::UserStoryBox,
+    },
+    routes::{guarded, Router},
+    util::{error::AppError, util::{ItemStatus, Status}},
+};
+
+use super::spreadsheet::entities_in;
+
+/// Every entity is read at once, from the projects to the sprints.
+const RANGE: (&str, &str) = ("A", "BE");
+
+///
+/// Progress of the projects and epics, rolled up from their stories, backlog items and sprints.
+pub struct SummaryService;
+
+impl SummaryService {
+    pub fn configure(router: &mut Router) {
+        router
+            .add(Method::GET, "/projects/{id}/summary", guarded::<Project>(web::route().to(Self::project)))
+            .add(Method::GET, "/projects/{id}/epics/{epic_id}/summary", guarded::<Epic>(web::route().to(Self::epic)));
+    }
+
+    pub async fn project(state: web::Data<AppState>, path: web::Path<String>) -> ApiResult<Summary> {
+        let project_id = path.into_inner();
+
+        let summary = Self::summary(&state, &project_id, None).await?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(vec![summary]))))
+    }
+
+    pub async fn epic(state: web::Data<AppState>, path: web::Path<(String, String)>) -> ApiResult<Summary> {
+        let (project_id, epic_id) = path.into_inner();
+
+        let summary = Self::summary(&state, &project_id, Some(epic_id)).await?;
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(vec![summary]))))
+    }
+
+    async fn summary(state: &AppState, project_id: &str, epic_id: Option<String>) -> Result<Summary, AppError> {
+        let (first_col, last_col) = RANGE;
+        let values = state.store.read(format!("{}!{}:{}", state.sheet(), first_col, last_col).as_str()).await?;
+
+        let in_scope = |entity_epic_id: &str| epic_id.as_deref().is_none_or(|epic_id| epic_id == entity_epic_id);
+
+        match &epic_id {
+            Some(epic_id) => {
+                let epics = entities_in::<Epic>(&values, first_col, project_id)?;
+
+                if !epics.iter().any(|epic| epic.id == *epic_id) {
+                    return Err(AppError::NotFound(format!("There is no data for {}/{}", project_id, epic_id)))
+                }
+            }
+            None => {
+                let projects = entities_in::<Project>(&values, first_col, project_id)?;
+
+                if projects.is_empty() {
+                    return Err(AppError::NotFound(format!("There is no data for {}", project_id)))
+                }
+            }
+        }
+
+        let stories = entities_in::<UserStoryBox>(&values, first_col, project_id)?
+            .into_iter()
+            .filter(|story| in_scope(&story.epic_id))
+            .map(|story| (story.status, story.story_points as i64))
+            .collect::<Vec<(ItemStatus, i64)>>();
+
+        let items = entities_in::<ProductBacklogItem>(&values, first_col, project_id)?
+            .into_iter()
+            .filter(|item| in_scope(&item.epic_id))
+            .collect::<Vec<ProductBacklogItem>>();
+
+        let sprints_in_progress = entities_in::<Sprint>(&values, first_col, project_id)?
+            .into_iter()
+            .filter(|sprint| in_scope(&sprint.epic_id) && sprint.status == Status::InProgress)
+            .map(|sprint| sprint.id)
+            .collect();
+
+        let mut backlog_items_by_kind = [ItemType::Story, ItemType::Bug, ItemType::Task]
+            .iter()
+            .map(|kind| (format!("{:?}", kind), Tally::default()))
+            .collect::<BTreeMap<String, Tally>>();
+
+        for item in &items {
+            let tally = backlog_items_by_kind.entry(format!("{:?}", item.kind)).or_default();
+            tally.count += 1;
+            tally.points += item.points.unwrap_or(0);
+        }
+
+        let open_bugs = items
+            .iter()
+            .filter(|item| item.kind == ItemType::Bug && item.status != ItemStatus::Done)
+            .count();
+
+        Ok(Summary {
+            project_id: project_id.to_string(),
+            epic_id,
+            user_stories: rollup(&stories),
+            backlog_items: rollup(&items.iter().map(|item| (item.status, item.points.unwrap_or(0))).collect::<Vec<_>>()),
+            backlog_items_by_kind,
+            open_bugs,
+            sprints_in_progress
+        })
+    }
+}
+
+///
+/// `entries`: Status and points of every story or item.
+fn rollup(entries: &[(ItemStatus, i64)]) -> Rollup {
+    let mut by_status = [ItemStatus::ToDo, ItemStatus::InProgress, ItemStatus::InReview, ItemStatus::Done, ItemStatus::Blocked]
+        .iter()
+        .map(|status| (format!("{:?}", status), Tally::default()))
+        .collect::<BTreeMap<String, Tally>>();
+
+    for (status, points) in entries {
+        let tally = by_status.entry(format!("{:?}", status)).or_default();
+        tally.count += 1;
+        tally.points += points;
+    }
+
+    let done = by_status.get("Done").cloned().unwrap_or_default();
+    let points = entries.iter().map(|(_, points)| points).sum::<i64>();
+
+    let percent_complete = match (points, entries.len()) {
+        (0, 0) => 0.0,
+        (0, count) => done.count as f64 / count as f64 * 100.0,
+        (points, _) => done.points as f64 / points as f64 * 100.0
+    };
+
+    Rollup {
+        count: entries.len(),
+        points,
+        completed_points: done.points,
+        percent_complete: (percent_complete * 100.0).round() / 100.0,
+        by_status
+    }
+}
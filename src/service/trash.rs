@@ -0,0 +1,279 @@
+use actix_web::{http::Method, web};
+use serde_json::Value;
+
+use crate::{
+    config::app_state::AppState,
+    interface::{crudy::ApiResult, resource::Resource, sheet_entity::SheetEntity, store::StoreResult},
+    model::{
+        auth::Identity,
+        epic::Epic,
+        member::TeamMember,
+        permission::ProjectRole,
+        product_backlog::ProductBacklogItem,
+        project::Project,
+        response::Response,
+        sprint::Sprint,
+        trash::{TrashItem, TrashQuery},
+        user_story::UserStoryBox,
+    },
+    routes::Router,
+    util::{
+        error::AppError,
+        range::column_to_index,
+        row::{cells, is_empty},
+        util::unix_now,
+    },
+};
+
+use super::{
+    audit::AuditService,
+    epic::EpicService,
+    member::MemberService,
+    permission::PermissionService,
+    product_backlog::ProductBacklogService,
+    project::ProjectService,
+    spreadsheet::{entity_id, find_row, locate_cell, read_row},
+    sprint::SprintService,
+    user_story::UserStoryService,
+};
+
+/// Seconds in a day of retention.
+const DAY: i64 = 86_400;
+
+///
+/// The removed entities are moved to the `Trash` sheet instead of being destroyed,
+/// they can be restored until they are purged (see `TrashSettings`).
+pub struct TrashService;
+
+impl TrashService {
+    pub fn configure(router: &mut Router) {
+        router
+            .add(Method::GET, "/trash", web::route().to(Self::list))
+            .add(Method::POST, "/trash/purge", web::route().to(Self::purge))
+            .add(Method::POST, "/trash/{id}/restore", web::route().to(Self::restore));
+    }
+
+    ///
+    /// The items of the trash, only the ones of `entity` when it is given.
+    /// An identified caller only sees the items of the projects where it has a role.
+    pub async fn list(state: web::Data<AppState>, param: Option<web::Query<TrashQuery>>, identity: Option<web::ReqData<Identity>>) -> ApiResult<TrashItem> {
+        let entity = param.and_then(|param| param.into_inner().entity);
+
+        let mut items = vec![];
+
+        for (_, item) in Self::items(&state).await? {
+            if entity.as_ref().is_some_and(|entity| item.entity != *entity) { continue }
+
+            let visible = match (identity.as_deref(), item.project_id()) {
+                (Some(identity), Some(project_id)) => PermissionService::role_of(&state, project_id, &identity.subject).await?.is_some(),
+                _ => true
+            };
+
+            if visible { items.push(item) }
+        }
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(items))))
+    }
+
+    ///
+    /// It puts the entity back in the row it was removed from (or at the end when the row was taken) with what referred to it,
+    /// the role that removes the entity is the one that restores it.
+    pub async fn restore(state: web::Data<AppState>, path: web::Path<String>, identity: Option<web::ReqData<Identity>>) -> ApiResult<TrashItem> {
+        let id = path.into_inner();
+
+        let (row, item) = Self::items(&state)
+            .await?
+            .into_iter()
+            .find(|(_, item)| item.id == id)
+            .ok_or(AppError::NotFound(format!("There is no item {} in the trash", id)))?;
+
+        let role = match item.entity.as_str() {
+            Sprint::KIND => ProjectRole::ScrumMaster,
+            _ => ProjectRole::ProductOwner
+        };
+
+        Self::authorize(&state, identity.as_deref(), &item, role).await?;
+
+        match item.entity.as_str() {
+            Project::KIND => Self::put_back::<ProjectService>(&state, &item).await?,
+            Epic::KIND => Self::put_back::<EpicService>(&state, &item).await?,
+            UserStoryBox::KIND => Self::put_back::<UserStoryService>(&state, &item).await?,
+            ProductBacklogItem::KIND => Self::put_back::<ProductBacklogService>(&state, &item).await?,
+            Sprint::KIND => Self::put_back::<SprintService>(&state, &item).await?,
+            TeamMember::KIND => Self::put_back::<MemberService>(&state, &item).await?,
+            kind => return Err(AppError::CorruptRow(format!("The trash item {} is a '{}', which is not an entity", item.id, kind)))
+        }
+
+        Self::remove(&state, row).await?;
+
+        Ok(web::Json(Response::ok("It was restored successfully!", Some(vec![item]))))
+    }
+
+    ///
+    /// It removes for good the items kept longer than `retention_days`,
+    /// an identified caller only purges the ones of the projects where it is `ProductOwner`.
+    pub async fn purge(state: web::Data<AppState>, identity: Option<web::ReqData<Identity>>) -> ApiResult<TrashItem> {
+        let limit = unix_now() - i64::from(state.settings.trash.retention_days) * DAY;
+
+        let mut purged = vec![];
+
+        for (row, item) in Self::items(&state).await? {
+            if item.deleted_at > limit { continue }
+
+            match Self::authorize(&state, identity.as_deref(), &item, ProjectRole::ProductOwner).await {
+                Ok(()) => {},
+                Err(AppError::Forbidden(_)) => continue,
+                Err(err) => return Err(err)
+            }
+
+            Self::remove(&state, row).await?;
+            purged.push(item);
+        }
+
+        Ok(web::Json(Response::ok("The trash was purged successfully!", Some(purged))))
+    }
+
+    ///
+    /// It checks the caller has `role` in the project of the item, nothing is checked when the API is open.
+    /// The members are outside of the projects, they are managed by the `ProductOwner` of any project.
+    async fn authorize(state: &AppState, identity: Option<&Identity>, item: &TrashItem, role: ProjectRole) -> StoreResult<()> {
+        match item.project_id() {
+            Some(project_id) => PermissionService::require(state, identity, project_id, Some(&[role])).await,
+            None => PermissionService::manage_members(state, identity).await
+        }
+    }
+
+    ///
+    /// It moves the entity stored in `row` (one based) to the trash, with what referred to it (see `Resource::detach`),
+    /// its cells are cleared.
+    pub async fn discard<R: Resource + ?Sized>(state: &AppState, row: usize, entity: &R::Entity) -> StoreResult<TrashItem> {
+        let relations = R::detach(state, entity).await?;
+        let values = entity.to_row();
+
+        let item = TrashItem {
+            id: format!("{:016x}", rand::random::<u64>()),
+            entity: R::Entity::KIND.to_string(),
+            entity_id: entity_id::<R::Entity>(&cells(&values)),
+            row,
+            deleted_at: unix_now(),
+            fields: R::Entity::FIELDS.iter().zip(&values).map(|(field, value)| (field.to_string(), Value::String(value.clone()))).collect(),
+            relations
+        };
+
+        let sheet = &state.settings.sheets.trash;
+        let (first_col, last_col) = TrashItem::COLUMNS;
+
+        state.ensure_sheet(sheet).await?;
+        state.store.append(format!("{}!{}1:{}1", sheet, first_col, last_col).as_str(), item.to_row()).await?;
+
+        let (first_col, last_col) = R::Entity::COLUMNS;
+        state.store.clear(format!("{}!{}{}:{}{}", state.sheet(), first_col, row, last_col, row).as_str()).await?;
+
+        AuditService::entity(state, Some(entity), None).await?;
+
+        Ok(item)
+    }
+
+    ///
+    /// It moves to the trash the entities stored from `start` to `end` (e.g: `I3`, `P4`) of the legacy `DELETE` routes,
+    /// the range must cover whole rows of the entity.
+    pub async fn clear<R: Resource>(state: &AppState, start: &str, end: &str) -> StoreResult<()> {
+        let (first_col, last_col) = R::Entity::COLUMNS;
+        let last_offset = column_to_index(last_col).unwrap_or_default() - column_to_index(first_col).unwrap_or_default();
+
+        let (first_row, last_row) = match (locate_cell(start, R::Entity::COLUMNS), locate_cell(end, R::Entity::COLUMNS)) {
+            (Ok((first_row, 0)), Ok((last_row, offset))) if offset == last_offset => (first_row.min(last_row), first_row.max(last_row)),
+            _ => return Err(AppError::Validation(format!(
+                "The range {}:{} does not cover whole rows of the {} ({}:{}), the cells of an entity are changed with `PATCH`",
+                start, end, R::Entity::KIND.replace('_', " "), first_col, last_col
+            )))
+        };
+
+        let values = state.store.read(format!("{}!{}{}:{}{}", state.sheet(), first_col, first_row, last_col, last_row).as_str()).await?;
+
+        let entities = values
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row))
+            .map(|(offset, row)| R::Entity::from_row(row, R::Entity::row_label(first_row + offset)).map(|entity| (first_row + offset, entity)))
+            .collect::<StoreResult<Vec<(usize, R::Entity)>>>()?;
+
+        // Nothing is removed when one of them can not be
+        for (_, entity) in &entities {
+            R::removing(state, entity).await?;
+        }
+
+        for (row, entity) in &entities {
+            Self::discard::<R>(state, *row, entity).await?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// It writes the cells of the item back and what referred to the entity (see `Resource::attach`),
+    /// the change is recorded as the others.
+    async fn put_back<R: Resource>(state: &AppState, item: &TrashItem) -> StoreResult<()> {
+        let values = Self::cells_of::<R::Entity>(item);
+
+        let keys = R::Entity::KEYS.iter().map(|key| values[*key].clone()).collect::<Vec<String>>();
+
+        if find_row::<R::Entity>(state.store.as_ref(), state.sheet(), &keys).await.is_ok() {
+            return Err(AppError::Conflict(format!("There is already a {} {}", R::Entity::KIND.replace('_', " "), keys.join("/"))))
+        }
+
+        // The cells must still make an entity
+        let entity = R::Entity::from_row(&cells(&values), String::new())?;
+
+        let (first_col, last_col) = R::Entity::COLUMNS;
+
+        match read_row(state.store.as_ref(), state.sheet(), R::Entity::COLUMNS, item.row).await {
+            Err(AppError::NotFound(_)) => {
+                let full_range = format!("{}!{}{}:{}{}", state.sheet(), first_col, item.row, last_col, item.row);
+                state.store.update(full_range.as_str(), values).await?;
+            },
+            Ok(_) => {
+                state.store.append(format!("{}!{}1:{}1", state.sheet(), first_col, last_col).as_str(), values).await?;
+            },
+            Err(err) => return Err(err)
+        }
+
+        AuditService::entity(state, None, Some(&entity)).await?;
+
+        R::attach(state, &entity, &item.relations).await
+    }
+
+    ///
+    /// Cells of the entity kept in the item, in the same order as `FIELDS`.
+    fn cells_of<E: SheetEntity>(item: &TrashItem) -> Vec<String> {
+        E::FIELDS
+            .iter()
+            .map(|field| item.fields.get(*field).and_then(Value::as_str).unwrap_or_default().to_string())
+            .collect()
+    }
+
+    ///
+    /// Every item of the trash with its row (one based).
+    async fn items(state: &AppState) -> StoreResult<Vec<(usize, TrashItem)>> {
+        let sheet = &state.settings.sheets.trash;
+        let (first_col, last_col) = TrashItem::COLUMNS;
+
+        state.ensure_sheet(sheet).await?;
+
+        let values = state.store.read(format!("{}!{}:{}", sheet, first_col, last_col).as_str()).await?;
+
+        values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row))
+            .map(|(index, row)| TrashItem::from_row(&row).map(|item| (index + 1, item)))
+            .collect()
+    }
+
+    async fn remove(state: &AppState, row: usize) -> StoreResult<()> {
+        let sheet = &state.settings.sheets.trash;
+        let (first_col, last_col) = TrashItem::COLUMNS;
+
+        state.store.clear(format!("{}!{}{}:{}{}", sheet, first_col, row, last_col, row).as_str()).await
+    }
+}
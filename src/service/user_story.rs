@@ -1,34 +1,38 @@
-use std::str::FromStr;
-
-use actix_web::{HttpResponse, web};
+use actix_web::web;
 use async_trait::async_trait;
-use google_sheets4::api::{ClearValuesRequest, ValueRange};
 
 use crate::{
-    config::google_sheet_authenticator::get_credentials, interface::crudy::CRUD, model::{
-        self, spreadsheet::{SpecificDataRange, SpecificPatch}, user_story::{InsertUserStoryBox, Scenario, ScenarioType, UserStory, UserStoryBox}
-    }, util::{
-        cons::{PROJECTS_SHEET_NAME, SPREADSHEET_ID},
-        util::{convert_pattern_to_string, convert_pattern_to_vec, extract_num, DoubleIdentificator},
+    config::app_state::AppState,
+    interface::{crudy::{ApiResult, CRUD}, resource::Resource, sheet_entity::SheetEntity, store::StoreResult}, model::{
+        response::Response,
+        spreadsheet::{SpecificDataRange, SpecificPatch}, trash::Relations, user_story::{details_to_pattern, scenarios_to_pattern, InsertUserStoryBox, UserStory, UserStoryBox, UserStoryQuery}
+    }, routes::{register_crud, register_resource, Router}, util::{
+        error::AppError,
+        row::{cells, is_empty, set, text},
+        util::{convert_pattern_to_string, convert_pattern_to_vec},
     }
 };
 
-use super::spreadsheet::create_id;
+use super::{assignment::AssignmentService, audit::AuditService, spreadsheet::{locate_cell, read_row}, trash::TrashService, workflow::check_transition};
 
 pub struct UserStoryService;
 
+impl UserStoryService {
+    pub fn configure(router: &mut Router) {
+        register_crud::<Self, _>(router, "/uss", "/us");
+        register_resource::<Self>(router, "/projects/{id}/epics/{epic_id}/stories/{us_id}");
+    }
+}
+
 #[async_trait]
 impl CRUD for UserStoryService {
+    type Entity = UserStoryBox;
     type CreatePayload = InsertUserStoryBox;
-    type ReadPayload = DoubleIdentificator;
+    type ReadPayload = UserStoryQuery;
     type UpdatePayload = SpecificPatch;
     type DeletePayload = SpecificDataRange;
 
-    async fn create(data: web::Json<Self::CreatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
-
+    async fn create(state: web::Data<AppState>, data: web::Json<Self::CreatePayload>) -> ApiResult<Self::Entity> {
         let InsertUserStoryBox {
             project_id,
             epic_id,
@@ -42,48 +46,20 @@ impl CRUD for UserStoryService {
                     benefit,
                 },
             acceptance_criteria: (scenarios, details),
+            status,
             created_at,
         } = data.into_inner();
         
         let updated_at = created_at.clone();
 
-        let mut user_story_id = create_id(
-                &PROJECTS_SHEET_NAME,
-                "R",
-                Some(("S", project_id.clone().as_str()))
-            )
-            .await
-            .unwrap();
+        let user_story_id = state.ids.next::<UserStoryBox>(state.store.as_ref(), Some(&project_id)).await?;
 
-        if user_story_id == "null" { user_story_id = "US1".to_string() }
-        let index = extract_num(user_story_id.clone().as_str());
+        let fmt_scenarios = scenarios_to_pattern(&scenarios);
 
+        let fmt_details = details_to_pattern(&details);
 
-        let fmt_scenarios = scenarios
-            .into_iter()
-            .enumerate()
-            .map(|(index, scenario)| {
-                return format!(
-                    "{}:{:?}^{}^{}^{}",
-                    index, scenario.kind, scenario.given, scenario.when, scenario.then
-                );
-            })
-            .collect::<Vec<String>>()
-            .join("|");
-
-        let fmt_details = details
-            .into_iter()
-            .enumerate()
-            .map(|(index, detail)| return format!("{}:{}", index, detail))
-            .collect::<Vec<String>>()
-            .join("|");
-
-        let full_range = format!(
-            "{}!R{}:AD{}",
-            &PROJECTS_SHEET_NAME.to_string(),
-            index,
-            index
-        );
+        // The row is chosen by the store, after the last row of the entity
+        let full_range = format!("{}!R1:AE1", state.sheet());
 
         let user_story_vector = vec![
             user_story_id,
@@ -98,306 +74,171 @@ impl CRUD for UserStoryService {
             fmt_scenarios,
             fmt_details,
             created_at.to_string(),
-            updated_at.to_string()
+            updated_at.to_string(),
+            format!("{status:?}")
         ];
 
-        let request = ValueRange {
-            major_dimension: Some("ROWS".to_string()),
-            range: Some(full_range.clone()),
-            values: Some(vec![
-                user_story_vector
-                    .iter()
-                    .map(|element| Into::into(element.as_str()))
-                    .collect(),
-            ]),
-        };
+        let row = state.store.append(full_range.as_str(), user_story_vector.clone()).await?;
 
-        sheet_credentials
-            .spreadsheets()
-            .values_append(request, &SPREADSHEET_ID, full_range.as_str())
-            .value_input_option("USER_ENTERED")
-            .doit()
-            .await
-            .expect("Could not insert the data in the sheet");
-
-        HttpResponse::Ok().json(serde_json::json!({
-            "response": "Data was inserted in the sheet successfully!"
-        }))
+        let user_story = UserStoryBox::from_row(&cells(&user_story_vector), UserStoryBox::row_label(row))?;
+
+        AuditService::entity(&state, None, Some(&user_story)).await?;
+
+        Ok(web::Json(Response::ok("Data was inserted in the sheet successfully!", Some(vec![user_story]))))
     }
 
-    async fn read(param: Option<web::Query<Self::ReadPayload>>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+    async fn read(state: web::Data<AppState>, param: Option<web::Query<Self::ReadPayload>>) -> ApiResult<Self::Entity> {
+        let UserStoryQuery { project_id, epic_id, status } = param
+            .ok_or(AppError::Validation("The query parameters are missing or invalid".to_string()))?
+            .into_inner();
 
-        let DoubleIdentificator { project_id, epic_id } = param.unwrap().into_inner();
+        let full_range = format!("{}!R:AE", state.sheet());
 
-        let full_range = format!("{}!R:AD", &PROJECTS_SHEET_NAME.to_string());
+        let values = state.store.read(full_range.as_str()).await?;
 
-        match sheet_credentials
-            .spreadsheets()
-            .values_get(&SPREADSHEET_ID, full_range.as_ref())
-            .doit()
-            .await
-        {
-            Ok((_, value_range)) => {
-                let values = value_range.values.unwrap_or_default();
+        // Filter empty rows
+        let mut rows: Vec<UserStoryBox> = values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| !is_empty(row)
+                && text(row, 1) == project_id.replace("\"", "")
+                && text(row, 2) == epic_id.replace("\"", ""))
+            .map(|(index, row)| UserStoryBox::from_row(&row, UserStoryBox::row_label(index + 1)))
+            .filter(|user_story| !matches!((user_story, status), (Ok(user_story), Some(status)) if user_story.status != status))
+            .collect::<Result<Vec<UserStoryBox>, AppError>>()?;
 
-                let rows: Vec<UserStoryBox> = values
-                    .into_iter()
-                    .enumerate()
-                    .filter(|(_, row)| row[1].to_string().replace("\"", "") == project_id.replace("\"", "") && row[2].to_string().replace("\"", "") == epic_id.replace("\"",""))
-                    .filter_map(|(index, row)| {
-                        let scenarios_value_range = ValueRange {
-                           range: None,
-                           major_dimension: None,
-                           values: Some(vec![vec![row.get(9).cloned().unwrap_or_default()]]),
-                           ..Default::default()
-                        };
-
-                        let details_value_range = ValueRange {
-                            range: None,
-                            major_dimension: None,
-                            values: Some(vec![vec![row.get(10).cloned().unwrap_or_default()]]),
-                            ..Default::default()
-                        };
-
-                        let scenarios: Vec<Scenario> = convert_pattern_to_vec(scenarios_value_range)
-                            .into_iter()
-                            .map(|val| {
-                                let scenario_string = val.get(0)
-                                    .cloned()
-                                    .unwrap_or_default();
-
-                                let scenario_kind = scenario_string
-                                    .as_str()
-                                    .split(":")
-                                    .collect::<Vec<&str>>();
-
-                                return Scenario {
-                                    kind: ScenarioType::from_str(scenario_kind[1]).ok().unwrap(),
-                                    given: val.get(1).cloned().unwrap_or_default(),
-                                    when: val.get(2).cloned().unwrap_or_default(),
-                                    then: val.get(3).cloned().unwrap_or_default()
-                                }
-                            })
-                            .collect();
-
-                        let details: Vec<String> = convert_pattern_to_vec(details_value_range)
-                            .get(0)
-                            .cloned()
-                            .unwrap_or_default();
-
-                        Some(UserStoryBox {
-                            id: row[0].as_str()?.to_string(),
-                            project_id: row[1].as_str()?.to_string(),
-                            epic_id: row[2].as_str()?.to_string(),
-                            title: row[3].as_str()?.to_string(),
-                            priority: row[4].as_str()?.to_string(),
-                            story_points: row[5].as_str()?.parse().ok()?,
-                            user_story: UserStory {
-                                role: row[6].as_str()?.to_string(),
-                                functionality: row[7].as_str()?.to_string(),
-                                benefit: row[8].as_str()?.to_string()
-                            },
-                            acceptance_criteria: (scenarios, details),
-                            created_at: row[11].as_str()?.parse().ok()?,
-                            updated_at: row[12].as_str()?.parse().ok()?,
-
-                            row: format!("R{}:AD{}", index, index)
-                        })                         
-                    })
-                    .collect();
-
-                return HttpResponse::Ok().json(
-                    model::response::Response {
-                        message: "Data was obtained successfully",
-                        content: Some(rows),
-                        error_bug: None
-                    }    
-                );
-            }
-            Err(err) => {
-                return HttpResponse::InternalServerError().json(serde_json::json!({
-                    "response": format!("Error reading the sheet: {}", err)
-                }));
-            }
+        let mut assignees = AssignmentService::assignees(&state, &project_id.replace("\"", ""), UserStoryBox::KIND).await?;
+
+        for user_story in rows.iter_mut() {
+            user_story.assignee = assignees.remove(&user_story.id);
         }
-    }
 
-    async fn update(data: web::Json<Self::UpdatePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(rows))))
+    }
 
+    async fn update(state: web::Data<AppState>, data: web::Json<Self::UpdatePayload>) -> ApiResult<Self::Entity> {
         let SpecificPatch {
             content,
             cell,
             index,
         } = data.into_inner();
 
-        let full_range = format!("{}!{}:{}", &PROJECTS_SHEET_NAME.to_string(), cell, cell);
+        let (row, offset) = locate_cell(&cell, ("R", "AE"))?;
+        let mut values = read_row(state.store.as_ref(), state.sheet(), ("R", "AE"), row).await?;
+        let current = UserStoryBox::from_row(&values, UserStoryBox::row_label(row))?;
 
-        if (cell.contains("AA") || cell.contains("AB")) && index.is_some() {
-            let (_, value_range) = sheet_credentials
-                .spreadsheets()
-                .values_get(&SPREADSHEET_ID, full_range.clone().as_str())
-                .doit()
-                .await
-                .expect("Could not get data");
+        let content = match index {
+            Some(index) if cell.contains("AA") || cell.contains("AB") => {
+                let mut str_to_vector: Vec<Vec<String>> =
+                    convert_pattern_to_vec(vec![vec![values.get(offset).cloned().unwrap_or_default()]]);
 
-            let mut str_to_vector: Vec<Vec<String>> = convert_pattern_to_vec(value_range);
+                if index >= str_to_vector.len() {
+                    let fmt_content = format!("{}:{}", str_to_vector.len(), content);
+                    str_to_vector.push(vec![fmt_content]);
+                } else {
+                    let fmt_content = format!("{}:{}", index, content);
+                    str_to_vector[index] = vec![fmt_content];
+                }
 
-            if index.unwrap() > str_to_vector.len() {
-                let fmt_content = format!("{}:{}", str_to_vector.len(), content).to_string();
-                str_to_vector.push([fmt_content].to_vec());
-            } else if index.unwrap() <= str_to_vector.len() {
-                let fmt_content = format!("{}:{}", index.unwrap(), content).to_string();
-                str_to_vector[index.unwrap()] = [fmt_content].to_vec();
+                convert_pattern_to_string(str_to_vector)
             }
+            Some(_) => {
+                return Err(AppError::Validation(format!(
+                    "The `index` is only allowed for the scenarios (AA) or details (AB), not for '{}'",
+                    cell
+                )));
+            }
+            None => content
+        };
 
-            let vector_to_str = convert_pattern_to_string(str_to_vector);
-
-            let input = vec![vector_to_str];
-
-            let request = ValueRange {
-                major_dimension: Some("ROWS".to_string()),
-                range: Some(full_range.clone()),
-                values: Some(vec![
-                    input
-                        .iter()
-                        .map(|element| Into::into(element.as_str()))
-                        .collect(),
-                ]),
-            };
-
-            sheet_credentials
-                .spreadsheets()
-                .values_update(request, &SPREADSHEET_ID, full_range.as_str())
-                .value_input_option("USER_ENTERED")
-                .doit()
-                .await
-                .expect("Could not removed the data in the sheet");
-        }
+        // The row is validated before writing the cell
+        set(&mut values, offset, content.clone());
+        let user_story = UserStoryBox::from_row(&values, UserStoryBox::row_label(row))
+            .map_err(AppError::into_validation)?;
 
-        if index.is_none() {
-            let us_data = vec![content.clone()];
-
-            let request = ValueRange {
-                major_dimension: Some("ROWS".to_string()),
-                range: Some(full_range.clone()),
-                values: Some(vec![
-                    us_data
-                        .iter()
-                        .map(|element| Into::into(element.as_str()))
-                        .collect(),
-                ]),
-            };
-
-            sheet_credentials
-                .spreadsheets()
-                .values_update(request, &SPREADSHEET_ID, full_range.as_str())
-                .value_input_option("USER_ENTERED")
-                .doit()
-                .await
-                .expect("Could not update the data in the sheet");
-        }
+        check_transition(&state.settings.workflow, &current, &user_story)?;
 
-        HttpResponse::Ok().json(serde_json::json!({
-            "response": "Data was updated in the sheet successfully!"
-        }))
-    }
+        let full_range = format!("{}!{}:{}", state.sheet(), cell, cell);
+
+        state.store.update(full_range.as_str(), vec![content]).await?;
 
-    async fn delete(data: web::Json<Self::DeletePayload>) -> HttpResponse {
-        let sheet_credentials = get_credentials()
-            .await
-            .expect("Could not establish the connection to the spreadsheet");
+        AuditService::entity(&state, Some(&current), Some(&user_story)).await?;
 
+        Ok(web::Json(Response::ok("Data was updated in the sheet successfully!", Some(vec![user_story]))))
+    }
+
+    async fn delete(state: web::Data<AppState>, data: web::Json<Self::DeletePayload>) -> ApiResult<Self::Entity> {
         let SpecificDataRange {
             range: (start, end),
             index,
         } = data.into_inner();
 
-        let range_str = format!("{}!{}:{}", &PROJECTS_SHEET_NAME.to_string(), start, end);
-
-        if (start.contains("AA") && end.contains("AA"))
-            || (start.contains("AB") && end.contains("AB")) && index.is_some()
-        {
-            let (_, value_range) = sheet_credentials
-                .spreadsheets()
-                .values_get(&SPREADSHEET_ID, range_str.clone().as_str())
-                .doit()
-                .await
-                .expect("Could not get data");
-
-            let str_to_vector: Vec<Vec<String>> = value_range
-                .values
-                .unwrap_or_default()
-                .into_iter()
-                .map(|element| {
-                    element[0]
-                        .as_str()
-                        .unwrap()
-                        .split("|")
-                        .map(|condition| {
-                            condition
-                                .split("^")
-                                .map(|s| s.to_string())
-                                .collect::<Vec<String>>()
-                        })
-                        .collect::<Vec<Vec<String>>>()
-                })
-                .flatten()
-                .collect();
-
-            let filter_values = str_to_vector
-                .iter()
-                .filter(|row| {
-                    row[0].as_str().split(":").collect::<Vec<&str>>()[0]
-                        != index.unwrap().to_string().as_str()
-                })
-                .map(|element| element.clone())
-                .collect::<Vec<Vec<String>>>();
-
-            let vector_to_str = filter_values
-                .iter()
-                .map(|row| row.join("^"))
-                .collect::<Vec<String>>()
-                .join("|");
-
-            let input = vec![vector_to_str];
-
-            let request = ValueRange {
-                major_dimension: Some("ROWS".to_string()),
-                range: Some(range_str.clone()),
-                values: Some(vec![
-                    input
-                        .iter()
-                        .map(|element| Into::into(element.as_str()))
-                        .collect(),
-                ]),
-            };
-
-            sheet_credentials
-                .spreadsheets()
-                .values_update(request, &SPREADSHEET_ID, range_str.as_str())
-                .value_input_option("USER_ENTERED")
-                .doit()
-                .await
-                .expect("Could not removed the data in the sheet");
-        }
+        let range_str = format!("{}!{}:{}", state.sheet(), start, end);
+
+        let is_pattern_cell = (start.contains("AA") && end.contains("AA"))
+            || (start.contains("AB") && end.contains("AB"));
+
+        match index {
+            Some(index) if is_pattern_cell => {
+                let (row, offset) = locate_cell(&start, ("R", "AE"))?;
+                let mut stored = read_row(state.store.as_ref(), state.sheet(), ("R", "AE"), row).await?;
+                let current = UserStoryBox::from_row(&stored, UserStoryBox::row_label(row))?;
+
+                let values = state.store.read(range_str.as_str()).await?;
+
+                let position = index.to_string();
 
-        if index.is_none() {
-            sheet_credentials
-                .spreadsheets()
-                .values_clear(ClearValuesRequest::default(), &SPREADSHEET_ID, &range_str)
-                .doit()
-                .await
-                .expect("Could not removed the data in the sheet");
+                let filter_values = convert_pattern_to_vec(values)
+                    .into_iter()
+                    .filter(|row| row.first().and_then(|head| head.split(":").next()) != Some(position.as_str()))
+                    .collect::<Vec<Vec<String>>>();
+
+                let vector_to_str = convert_pattern_to_string(filter_values);
+
+                state.store.update(range_str.as_str(), vec![vector_to_str.clone()]).await?;
+
+                set(&mut stored, offset, vector_to_str);
+                let user_story = UserStoryBox::from_row(&stored, UserStoryBox::row_label(row))?;
+
+                AuditService::entity(&state, Some(&current), Some(&user_story)).await?;
+            }
+            Some(_) => {
+                return Err(AppError::Validation(format!(
+                    "The `index` is only allowed for the scenarios (AA) or details (AB), not for '{}:{}'",
+                    start, end
+                )));
+            }
+            None => {
+                TrashService::clear::<Self>(&state, &start, &end).await?;
+            }
         }
 
-        HttpResponse::Ok().json(serde_json::json!({
-            "response": "It was removed successfully!"
-        }))
+        Ok(web::Json(Response::ok("It was removed successfully!", None)))
+    }
+}
+
+#[async_trait]
+impl Resource for UserStoryService {
+    type Entity = UserStoryBox;
+    type Path = (String, String, String);
+
+    async fn loaded(state: &AppState, story: &mut UserStoryBox) -> StoreResult<()> {
+        story.assignee = AssignmentService::assignee(state, &story.project_id, UserStoryBox::KIND, &story.id).await?;
+
+        Ok(())
+    }
+
+    async fn detach(state: &AppState, story: &UserStoryBox) -> StoreResult<Relations> {
+        let mut relations = Relations::default();
+
+        relations.keep(&AssignmentService::unlink(state, |assignment| {
+            assignment.project_id == story.project_id && assignment.kind == UserStoryBox::KIND && assignment.entity_id == story.id
+        }).await?);
+
+        Ok(relations)
+    }
+
+    async fn attach(state: &AppState, _story: &UserStoryBox, relations: &Relations) -> StoreResult<()> {
+        AssignmentService::relink(state, relations.kept()?).await
     }
-}
\ No newline at end of file
+}
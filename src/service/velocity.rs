@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+
+use actix_web::{http::Method, web};
+
+use crate::{
+    config::app_state::AppState,
+    interface::crudy::ApiResult,
+    model::{
+        product_backlog::ProductBacklogItem,
+        project::Project,
+        response::Response,
+        sprint::Sprint,
+        velocity::{Forecast, SprintVelocity, Velocity, VelocityQuery},
+    },
+    routes::{guarded, Router},
+    util::{error::AppError, util::{ItemStatus, Status}},
+};
+
+use super::{spreadsheet::{find_row, rows_of}, sprint_item::SprintItemService};
+
+/// Done sprints of the rolling average when the query does not say it.
+const DEFAULT_WINDOW: usize = 3;
+
+/// Runs of the Monte Carlo simulation.
+const SIMULATIONS: usize = 10_000;
+
+/// A run stops after these sprints, e.g: when most of the past sprints completed nothing.
+const MAX_SPRINTS: i64 = 1_000;
+
+///
+/// Velocity of the team across the done sprints of a project and the forecast of the product backlog.
+pub struct VelocityService;
+
+impl VelocityService {
+    pub fn configure(router: &mut Router) {
+        router.add(Method::GET, "/projects/{id}/velocity", guarded::<Project>(web::route().to(Self::velocity)));
+    }
+
+    pub async fn velocity(state: web::Data<AppState>, path: web::Path<String>, query: Option<web::Query<VelocityQuery>>) -> ApiResult<Velocity> {
+        let project_id = path.into_inner();
+
+        let VelocityQuery { window } = query
+            .ok_or(AppError::Validation("The query parameters are missing or invalid".to_string()))?
+            .into_inner();
+
+        let window = window.unwrap_or(DEFAULT_WINDOW);
+
+        if window == 0 {
+            return Err(AppError::Validation("The window must contain one sprint at least".to_string()))
+        }
+
+        find_row::<Project>(state.store.as_ref(), state.sheet(), std::slice::from_ref(&project_id)).await?;
+
+        let links = SprintItemService::links(&state, &project_id).await?;
+        let backlog = rows_of::<ProductBacklogItem>(state.store.as_ref(), state.sheet(), &project_id).await?;
+
+        let points = |item_id: &str| {
+            backlog.iter().find(|item| item.id == item_id).and_then(|item| item.points).unwrap_or(0)
+        };
+
+        let mut done = rows_of::<Sprint>(state.store.as_ref(), state.sheet(), &project_id)
+            .await?
+            .into_iter()
+            .filter(|sprint| sprint.status == Status::Done)
+            .collect::<Vec<Sprint>>();
+
+        // Sprints done before `completedAt` was stamped are ordered by their end
+        done.sort_by_key(|sprint| match sprint.completed_at {
+            0 => sprint.end_date,
+            completed_at => completed_at
+        });
+
+        let mut sprints: Vec<SprintVelocity> = vec![];
+
+        for sprint in done {
+            let sprint_links = links.iter().filter(|(_, link)| link.sprint_id == sprint.id).collect::<Vec<_>>();
+
+            // A sprint without items keeps its points from before they were linked
+            let completed = match sprint_links.is_empty() {
+                true => sprint.estimated_points,
+                false => sprint_links
+                    .iter()
+                    .filter(|(_, link)| link.completed_at.is_some())
+                    .map(|(_, link)| points(&link.item_id))
+                    .sum()
+            };
+
+            let previous = sprints.iter().rev().take(window - 1).map(|past| past.completed);
+
+            sprints.push(SprintVelocity {
+                sprint_id: sprint.id,
+                denomination: sprint.denomination,
+                completed_at: sprint.completed_at,
+                committed: sprint.estimated_points,
+                completed,
+                rolling_average: average(previous.chain([completed]))
+            });
+        }
+
+        let completed_items = links
+            .iter()
+            .filter(|(_, link)| link.completed_at.is_some())
+            .map(|(_, link)| link.item_id.as_str())
+            .collect::<HashSet<&str>>();
+
+        let remaining_points = backlog
+            .iter()
+            .filter(|item| item.status != ItemStatus::Done && !completed_items.contains(item.id.as_str()))
+            .filter_map(|item| item.points)
+            .sum::<i64>();
+
+        let throughput = sprints.iter().map(|sprint| sprint.completed).collect::<Vec<i64>>();
+        let average = sprints.last().map(|sprint| sprint.rolling_average).unwrap_or(0.0);
+
+        let velocity = Velocity {
+            project_id,
+            window,
+            sprints,
+            average,
+            remaining_points,
+            forecast: forecast(remaining_points, average, &throughput)
+        };
+
+        Ok(web::Json(Response::ok("Data was obtained successfully", Some(vec![velocity]))))
+    }
+}
+
+///
+/// Mean rounded to two decimals, 0 without values.
+fn average(values: impl Iterator<Item = i64>) -> f64 {
+    let (sum, count) = values.fold((0, 0), |(sum, count), value| (sum + value, count + 1));
+
+    match count {
+        0 => 0.0,
+        _ => (sum as f64 / count as f64 * 100.0).round() / 100.0
+    }
+}
+
+///
+/// `throughput`: Completed points of every done sprint.
+fn forecast(remaining_points: i64, average: f64, throughput: &[i64]) -> Forecast {
+    if remaining_points <= 0 {
+        return Forecast { sprints: Some(0), p50: Some(0), p85: Some(0), p95: Some(0) }
+    }
+
+    if throughput.iter().all(|points| *points <= 0) {
+        return Forecast { sprints: None, p50: None, p85: None, p95: None }
+    }
+
+    let sprints = match average > 0.0 {
+        true => Some((remaining_points as f64 / average).ceil() as i64),
+        false => None
+    };
+
+    let mut runs = (0..SIMULATIONS)
+        .map(|_| {
+            let (mut burnt, mut count) = (0, 0);
+
+            while burnt < remaining_points && count < MAX_SPRINTS {
+                burnt += throughput[rand::random_range(0..throughput.len())];
+                count += 1;
+            }
+
+            count
+        })
+        .collect::<Vec<i64>>();
+
+    runs.sort_unstable();
+
+    let percentile = |percent: usize| Some(runs[(runs.len() * percent).div_ceil(100) - 1]);
+
+    Forecast { sprints, p50: percentile(50), p85: percentile(85), p95: percentile(95) }
+}
@@ -0,0 +1,28 @@
+use serde_json::Value;
+
+use crate::{config::settings::WorkflowSettings, interface::sheet_entity::SheetEntity, util::error::AppError};
+
+///
+/// It checks the status of the entity can change from the stored one, following the table of its kind
+/// (see `WorkflowSettings`). Entities without status or without table are not checked.
+pub fn check_transition<E: SheetEntity>(workflow: &WorkflowSettings, stored: &E, entity: &E) -> Result<(), AppError> {
+    let Some(table) = workflow.table(E::KIND) else { return Ok(()) };
+
+    let (Some(from), Some(to)) = (status(stored), status(entity)) else { return Ok(()) };
+
+    if from == to { return Ok(()) }
+
+    let allowed = table.get(&from).cloned().unwrap_or_default();
+
+    match allowed.contains(&to) {
+        true => Ok(()),
+        false => Err(AppError::InvalidTransition { from, to, allowed })
+    }
+}
+
+fn status<E: SheetEntity>(entity: &E) -> Option<String> {
+    match serde_json::to_value(entity).ok()?.get("status")? {
+        Value::String(status) => Some(status.clone()),
+        _ => None
+    }
+}
@@ -0,0 +1,175 @@
+use async_trait::async_trait;
+use google_sheets4::api::{AddSheetRequest, BatchUpdateSpreadsheetRequest, ClearValuesRequest, Request, SheetProperties, ValueRange};
+use serde_json::Value;
+
+use crate::{
+    config::google_sheet_authenticator::{get_credentials, SheetsEndpoint, SheetsHub},
+    interface::store::{Store, StoreResult},
+    util::{error::AppError, range::parse_range},
+};
+
+///
+/// It classifies the errors of the Sheets API:
+/// a missing token is an authentication failure and a rejected request (e.g: an invalid range) is a validation error.
+fn sheets_error(err: google_sheets4::Error) -> AppError {
+    match err {
+        google_sheets4::Error::MissingToken(err) => AppError::Auth(err.to_string()),
+        google_sheets4::Error::BadRequest(detail) => AppError::Validation(detail.to_string()),
+        err => AppError::Upstream(err.to_string())
+    }
+}
+
+///
+/// Storage backend that reads and writes a Google spreadsheet through the Sheets v4 API.
+///
+/// - `hub`: Authenticated once and reused by every request.
+pub struct GoogleSheetsStore {
+    spreadsheet_id: String,
+    hub: SheetsHub
+}
+
+impl GoogleSheetsStore {
+    ///
+    /// It authenticates against Google, or against `endpoint` when it is given
+    /// (e.g: a fake of the Sheets API), and keeps the hub for the next requests.
+    ///
+    /// `credentials_path`: Service account key provided by Google, unused when `endpoint` is given.
+    pub async fn connect(spreadsheet_id: String, credentials_path: &str, endpoint: Option<&SheetsEndpoint>) -> StoreResult<Self> {
+        let hub = get_credentials(credentials_path, endpoint)
+            .await
+            .map_err(|err| AppError::Auth(err.to_string()))?;
+
+        Ok(GoogleSheetsStore { spreadsheet_id, hub })
+    }
+}
+
+#[async_trait]
+impl Store for GoogleSheetsStore {
+    async fn ensure_sheet(&self, sheet_name: &str) -> StoreResult<()> {
+        let (_, spreadsheet) = self.hub
+            .spreadsheets()
+            .get(&self.spreadsheet_id)
+            .doit()
+            .await
+            .map_err(sheets_error)?;
+
+        let sheet_exists = spreadsheet
+            .sheets
+            .unwrap_or_default()
+            .iter()
+            .any(|sheet| {
+                sheet
+                    .properties
+                    .as_ref()
+                    .is_some_and(|properties| properties.title.as_deref() == Some(sheet_name))
+            });
+
+        if sheet_exists { return Ok(()) }
+
+        // Create the sheet if does not exists.
+        let new_sheet = AddSheetRequest {
+            properties: Some(SheetProperties {
+                title: Some(sheet_name.to_string()),
+                sheet_id: None,
+                index: None,
+                ..Default::default()
+            }),
+        };
+
+        // Update the sheets
+        let batch_update_request = BatchUpdateSpreadsheetRequest {
+            requests: Some(vec![Request {
+                add_sheet: Some(new_sheet),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        self.hub
+            .spreadsheets()
+            .batch_update(batch_update_request, &self.spreadsheet_id)
+            .doit()
+            .await
+            .map_err(sheets_error)?;
+
+        Ok(())
+    }
+
+    async fn read(&self, range: &str) -> StoreResult<Vec<Vec<Value>>> {
+        let (_, value_range) = self.hub
+            .spreadsheets()
+            .values_get(&self.spreadsheet_id, range)
+            .doit()
+            .await
+            .map_err(sheets_error)?;
+
+        Ok(value_range.values.unwrap_or_default())
+    }
+
+    async fn append(&self, range: &str, row: Vec<String>) -> StoreResult<usize> {
+        self.append_rows(range, vec![row]).await
+    }
+
+    async fn append_rows(&self, range: &str, rows: Vec<Vec<String>>) -> StoreResult<usize> {
+        let request = ValueRange {
+            major_dimension: Some("ROWS".to_string()),
+            range: Some(range.to_string()),
+            values: Some(
+                rows
+                    .iter()
+                    .map(|row| row.iter().map(|element| Into::into(element.as_str())).collect())
+                    .collect()
+            ),
+        };
+
+        let (_, response) = self.hub
+            .spreadsheets()
+            .values_append(request, &self.spreadsheet_id, range)
+            .value_input_option("USER_ENTERED")
+            .doit()
+            .await
+            .map_err(sheets_error)?;
+
+        // The API reports where the rows were written, e.g: `Projects!A3:G4`
+        response
+            .updates
+            .and_then(|updates| updates.updated_range)
+            .and_then(|updated_range| parse_range(&updated_range).ok())
+            .and_then(|updated_range| updated_range.start_row)
+            .ok_or(AppError::Upstream(format!("The append of '{}' did not report the updated range", range)))
+    }
+
+    async fn update(&self, range: &str, row: Vec<String>) -> StoreResult<()> {
+        let request = ValueRange {
+            major_dimension: Some("ROWS".to_string()),
+            range: Some(range.to_string()),
+            values: Some(vec![
+                row
+                    .iter()
+                    .map(|element| Into::into(element.as_str()))
+                    .collect(),
+            ]),
+        };
+
+        self.hub
+            .spreadsheets()
+            .values_update(request, &self.spreadsheet_id, range)
+            .value_input_option("USER_ENTERED")
+            .doit()
+            .await
+            .map_err(sheets_error)?;
+
+        Ok(())
+    }
+
+    async fn clear(&self, range: &str) -> StoreResult<()> {
+        self.hub
+            .spreadsheets()
+            .values_clear(ClearValuesRequest::default(), &self.spreadsheet_id, range)
+            .doit()
+            .await
+            .map_err(sheets_error)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use std::{collections::{BTreeMap, HashMap}, sync::Mutex};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{
+    interface::store::{Store, StoreResult},
+    util::{error::AppError, range::{assemble_rows, next_free_row, parse_range, A1Range}},
+};
+
+/// Cells of a sheet indexed by (row, col).
+type Sheet = BTreeMap<(usize, usize), String>;
+
+///
+/// Storage backend that keeps every sheet in memory, the data is lost when the server stops.
+///
+/// Useful to try the API without credentials and to run the tests.
+#[derive(Default)]
+pub struct MemoryStore {
+    sheets: Mutex<HashMap<String, Sheet>>
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+
+    /// Cells of the sheet that belong to the columns of the range.
+    fn cells(sheets: &HashMap<String, Sheet>, range: &A1Range) -> Vec<(usize, usize, String)> {
+        sheets
+            .get(&range.sheet)
+            .map(|sheet| {
+                sheet
+                    .iter()
+                    .filter(|((_, col), _)| range.contains_column(*col))
+                    .map(|((row, col), value)| (*row, *col, value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Writes the values from the column of the range in the given row.
+    fn write_row(sheets: &mut HashMap<String, Sheet>, range: &A1Range, row_index: usize, row: Vec<String>) {
+        let sheet = sheets.entry(range.sheet.clone()).or_default();
+
+        for (offset, value) in row.into_iter().enumerate() {
+            let key = (row_index, range.start_col + offset);
+
+            if value.is_empty() {
+                sheet.remove(&key);
+            } else {
+                sheet.insert(key, value);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn ensure_sheet(&self, sheet_name: &str) -> StoreResult<()> {
+        let mut sheets = self.sheets.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        sheets.entry(sheet_name.to_string()).or_default();
+
+        Ok(())
+    }
+
+    async fn read(&self, range: &str) -> StoreResult<Vec<Vec<Value>>> {
+        let a1_range = parse_range(range).map_err(AppError::Validation)?;
+        let sheets = self.sheets.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        // As Google Sheets does, a sheet must be created before it is read
+        if !sheets.contains_key(&a1_range.sheet) {
+            return Err(AppError::Validation(format!("Unable to parse range: {}", range)))
+        }
+
+        Ok(assemble_rows(&a1_range, Self::cells(&sheets, &a1_range)))
+    }
+
+    async fn append(&self, range: &str, row: Vec<String>) -> StoreResult<usize> {
+        self.append_rows(range, vec![row]).await
+    }
+
+    async fn append_rows(&self, range: &str, rows: Vec<Vec<String>>) -> StoreResult<usize> {
+        let range = parse_range(range).map_err(AppError::Validation)?;
+        let mut sheets = self.sheets.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        let row_index = next_free_row(&range, &Self::cells(&sheets, &range));
+
+        for (offset, row) in rows.into_iter().enumerate() {
+            Self::write_row(&mut sheets, &range, row_index + offset, row);
+        }
+
+        Ok(row_index)
+    }
+
+    async fn update(&self, range: &str, row: Vec<String>) -> StoreResult<()> {
+        let range = parse_range(range).map_err(AppError::Validation)?;
+        let mut sheets = self.sheets.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        Self::write_row(&mut sheets, &range, range.start_row.unwrap_or(1), row);
+
+        Ok(())
+    }
+
+    async fn clear(&self, range: &str) -> StoreResult<()> {
+        let range = parse_range(range).map_err(AppError::Validation)?;
+        let mut sheets = self.sheets.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        if let Some(sheet) = sheets.get_mut(&range.sheet) {
+            sheet.retain(|(row, col), _| !(range.contains_row(*row) && range.contains_column(*col)));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+pub mod google_sheets;
+pub mod memory;
+pub mod sqlite;
+
+use std::sync::Arc;
+
+use crate::{
+    config::{
+        google_sheet_authenticator::SheetsEndpoint,
+        settings::{Settings, StorageBackend},
+    },
+    interface::store::Store,
+};
+
+use google_sheets::GoogleSheetsStore;
+use memory::MemoryStore;
+use sqlite::SqliteStore;
+
+///
+/// It builds the storage backend selected by `storage.backend` in the settings.
+///
+/// - `google_sheets` (default): The spreadsheet identified by `spreadsheet_id`,
+///   `sheets_base_url` and `sheets_token` point it to another server (e.g: a fake of the API).
+/// - `sqlite`: A local database file located in `sqlite_path`.
+/// - `memory`: Nothing is persisted, the data is lost when the server stops.
+pub async fn build_store(settings: &Settings) -> Arc<dyn Store> {
+    let storage = &settings.storage;
+
+    match storage.backend {
+        StorageBackend::GoogleSheets => {
+            let endpoint = storage.sheets_base_url.as_ref().map(|base_url| SheetsEndpoint {
+                base_url: base_url.to_string(),
+                token: storage.sheets_token.to_string()
+            });
+
+            // The settings are validated at startup, the id is always present for this backend
+            let spreadsheet_id = storage.spreadsheet_id.clone().unwrap_or_default();
+
+            let store = GoogleSheetsStore::connect(spreadsheet_id, &storage.credentials_path, endpoint.as_ref())
+                .await
+                .expect("Could not establish the connection to the spreadsheet");
+
+            Arc::new(store)
+        }
+        StorageBackend::Sqlite => Arc::new(
+            SqliteStore::open(&storage.sqlite_path, &settings.sheets.projects).expect("Could not open the SQLite database")
+        ),
+        StorageBackend::Memory => Arc::new(MemoryStore::new())
+    }
+}
@@ -0,0 +1,337 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+
+use crate::{
+    interface::store::{Store, StoreResult},
+    util::{
+        error::AppError,
+        range::{assemble_rows, parse_range, next_free_row, A1Range},
+        util::unix_now,
+    },
+};
+
+///
+/// Storage backend that keeps the spreadsheet layout inside a local SQLite file.
+///
+/// Every cell is a row of the `cells` table, so the services keep working with
+/// the same ranges (`A:G`, `I:P`, `R:AE`, `AF:AQ`, `AR:BE`, `BG:BM`) used in Google Sheets.
+pub struct SqliteStore {
+    connection: Mutex<Connection>
+}
+
+///
+/// It describes the columns of an entity in the `Projects` sheet,
+/// used to expose every entity as a SQL view.
+struct EntityView {
+    name: &'static str,
+    first_col: usize,
+    columns: &'static [&'static str]
+}
+
+///
+/// Views created by the migration `2`.
+const VIEWS_V2: &[EntityView] = &[
+    EntityView {
+        name: "projects",
+        first_col: 0, // A
+        columns: &["id", "denomination", "description", "owner", "status", "created_at", "updated_at"]
+    },
+    EntityView {
+        name: "epics",
+        first_col: 8, // I
+        columns: &["id", "project_id", "title", "description", "status", "owner", "created_at", "updated_at"]
+    },
+    EntityView {
+        name: "user_stories",
+        first_col: 17, // R
+        columns: &[
+            "id", "project_id", "epic_id", "title", "priority", "story_points", "role", "functionality",
+            "benefit", "scenarios", "details", "created_at", "updated_at"
+        ]
+    },
+    EntityView {
+        name: "product_backlog_items",
+        first_col: 31, // AF
+        columns: &[
+            "id", "project_id", "epic_id", "title", "priority", "description", "points", "kind",
+            "assigned_at", "created_at", "updated_at"
+        ]
+    },
+    EntityView {
+        name: "sprints",
+        first_col: 43, // AR
+        columns: &[
+            "id", "project_id", "epic_id", "denomination", "description", "goal", "owner", "estimated_points",
+            "status", "completed_at", "start_date", "end_date", "created_at", "updated_at"
+        ]
+    },
+];
+
+///
+/// Views created or replaced by the migration `3`.
+const VIEWS_V3: &[EntityView] = &[
+    EntityView {
+        name: "user_stories",
+        first_col: 17, // R
+        columns: &[
+            "id", "project_id", "epic_id", "title", "priority", "story_points", "role", "functionality",
+            "benefit", "scenarios", "details", "created_at", "updated_at", "status"
+        ]
+    },
+    EntityView {
+        name: "product_backlog_items",
+        first_col: 31, // AF
+        columns: &[
+            "id", "project_id", "epic_id", "title", "priority", "description", "points", "kind",
+            "assigned_at", "created_at", "updated_at", "status"
+        ]
+    },
+    EntityView {
+        name: "team_members",
+        first_col: 58, // BG
+        columns: &["id", "name", "email", "role", "capacity_hours", "created_at", "updated_at"]
+    },
+];
+
+/// Views of every migration, in the order they are applied.
+const VIEWS: &[(i64, &[EntityView])] = &[(2, VIEWS_V2), (3, VIEWS_V3)];
+
+/// Builds a view that pivots the cells of an entity (stored in `sheet_name`) into columns.
+fn entity_view_sql(view: &EntityView, sheet_name: &str) -> String {
+    let columns = view
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(offset, column)| {
+            format!("MAX(CASE WHEN col = {} THEN value END) AS {}", view.first_col + offset, column)
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        "DROP VIEW IF EXISTS {name}; CREATE VIEW {name} AS SELECT row, {columns} FROM cells \
+         WHERE sheet = '{sheet}' AND col BETWEEN {first} AND {last} GROUP BY row;",
+        name = view.name,
+        columns = columns,
+        sheet = sheet_name,
+        first = view.first_col,
+        last = view.first_col + view.columns.len() - 1
+    )
+}
+
+/// Views of every entity stored in `sheet_name`.
+fn entity_views_sql<'a>(views: impl IntoIterator<Item = &'a EntityView>, sheet_name: &str) -> String {
+    views.into_iter().map(|view| entity_view_sql(view, sheet_name)).collect::<Vec<String>>().join("\n")
+}
+
+/// The last definition of every view.
+fn latest_views() -> Vec<&'static EntityView> {
+    let mut latest: Vec<&'static EntityView> = vec![];
+
+    for view in VIEWS.iter().flat_map(|(_, views)| views.iter()) {
+        match latest.iter().position(|other| other.name == view.name) {
+            Some(index) => latest[index] = view,
+            None => latest.push(view)
+        }
+    }
+
+    latest
+}
+
+///
+/// Schema migrations, applied in order and only once (tracked in `schema_migrations`).
+///
+/// - `1`: Sheets and cells.
+/// - `2`: A view per entity (`projects`, `epics`, `user_stories`, `product_backlog_items`, `sprints`).
+/// - `3`: The `status` of `user_stories` and `product_backlog_items`, and the `team_members` view.
+fn migrations(sheet_name: &str) -> Vec<(i64, String)> {
+    let mut migrations = vec![
+        (1, "CREATE TABLE sheets (name TEXT PRIMARY KEY);
+             CREATE TABLE cells (
+                 sheet TEXT NOT NULL,
+                 row INTEGER NOT NULL,
+                 col INTEGER NOT NULL,
+                 value TEXT NOT NULL,
+                 PRIMARY KEY (sheet, row, col)
+             );".to_string()),
+    ];
+
+    migrations.extend(VIEWS.iter().map(|(version, views)| (*version, entity_views_sql(views.iter(), sheet_name))));
+
+    migrations
+}
+
+fn sqlite_error(err: rusqlite::Error) -> AppError {
+    AppError::Upstream(err.to_string())
+}
+
+fn migrate(connection: &Connection, sheet_name: &str) -> rusqlite::Result<()> {
+    connection.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (version INTEGER PRIMARY KEY, applied_at INTEGER NOT NULL);"
+    )?;
+
+    let current_version: i64 = connection.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0)
+    )?;
+
+    for (version, sql) in migrations(sheet_name).into_iter().filter(|(version, _)| *version > current_version) {
+        let transaction = connection.unchecked_transaction()?;
+
+        transaction.execute_batch(&sql)?;
+        transaction.execute(
+            "INSERT INTO schema_migrations (version, applied_at) VALUES (?1, ?2)",
+            params![version, unix_now()]
+        )?;
+
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+impl SqliteStore {
+    ///
+    /// It opens (or creates) the database file and applies the pending migrations.
+    ///
+    /// `sheet_name`: Sheet where the entities are stored, e.g: `Projects`.
+    pub fn open(path: &str, sheet_name: &str) -> StoreResult<Self> {
+        let connection = Connection::open(path).map_err(sqlite_error)?;
+
+        migrate(&connection, sheet_name).map_err(sqlite_error)?;
+
+        // The views follow the sheet name of the settings, even if it changed after the migrations
+        connection.execute_batch(&entity_views_sql(latest_views(), sheet_name)).map_err(sqlite_error)?;
+
+        Ok(SqliteStore { connection: Mutex::new(connection) })
+    }
+
+    fn has_sheet(connection: &Connection, sheet_name: &str) -> rusqlite::Result<bool> {
+        connection.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sheets WHERE name = ?1) OR EXISTS (SELECT 1 FROM cells WHERE sheet = ?1)",
+            params![sheet_name],
+            |row| row.get(0)
+        )
+    }
+
+    /// Cells of the sheet that belong to the columns of the range.
+    fn cells(connection: &Connection, range: &A1Range) -> rusqlite::Result<Vec<(usize, usize, String)>> {
+        let mut statement = connection.prepare(
+            "SELECT row, col, value FROM cells WHERE sheet = ?1 AND col BETWEEN ?2 AND ?3"
+        )?;
+
+        let cells = statement
+            .query_map(params![range.sheet, range.start_col as i64, range.end_col as i64], |row| {
+                Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as usize, row.get::<_, String>(2)?))
+            })?
+            .collect::<rusqlite::Result<Vec<(usize, usize, String)>>>()?;
+
+        Ok(cells)
+    }
+
+    /// Writes the values from the column of the range in the given row, the caller opens the transaction.
+    fn write_row(connection: &Connection, range: &A1Range, row_index: usize, row: Vec<String>) -> rusqlite::Result<()> {
+        for (offset, value) in row.into_iter().enumerate() {
+            let col = (range.start_col + offset) as i64;
+
+            if value.is_empty() {
+                connection.execute(
+                    "DELETE FROM cells WHERE sheet = ?1 AND row = ?2 AND col = ?3",
+                    params![range.sheet, row_index as i64, col]
+                )?;
+            } else {
+                connection.execute(
+                    "INSERT INTO cells (sheet, row, col, value) VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (sheet, row, col) DO UPDATE SET value = excluded.value",
+                    params![range.sheet, row_index as i64, col, value]
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Store for SqliteStore {
+    async fn ensure_sheet(&self, sheet_name: &str) -> StoreResult<()> {
+        let connection = self.connection.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        connection
+            .execute("INSERT OR IGNORE INTO sheets (name) VALUES (?1)", params![sheet_name])
+            .map_err(sqlite_error)?;
+
+        Ok(())
+    }
+
+    async fn read(&self, range: &str) -> StoreResult<Vec<Vec<Value>>> {
+        let a1_range = parse_range(range).map_err(AppError::Validation)?;
+        let connection = self.connection.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        // As Google Sheets does, a sheet must be created before it is read (the files written before
+        // the sheets were tracked only have cells)
+        if !Self::has_sheet(&connection, &a1_range.sheet).map_err(sqlite_error)? {
+            return Err(AppError::Validation(format!("Unable to parse range: {}", range)))
+        }
+
+        let cells = Self::cells(&connection, &a1_range).map_err(sqlite_error)?;
+
+        Ok(assemble_rows(&a1_range, cells))
+    }
+
+    async fn append(&self, range: &str, row: Vec<String>) -> StoreResult<usize> {
+        self.append_rows(range, vec![row]).await
+    }
+
+    async fn append_rows(&self, range: &str, rows: Vec<Vec<String>>) -> StoreResult<usize> {
+        let range = parse_range(range).map_err(AppError::Validation)?;
+        let connection = self.connection.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        let cells = Self::cells(&connection, &range).map_err(sqlite_error)?;
+        let row_index = next_free_row(&range, &cells);
+
+        // Every row is written or none of them
+        let transaction = connection.unchecked_transaction().map_err(sqlite_error)?;
+
+        for (offset, row) in rows.into_iter().enumerate() {
+            Self::write_row(&transaction, &range, row_index + offset, row).map_err(sqlite_error)?;
+        }
+
+        transaction.commit().map_err(sqlite_error)?;
+
+        Ok(row_index)
+    }
+
+    async fn update(&self, range: &str, row: Vec<String>) -> StoreResult<()> {
+        let range = parse_range(range).map_err(AppError::Validation)?;
+        let connection = self.connection.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        let transaction = connection.unchecked_transaction().map_err(sqlite_error)?;
+
+        Self::write_row(&transaction, &range, range.start_row.unwrap_or(1), row).map_err(sqlite_error)?;
+
+        transaction.commit().map_err(sqlite_error)
+    }
+
+    async fn clear(&self, range: &str) -> StoreResult<()> {
+        let range = parse_range(range).map_err(AppError::Validation)?;
+        let connection = self.connection.lock().map_err(|err| AppError::Upstream(err.to_string()))?;
+
+        connection.execute(
+            "DELETE FROM cells WHERE sheet = ?1 AND col BETWEEN ?2 AND ?3 AND row BETWEEN ?4 AND ?5",
+            params![
+                range.sheet,
+                range.start_col as i64,
+                range.end_col as i64,
+                range.start_row.unwrap_or(1) as i64,
+                range.end_row.map_or(i64::MAX, |end| end as i64)
+            ]
+        ).map_err(sqlite_error)?;
+
+        Ok(())
+    }
+}
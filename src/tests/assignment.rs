@@ -0,0 +1,126 @@
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::{json, Value};
+
+use crate::config::app_state::AppState;
+
+use super::{call, create, member, new_item, new_member, new_story, state, with};
+
+async fn seed(state: &web::Data<AppState>) {
+    member(state).await;
+    create(state, "/member", new_member("John Roe", "john@example.com", "Developer")).await;
+
+    for project_id in ["P1", "P2"] {
+        create(state, "/pb", with(new_item(&format!("Crash on save of {}", project_id)), json!({
+            "projectId": project_id,
+            "kind": "Bug",
+            "assignedAt": 0
+        }))).await;
+    }
+
+    create(state, "/us", with(new_story("Offline mode"), json!({ "status": "InProgress" }))).await;
+}
+
+async fn work(state: &web::Data<AppState>, member_id: &str) -> Vec<Value> {
+    let (status, body) = call(state, TestRequest::get().uri(&format!("/members/{}/work", member_id))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    body["content"].as_array().unwrap().clone()
+}
+
+#[actix_web::test]
+async fn assign_and_list_the_work() {
+    let state = state();
+    seed(&state).await;
+
+    let (status, body) = call(&state, TestRequest::put().uri("/projects/P1/backlog/PB1/assignee").set_json(json!({ "memberId": "M2" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["kind"], "backlog_item");
+
+    // `assignedAt` of the item follows the assignment
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/backlog/PB1")).await;
+    assert!(body["content"][0]["assignedAt"].as_i64().unwrap() > 0);
+
+    call(&state, TestRequest::put().uri("/projects/P2/backlog/PB1/assignee").set_json(json!({ "memberId": "M2" }))).await;
+    call(&state, TestRequest::put().uri("/projects/P1/epics/E1/stories/US1/assignee").set_json(json!({ "memberId": "M2" }))).await;
+
+    let mut listed = work(&state, "M2")
+        .await
+        .iter()
+        .map(|work| format!("{}/{} {} {}", work["projectId"], work["id"], work["status"], work["points"]).replace('"', ""))
+        .collect::<Vec<String>>();
+    listed.sort();
+    assert_eq!(listed, vec!["P1/PB1 ToDo 3", "P1/US1 InProgress 5", "P2/PB1 ToDo 3"]);
+
+    // A new assignee replaces the one before
+    call(&state, TestRequest::put().uri("/projects/P1/backlog/PB1/assignee").set_json(json!({ "memberId": "M1" }))).await;
+    assert_eq!(work(&state, "M2").await.len(), 2);
+    assert_eq!(work(&state, "M1").await[0]["id"], "PB1");
+
+    let (status, _) = call(&state, TestRequest::delete().uri("/projects/P1/epics/E1/stories/US1/assignee")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(work(&state, "M2").await.len(), 1);
+
+    let (status, _) = call(&state, TestRequest::delete().uri("/projects/P1/epics/E1/stories/US1/assignee")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Removing the item removes its assignment
+    call(&state, TestRequest::delete().uri("/projects/P2/backlog/PB1")).await;
+    assert!(work(&state, "M2").await.is_empty());
+}
+
+#[actix_web::test]
+async fn assignees_are_members() {
+    let state = state();
+    seed(&state).await;
+
+    let (status, body) = call(&state, TestRequest::put().uri("/projects/P1/backlog/PB1/assignee").set_json(json!({ "memberId": "M9" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["fields"][0]["field"], "memberId");
+
+    let (status, _) = call(&state, TestRequest::put().uri("/projects/P1/backlog/PB9/assignee").set_json(json!({ "memberId": "M2" }))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = call(&state, TestRequest::get().uri("/members/M9/work")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    call(&state, TestRequest::put().uri("/projects/P1/backlog/PB1/assignee").set_json(json!({ "memberId": "M2" }))).await;
+
+    let (status, body) = call(&state, TestRequest::delete().uri("/members/M2")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["errorBug"][0], "The member M2 is assigned to P1/PB1");
+}
+
+#[actix_web::test]
+async fn assignee_is_read_with_the_entity() {
+    let state = state();
+    seed(&state).await;
+
+    call(&state, TestRequest::put().uri("/projects/P1/backlog/PB1/assignee").set_json(json!({ "memberId": "M2" }))).await;
+    call(&state, TestRequest::put().uri("/projects/P1/epics/E1/stories/US1/assignee").set_json(json!({ "memberId": "M1" }))).await;
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/backlog/PB1")).await;
+    assert_eq!(body["content"][0]["assignee"], "M2");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/pbs?id=P1")).await;
+    assert_eq!(body["content"][0]["assignee"], "M2");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/pbs?id=P2")).await;
+    assert!(body["content"][0].get("assignee").is_none());
+
+    let (_, body) = call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+    assert_eq!(body["content"][0]["assignee"], "M1");
+
+    let (_, body) = call(&state, TestRequest::patch().uri("/projects/P1/epics/E1/stories/US1").set_json(json!({ "title": "Offline" }))).await;
+    assert_eq!(body["content"][0]["assignee"], "M1");
+
+    // It is changed with its own endpoints
+    let (status, _) = call(&state, TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "assignee": "M1" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = call(&state, TestRequest::delete().uri("/projects/P1/backlog/PB1/assignee")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/backlog/PB1")).await;
+    assert!(body["content"][0].get("assignee").is_none());
+    assert_eq!(body["content"][0]["assignedAt"], 0);
+}
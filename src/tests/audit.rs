@@ -0,0 +1,202 @@
+use std::sync::{atomic::{AtomicBool, AtomicUsize, Ordering}, Arc};
+
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::{
+    config::{app_state::AppState, settings::Settings},
+    interface::store::{Store, StoreResult},
+    storage::memory::MemoryStore,
+    util::error::AppError,
+};
+
+use super::{call, call_as, create, member, new_item, new_member, new_project, new_sprint, protected_state, state, with};
+
+async fn audit(state: &web::Data<AppState>, query: &str) -> Vec<Value> {
+    let (status, body) = call(state, TestRequest::get().uri(&format!("/audit{}", query))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    body["content"].as_array().unwrap().clone()
+}
+
+///
+/// A `memory` store that counts the writes to the `Audit` sheet, they fail while `failing` is set.
+#[derive(Default)]
+struct AuditWrites {
+    store: MemoryStore,
+    writes: Arc<AtomicUsize>,
+    failing: Arc<AtomicBool>
+}
+
+impl AuditWrites {
+    fn check(&self, range: &str) -> StoreResult<()> {
+        if !range.starts_with("Audit!") { return Ok(()) }
+
+        self.writes.fetch_add(1, Ordering::SeqCst);
+
+        match self.failing.load(Ordering::SeqCst) {
+            true => Err(AppError::Upstream("The audit is not available".to_string())),
+            false => Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl Store for AuditWrites {
+    async fn ensure_sheet(&self, sheet_name: &str) -> StoreResult<()> {
+        self.store.ensure_sheet(sheet_name).await
+    }
+
+    async fn read(&self, range: &str) -> StoreResult<Vec<Vec<Value>>> {
+        self.store.read(range).await
+    }
+
+    async fn append(&self, range: &str, row: Vec<String>) -> StoreResult<usize> {
+        self.check(range)?;
+        self.store.append(range, row).await
+    }
+
+    async fn append_rows(&self, range: &str, rows: Vec<Vec<String>>) -> StoreResult<usize> {
+        self.check(range)?;
+        self.store.append_rows(range, rows).await
+    }
+
+    async fn update(&self, range: &str, row: Vec<String>) -> StoreResult<()> {
+        self.store.update(range, row).await
+    }
+
+    async fn clear(&self, range: &str) -> StoreResult<()> {
+        self.store.clear(range).await
+    }
+}
+
+fn traced(records: &[Value]) -> Vec<String> {
+    records
+        .iter()
+        .map(|record| format!("{} {} {}: {} -> {}", record["actor"], record["entityId"], record["field"], record["oldValue"], record["newValue"]).replace('"', ""))
+        .collect()
+}
+
+#[actix_web::test]
+async fn every_change_is_recorded() {
+    let state = state();
+    member(&state).await;
+
+    create(&state, "/project", with(new_project("Alpha"), json!({ "description": "" }))).await;
+
+    let created = audit(&state, "?entity=project").await;
+    assert_eq!(traced(&created)[..2], ["anonymous P1 id:  -> P1", "anonymous P1 denomination:  -> Alpha"]);
+    assert!(created.iter().all(|record| record["change"] == created[0]["change"]));
+
+    let (status, _) = call(&state, TestRequest::patch().uri("/projects/P1").set_json(json!({ "description": "Scrum board" }))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The legacy routes overwrite the cells
+    call(&state, TestRequest::patch().uri("/project").set_json(json!({ "content": "Beta", "cell": "B1" }))).await;
+
+    let records = audit(&state, "?entity=project").await;
+    let changes = traced(&records[created.len()..]);
+    assert!(changes.contains(&"anonymous P1 description:  -> Scrum board".to_string()));
+    assert!(changes.contains(&"anonymous P1 denomination: Alpha -> Beta".to_string()));
+
+    // A rejected request changes nothing
+    let (status, _) = call(&state, TestRequest::patch().uri("/projects/P1").set_json(json!({ "owner": "M9" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(audit(&state, "?entity=project").await.len(), records.len());
+
+    call(&state, TestRequest::delete().uri("/projects/P1")).await;
+
+    let removed = audit(&state, "?entity=project").await;
+    assert!(traced(&removed).contains(&"anonymous P1 denomination: Beta -> ".to_string()));
+
+    // The members are recorded too
+    assert_eq!(audit(&state, "?entity=member").await[0]["entityId"], "M1");
+    assert!(audit(&state, "?since=99999999999").await.is_empty());
+}
+
+#[actix_web::test]
+async fn cascades_share_the_change() {
+    let state = state();
+    member(&state).await;
+
+    create(&state, "/pb", new_item("Login")).await;
+    create(&state, "/sprint", new_sprint("Sprint 1")).await;
+
+    let (status, _) = call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB1" }))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let records = audit(&state, "").await;
+    let added = records.iter().filter(|record| record["entity"] == "sprint_item").collect::<Vec<&Value>>();
+    assert_eq!(added[0]["entityId"], "P1/S1/PB1");
+
+    // The points of the sprint are recomputed in the same change
+    let recomputed = records
+        .iter()
+        .find(|record| record["entity"] == "sprint" && record["field"] == "estimatedPoints" && record["change"] == added[0]["change"])
+        .unwrap();
+    assert_eq!((&recomputed["oldValue"], &recomputed["newValue"]), (&json!("0"), &json!("3")));
+
+    let (status, _) = call(&state, TestRequest::put().uri("/projects/P1/backlog/PB1/assignee").set_json(json!({ "memberId": "M1" }))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let assigned = audit(&state, "?entity=assignment").await;
+    assert!(traced(&assigned).contains(&"anonymous P1/backlog_item/PB1 memberId:  -> M1".to_string()));
+
+    let item = audit(&state, "?entity=backlog_item").await;
+    let stamped = item.iter().find(|record| record["field"] == "assignedAt" && record["change"] == assigned[0]["change"]).unwrap();
+    assert_eq!(stamped["oldValue"], "1700000100");
+}
+
+#[actix_web::test]
+async fn callers_only_see_the_records_of_their_projects() {
+    let state = protected_state();
+
+    for (name, email) in [("Jane Doe", "jane@example.com"), ("John Roe", "john@example.com")] {
+        call_as(&state, "key-of-jane", TestRequest::post().uri("/member").set_json(new_member(name, email, "Developer"))).await;
+    }
+
+    call_as(&state, "key-of-jane", TestRequest::post().uri("/project").set_json(new_project("Alpha"))).await;
+    call_as(&state, "key-of-john", TestRequest::post().uri("/project").set_json(with(new_project("Beta"), json!({ "owner": "M2" })))).await;
+
+    let entity_ids = |body: &Value| -> Vec<String> {
+        body["content"].as_array().unwrap().iter().map(|record| record["entityId"].as_str().unwrap().to_string()).collect()
+    };
+
+    let (status, body) = call_as(&state, "key-of-john", TestRequest::get().uri("/audit")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let seen = entity_ids(&body);
+    assert!(seen.contains(&"P2".to_string()) && seen.contains(&"P2/M2".to_string()));
+    assert!(!seen.iter().any(|entity_id| entity_id.split('/').next() == Some("P1")));
+
+    // The members are outside of the projects
+    assert!(seen.contains(&"M1".to_string()));
+
+    let (_, body) = call_as(&state, "key-of-jane", TestRequest::get().uri("/audit?entity=permission")).await;
+    let granted = entity_ids(&body);
+    assert!(!granted.is_empty() && granted.iter().all(|entity_id| entity_id == "P1/M1"));
+}
+
+#[actix_web::test]
+async fn a_change_is_written_at_once_or_fails() {
+    let store = AuditWrites::default();
+    let (writes, failing) = (store.writes.clone(), store.failing.clone());
+
+    let state = web::Data::new(AppState::new(Arc::new(store), Settings::default()));
+    member(&state).await;
+
+    // Every field of the member in one write
+    assert_eq!(writes.load(Ordering::SeqCst), 1);
+    assert_eq!(audit(&state, "?entity=member").await.len(), 7);
+
+    create(&state, "/project", new_project("Alpha")).await;
+    assert_eq!(writes.load(Ordering::SeqCst), 2);
+
+    // A change that can not be recorded is reported to the caller
+    failing.store(true, Ordering::SeqCst);
+
+    let (status, body) = call(&state, TestRequest::patch().uri("/projects/P1").set_json(json!({ "description": "Scrum board" }))).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert_eq!(body["code"], "UPSTREAM");
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::json;
+
+use crate::{config::{app_state::AppState, settings::Settings}, storage::google_sheets::GoogleSheetsStore};
+
+use super::{
+    call,
+    fake_sheets::{start_fake_sheets, FakeSheets, FAKE_SPREADSHEET_ID},
+    member, new_epic, new_item, new_project, new_story, with,
+};
+
+///
+/// A state whose store talks to a fake of the Sheets API through the real `google-sheets4` client.
+async fn fake_state(fake: &web::Data<FakeSheets>) -> web::Data<AppState> {
+    // As in `main`, the tests of the other modules may have installed it already
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let endpoint = start_fake_sheets(fake.clone());
+
+    let store = GoogleSheetsStore::connect(FAKE_SPREADSHEET_ID.to_string(), "credentials.json", Some(&endpoint))
+        .await
+        .unwrap();
+
+    web::Data::new(AppState::new(Arc::new(store), Settings::default()))
+}
+
+#[actix_web::test]
+async fn project_routes_use_columns_a_to_g() {
+    let fake = web::Data::new(FakeSheets::new(&["Sheet1"]));
+    let state = fake_state(&fake).await;
+    member(&state).await;
+
+    let (status, body) = call(&state, TestRequest::post().uri("/project").set_json(new_project("Alpha"))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(fake.sheet_names().contains(&"Projects".to_string()));
+
+    // The row comes from the range reported by `values.append`
+    assert_eq!(body["content"][0]["row"], "A1:G1");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects")).await;
+    assert_eq!(body["content"][0]["id"], "P1");
+
+    call(&state, TestRequest::delete().uri("/project").set_json(json!({ "range": ["A1", "G1"] }))).await;
+
+    let requests = fake.requests();
+    assert!(requests.contains(&"batchUpdate addSheet Projects".to_string()));
+    assert!(requests.contains(&"values.append Projects!A1:G1".to_string()));
+    assert!(requests.contains(&"values.get Projects!A:G".to_string()));
+    assert!(requests.contains(&"values.clear Projects!A1:G1".to_string()));
+    assert!(requests.contains(&"values.append Projects!BG1:BM1".to_string()));
+}
+
+#[actix_web::test]
+async fn child_entities_use_their_own_columns() {
+    let fake = web::Data::new(FakeSheets::new(&["Projects"]));
+    let state = fake_state(&fake).await;
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P1", "Login"))).await;
+    call(&state, TestRequest::get().uri("/epics?id=P1")).await;
+
+    call(&state, TestRequest::post().uri("/us").set_json(new_story("Offline mode"))).await;
+    call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+
+    call(&state, TestRequest::post().uri("/pb").set_json(with(new_item("Crash on save"), json!({ "kind": "Bug" })))).await;
+    call(&state, TestRequest::get().uri("/pbs?id=P1")).await;
+
+    call(&state, TestRequest::get().uri("/sprints?id=P1")).await;
+
+    let requests = fake.requests();
+    for expected in [
+        "values.append Projects!I1:P1",
+        "values.get Projects!I:R",
+        "values.append Projects!R1:AE1",
+        "values.get Projects!R:AE",
+        "values.append Projects!AF1:AQ1",
+        "values.get Projects!AF:AQ",
+        "values.get Projects!AR:BE",
+        "values.get Projects!BG:BM",
+    ] {
+        assert!(requests.contains(&expected.to_string()), "missing request: {}", expected);
+    }
+}
+
+#[actix_web::test]
+async fn merge_patch_writes_the_row_once() {
+    let fake = web::Data::new(FakeSheets::new(&["Projects"]));
+    let state = fake_state(&fake).await;
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P1", "Login"))).await;
+
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/epics/E1").set_json(json!({ "title": "Auth", "status": "Done" }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let updates = fake
+        .requests()
+        .into_iter()
+        .filter(|request| request.starts_with("values.update"))
+        .collect::<Vec<String>>();
+    assert_eq!(updates, vec!["values.update Projects!I1:P1".to_string()]);
+}
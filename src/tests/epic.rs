@@ -0,0 +1,68 @@
+use actix_web::{http::StatusCode, test::TestRequest};
+use serde_json::json;
+
+use super::{call, member, new_epic, state};
+
+#[actix_web::test]
+async fn create_and_read_epics_by_project() {
+    let state = state();
+    member(&state).await;
+
+    let (status, _) = call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P1", "Login"))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P1", "Sign up"))).await;
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P2", "Reports"))).await;
+
+    let (status, body) = call(&state, TestRequest::get().uri("/epics?id=P1")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let epics = body["content"].as_array().unwrap();
+    assert_eq!(epics.len(), 2);
+    assert_eq!(epics[0]["id"], "E1");
+    assert_eq!(epics[1]["id"], "E2");
+    assert_eq!(epics[1]["title"], "Sign up");
+
+    // Epics are numbered per project
+    let (_, body) = call(&state, TestRequest::get().uri("/epics?id=P2")).await;
+    assert_eq!(body["content"][0]["id"], "E1");
+    assert_eq!(body["content"][0]["title"], "Reports");
+}
+
+#[actix_web::test]
+async fn update_epic_cell() {
+    let state = state();
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P1", "Login"))).await;
+
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/epic").set_json(json!({ "content": "Authentication", "cell": "K1" }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/epics?id=P1")).await;
+    assert_eq!(body["content"][0]["title"], "Authentication");
+}
+
+#[actix_web::test]
+async fn delete_epic_range() {
+    let state = state();
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P1", "Login"))).await;
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P2", "Reports"))).await;
+
+    let (status, _) = call(
+        &state,
+        TestRequest::delete().uri("/epic").set_json(json!({ "range": ["I2", "P2"] }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/epics?id=P2")).await;
+    assert!(body["content"].as_array().unwrap().is_empty());
+
+    let (_, body) = call(&state, TestRequest::get().uri("/epics?id=P1")).await;
+    assert_eq!(body["content"].as_array().unwrap().len(), 1);
+}
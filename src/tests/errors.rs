@@ -0,0 +1,87 @@
+use actix_web::{http::StatusCode, test::TestRequest};
+use serde_json::json;
+
+use super::{call, member, new_project, state};
+
+#[actix_web::test]
+async fn corrupt_row_is_reported_instead_of_panicking() {
+    let state = state();
+
+    state.store
+        .append("Projects!I1:P1", vec![
+            "E1".to_string(), "P1".to_string(), "Login".to_string(), "".to_string(),
+            "Finished".to_string(), "Jane Doe".to_string(), "1700000000".to_string(), "1700000000".to_string()
+        ])
+        .await
+        .unwrap();
+
+    let (status, body) = call(&state, TestRequest::get().uri("/epics?id=P1")).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["message"], "The stored data could not be read");
+    assert_eq!(body["code"], "CORRUPT_ROW");
+    assert_eq!(body["content"], json!(null));
+    assert!(body["errorBug"][0].as_str().unwrap().contains("status"));
+}
+
+#[actix_web::test]
+async fn invalid_cell_is_a_bad_request() {
+    let state = state();
+
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/project").set_json(json!({ "content": "Renamed", "cell": "1B" }))
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["message"], "The request is not valid");
+
+    // Cells outside of the columns of the entity are rejected
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/epic").set_json(json!({ "content": "Renamed", "cell": "B1" }))
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn missing_query_is_a_bad_request() {
+    let state = state();
+
+    let (status, _) = call(&state, TestRequest::get().uri("/sprints")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = call(&state, TestRequest::get().uri("/uss?projectId=P1")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn malformed_body_is_a_bad_request() {
+    let state = state();
+
+    let (status, body) = call(&state, TestRequest::post().uri("/epic").set_json(json!({ "title": "Login" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["message"], "The request is not valid");
+    assert_eq!(body["code"], "VALIDATION");
+    assert_eq!(body["fields"][0]["field"], "projectId");
+
+    let (status, body) = call(
+        &state,
+        TestRequest::post().uri("/epic").insert_header(("content-type", "application/json")).set_payload("{")
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["fields"], json!(null));
+}
+
+#[actix_web::test]
+async fn identificator_without_number_is_skipped() {
+    let state = state();
+    member(&state).await;
+
+    state.store.append("Projects!A1:G1", vec!["P".to_string()]).await.unwrap();
+
+    let (status, body) = call(
+        &state,
+        TestRequest::post().uri("/project").set_json(new_project("Little Things"))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["id"], "P1");
+}
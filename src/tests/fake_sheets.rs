@@ -0,0 +1,276 @@
+use std::sync::Mutex;
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use serde_json::{json, Value};
+
+use crate::{
+    config::google_sheet_authenticator::SheetsEndpoint,
+    interface::store::Store,
+    storage::memory::MemoryStore,
+    util::range::parse_range,
+};
+
+pub const FAKE_SPREADSHEET_ID: &str = "fake-spreadsheet";
+pub const FAKE_TOKEN: &str = "fake-token";
+
+///
+/// In-memory fake of the Sheets v4 API, it answers the endpoints used by `GoogleSheetsStore`:
+/// `spreadsheets.get`, `batchUpdate` (add/delete sheet), `values.get`, `values.append`,
+/// `values.update` and `values.clear`.
+///
+/// FakeSheets attributes:
+///
+/// - `cells`: The values of every sheet.
+/// - `sheets`: (sheet_id, title) of the existing sheets, ranges of other sheets are rejected like Google does.
+/// - `requests`: Log of the requests received, e.g: `values.append Projects!A1:G1`.
+pub struct FakeSheets {
+    cells: MemoryStore,
+    sheets: Mutex<Vec<(i32, String)>>,
+    requests: Mutex<Vec<String>>
+}
+
+impl FakeSheets {
+    pub fn new(sheet_names: &[&str]) -> Self {
+        FakeSheets {
+            cells: MemoryStore::new(),
+            sheets: Mutex::new(
+                sheet_names
+                    .iter()
+                    .enumerate()
+                    .map(|(index, name)| (index as i32, name.to_string()))
+                    .collect()
+            ),
+            requests: Mutex::new(vec![])
+        }
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn sheet_names(&self) -> Vec<String> {
+        self.sheets.lock().unwrap().iter().map(|(_, name)| name.clone()).collect()
+    }
+
+    fn log(&self, request: String) {
+        self.requests.lock().unwrap().push(request);
+    }
+
+    /// Checks that the range is valid and belongs to an existing sheet, returning the sheet.
+    /// The error is the message of the API.
+    fn validate(&self, range: &str) -> Result<String, String> {
+        let parsed = parse_range(range)?;
+
+        if !self.sheet_names().contains(&parsed.sheet) {
+            return Err(format!("Unable to parse range: {}", range))
+        }
+
+        Ok(parsed.sheet)
+    }
+}
+
+fn bad_request(message: String) -> HttpResponse {
+    HttpResponse::BadRequest().json(json!({
+        "error": { "code": 400, "message": message, "status": "INVALID_ARGUMENT" }
+    }))
+}
+
+/// Rows of the `values` of a write.
+fn rows(body: &Value) -> Vec<Vec<String>> {
+    body["values"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .map(|row| row
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .map(|value| value.as_str().map(String::from).unwrap_or(value.to_string()))
+            .collect())
+        .collect()
+}
+
+/// Only single-row updates are supported, like the ones sent by `GoogleSheetsStore`.
+fn single_row(body: &Value) -> Result<Vec<String>, String> {
+    match rows(body).as_slice() {
+        [row] => Ok(row.clone()),
+        _ => Err("The fake only supports updating one row per request".to_string())
+    }
+}
+
+///
+/// It converts a zero based index into its column, e.g: 0 -> `A`, 26 -> `AA`.
+/// Only the fake writes columns back, in the ranges it reports.
+pub fn index_to_column(index: usize) -> String {
+    let mut column = String::new();
+    let mut rest = index + 1;
+
+    while rest > 0 {
+        column.insert(0, (b'A' + ((rest - 1) % 26) as u8) as char);
+        rest = (rest - 1) / 26;
+    }
+
+    column
+}
+
+/// The range of the rows written by an append, e.g: `Projects!A1:G1` appended in the rows 3 and 4 is `Projects!A3:G4`.
+fn written_range(range: &str, first_row: usize, last_row: usize) -> String {
+    match parse_range(range) {
+        Ok(parsed) => format!(
+            "{}!{}{}:{}{}",
+            parsed.sheet,
+            index_to_column(parsed.start_col),
+            first_row,
+            index_to_column(parsed.end_col),
+            last_row
+        ),
+        Err(_) => range.to_string()
+    }
+}
+
+async fn get_spreadsheet(fake: web::Data<FakeSheets>, path: web::Path<String>) -> HttpResponse {
+    fake.log("spreadsheets.get".to_string());
+
+    let sheets = fake.sheets.lock().unwrap().iter()
+        .enumerate()
+        .map(|(index, (sheet_id, title))| json!({
+            "properties": { "sheetId": sheet_id, "title": title, "index": index }
+        }))
+        .collect::<Vec<Value>>();
+
+    HttpResponse::Ok().json(json!({ "spreadsheetId": path.into_inner(), "sheets": sheets }))
+}
+
+async fn batch_update(fake: web::Data<FakeSheets>, path: web::Path<String>, body: web::Json<Value>) -> HttpResponse {
+    let spreadsheet_id = path.into_inner();
+
+    let Some(spreadsheet_id) = spreadsheet_id.strip_suffix(":batchUpdate") else {
+        return HttpResponse::NotFound().finish()
+    };
+
+    let requests = body["requests"].as_array().cloned().unwrap_or_default();
+    let mut sheets = fake.sheets.lock().unwrap();
+
+    for request in requests.iter() {
+        if let Some(title) = request["addSheet"]["properties"]["title"].as_str() {
+            fake.log(format!("batchUpdate addSheet {}", title));
+
+            let sheet_id = sheets.iter().map(|(sheet_id, _)| sheet_id + 1).max().unwrap_or(0);
+            sheets.push((sheet_id, title.to_string()));
+        }
+
+        if let Some(sheet_id) = request["deleteSheet"]["sheetId"].as_i64() {
+            fake.log(format!("batchUpdate deleteSheet {}", sheet_id));
+
+            sheets.retain(|(id, _)| *id as i64 != sheet_id);
+        }
+    }
+
+    HttpResponse::Ok().json(json!({ "spreadsheetId": spreadsheet_id, "replies": [] }))
+}
+
+async fn get_values(fake: web::Data<FakeSheets>, path: web::Path<(String, String)>) -> HttpResponse {
+    let (_, range) = path.into_inner();
+
+    fake.log(format!("values.get {}", range));
+
+    let sheet = match fake.validate(&range) {
+        Ok(sheet) => sheet,
+        Err(message) => return bad_request(message)
+    };
+
+    // The sheets are the ones of the fake, the cells may not have been written yet
+    if let Err(err) = fake.cells.ensure_sheet(&sheet).await { return bad_request(err.to_string()) }
+
+    match fake.cells.read(&range).await {
+        Ok(values) if values.is_empty() => HttpResponse::Ok().json(json!({ "range": range, "majorDimension": "ROWS" })),
+        Ok(values) => HttpResponse::Ok().json(json!({ "range": range, "majorDimension": "ROWS", "values": values })),
+        Err(err) => bad_request(err.to_string())
+    }
+}
+
+async fn update_values(fake: web::Data<FakeSheets>, path: web::Path<(String, String)>, body: web::Json<Value>) -> HttpResponse {
+    let (spreadsheet_id, range) = path.into_inner();
+
+    fake.log(format!("values.update {}", range));
+
+    if let Err(message) = fake.validate(&range) { return bad_request(message) }
+
+    let row = match single_row(&body) {
+        Ok(row) => row,
+        Err(message) => return bad_request(message)
+    };
+
+    match fake.cells.update(&range, row).await {
+        Ok(_) => HttpResponse::Ok().json(json!({ "spreadsheetId": spreadsheet_id, "updatedRange": range })),
+        Err(err) => bad_request(err.to_string())
+    }
+}
+
+///
+/// `values.append` and `values.clear` share the path, they are told apart by their suffix.
+async fn post_values(fake: web::Data<FakeSheets>, path: web::Path<(String, String)>, body: web::Json<Value>) -> HttpResponse {
+    let (spreadsheet_id, range) = path.into_inner();
+
+    if let Some(range) = range.strip_suffix(":append") {
+        fake.log(format!("values.append {}", range));
+
+        if let Err(message) = fake.validate(range) { return bad_request(message) }
+
+        let rows = rows(&body);
+        let count = rows.len().max(1);
+
+        return match fake.cells.append_rows(range, rows).await {
+            Ok(row_index) => HttpResponse::Ok().json(json!({
+                "spreadsheetId": spreadsheet_id,
+                "tableRange": range,
+                "updates": { "spreadsheetId": spreadsheet_id, "updatedRange": written_range(range, row_index, row_index + count - 1) }
+            })),
+            Err(err) => bad_request(err.to_string())
+        }
+    }
+
+    if let Some(range) = range.strip_suffix(":clear") {
+        fake.log(format!("values.clear {}", range));
+
+        if let Err(message) = fake.validate(range) { return bad_request(message) }
+
+        return match fake.cells.clear(range).await {
+            Ok(_) => HttpResponse::Ok().json(json!({ "spreadsheetId": spreadsheet_id, "clearedRange": range })),
+            Err(err) => bad_request(err.to_string())
+        }
+    }
+
+    HttpResponse::NotFound().finish()
+}
+
+///
+/// Registers the routes of the fake, paths are the same as `https://sheets.googleapis.com/v4/`.
+pub fn fake_routes(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/v4/spreadsheets/{id}", web::get().to(get_spreadsheet))
+        .route("/v4/spreadsheets/{id}", web::post().to(batch_update))
+        .route("/v4/spreadsheets/{id}/values/{range}", web::get().to(get_values))
+        .route("/v4/spreadsheets/{id}/values/{range}", web::put().to(update_values))
+        .route("/v4/spreadsheets/{id}/values/{range}", web::post().to(post_values));
+}
+
+///
+/// It starts the fake in a random local port and returns the endpoint to reach it.
+pub fn start_fake_sheets(fake: web::Data<FakeSheets>) -> SheetsEndpoint {
+    let server = HttpServer::new(move || App::new().app_data(fake.clone()).configure(fake_routes))
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .expect("Could not bind the fake Sheets server");
+
+    let address = server.addrs()[0];
+
+    actix_web::rt::spawn(server.run());
+
+    SheetsEndpoint {
+        base_url: format!("http://{}", address),
+        token: FAKE_TOKEN.to_string()
+    }
+}
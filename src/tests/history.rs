@@ -0,0 +1,108 @@
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::{json, Value};
+
+use crate::config::app_state::AppState;
+
+use super::{call, create, member, new_story, state, with};
+
+const STORY: &str = "/projects/P1/epics/E1/stories/US1";
+
+fn criteria(then: &str) -> Value {
+    json!([[{ "kind": "Success", "given": "a saved draft", "when": "the network is lost", "then": then }], []])
+}
+
+async fn history(state: &web::Data<AppState>, path: &str) -> Vec<Value> {
+    let (status, body) = call(state, TestRequest::get().uri(&format!("{}/history", path))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    body["content"].as_array().unwrap().clone()
+}
+
+#[actix_web::test]
+async fn an_overwrite_is_undone() {
+    let state = state();
+    member(&state).await;
+
+    create(&state, "/us", with(new_story("Offline mode"), json!({ "acceptance_criteria": criteria("the draft is kept") }))).await;
+
+    call(&state, TestRequest::patch().uri(STORY).set_json(json!({ "acceptanceCriteria": criteria("the draft is lost") }))).await;
+    call(&state, TestRequest::patch().uri(STORY).set_json(json!({ "title": "Offline" }))).await;
+
+    let versions = history(&state, STORY).await;
+    assert_eq!(versions.len(), 3);
+    assert_eq!(versions[0]["version"], 1);
+    assert_eq!(versions[0]["actor"], "anonymous");
+    assert_eq!(versions[1]["fields"], json!(["scenarios", "updatedAt"]));
+    assert_eq!(versions[2]["entity"]["title"], "Offline");
+
+    let (status, body) = call(&state, TestRequest::post().uri(&format!("{}/restore?version=1", STORY))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["content"][0]["acceptanceCriteria"], criteria("the draft is kept"));
+    assert_eq!(body["content"][0]["title"], "Offline mode");
+
+    // The restore is one more version
+    let versions = history(&state, STORY).await;
+    assert_eq!(versions.len(), 4);
+    assert_eq!(versions[3]["entity"]["acceptanceCriteria"], criteria("the draft is kept"));
+
+    let (status, _) = call(&state, TestRequest::post().uri(&format!("{}/restore?version=9", STORY))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // A removed story keeps its history, but it is not restored
+    call(&state, TestRequest::delete().uri(STORY)).await;
+
+    let versions = history(&state, STORY).await;
+    assert_eq!(versions.len(), 5);
+    assert!(versions[4]["entity"].is_null());
+
+    let (status, _) = call(&state, TestRequest::post().uri(&format!("{}/restore?version=1", STORY))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn entities_before_the_audit_have_a_first_version() {
+    let state = state();
+
+    // Stored before the changes were recorded
+    state.store.append("Projects!A1:G1", vec![
+        "P1".to_string(), "Alpha".to_string(), "".to_string(), "".to_string(), "Pending".to_string(), "1700000000".to_string(), "1700000000".to_string(),
+    ]).await.unwrap();
+
+    call(&state, TestRequest::patch().uri("/projects/P1").set_json(json!({ "denomination": "Beta" }))).await;
+
+    let versions = history(&state, "/projects/P1").await;
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["version"], 0);
+    assert_eq!(versions[0]["entity"]["denomination"], "Alpha");
+    assert_eq!(versions[1]["entity"]["denomination"], "Beta");
+
+    let (status, _) = call(&state, TestRequest::post().uri("/projects/P1/restore?version=0")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1")).await;
+    assert_eq!(body["content"][0]["denomination"], "Alpha");
+
+    let (status, _) = call(&state, TestRequest::get().uri("/projects/P9/history")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn a_restore_follows_the_workflow() {
+    let state = state();
+    member(&state).await;
+
+    create(&state, "/us", new_story("Offline mode")).await;
+
+    for status in ["InProgress", "Done"] {
+        call(&state, TestRequest::patch().uri(STORY).set_json(json!({ "status": status }))).await;
+    }
+
+    // `Done` only goes back to `InProgress`
+    let (status, body) = call(&state, TestRequest::post().uri(&format!("{}/restore?version=1", STORY))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "INVALID_TRANSITION");
+
+    let (status, body) = call(&state, TestRequest::post().uri(&format!("{}/restore?version=2", STORY))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["status"], "InProgress");
+}
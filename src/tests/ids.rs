@@ -0,0 +1,158 @@
+use std::{collections::HashSet, sync::Arc};
+
+use actix_web::{http::StatusCode, rt, test::TestRequest, web};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::{
+    config::{app_state::AppState, settings::{IdMode, Settings}},
+    interface::store::{Store, StoreResult},
+    storage::memory::MemoryStore,
+};
+
+use super::{call, new_epic, new_project, new_sprint, state, with};
+
+///
+/// A `memory` store that yields before every operation,
+/// so concurrent requests interleave as they do with a remote backend.
+#[derive(Default)]
+struct YieldingStore(MemoryStore);
+
+#[async_trait]
+impl Store for YieldingStore {
+    async fn ensure_sheet(&self, sheet_name: &str) -> StoreResult<()> {
+        rt::task::yield_now().await;
+        self.0.ensure_sheet(sheet_name).await
+    }
+
+    async fn read(&self, range: &str) -> StoreResult<Vec<Vec<Value>>> {
+        rt::task::yield_now().await;
+        self.0.read(range).await
+    }
+
+    async fn append(&self, range: &str, row: Vec<String>) -> StoreResult<usize> {
+        rt::task::yield_now().await;
+        self.0.append(range, row).await
+    }
+
+    async fn append_rows(&self, range: &str, rows: Vec<Vec<String>>) -> StoreResult<usize> {
+        rt::task::yield_now().await;
+        self.0.append_rows(range, rows).await
+    }
+
+    async fn update(&self, range: &str, row: Vec<String>) -> StoreResult<()> {
+        rt::task::yield_now().await;
+        self.0.update(range, row).await
+    }
+
+    async fn clear(&self, range: &str) -> StoreResult<()> {
+        rt::task::yield_now().await;
+        self.0.clear(range).await
+    }
+}
+
+///
+/// An epic without owner, no member is created by these tests.
+fn epic_of(project_id: &str) -> Value {
+    with(new_epic(project_id, "Login"), json!({ "owner": "" }))
+}
+
+///
+/// It sends the requests at the same time, returning the ids created.
+async fn create_concurrently(state: &web::Data<AppState>, uri: &'static str, bodies: Vec<Value>) -> Vec<String> {
+    let tasks = bodies
+        .into_iter()
+        .map(|body| {
+            let state = state.clone();
+            rt::spawn(async move { call(&state, TestRequest::post().uri(uri).set_json(body)).await })
+        })
+        .collect::<Vec<_>>();
+
+    let mut ids = vec![];
+
+    for task in tasks {
+        let (status, body) = task.await.unwrap();
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        ids.push(body["content"][0]["id"].as_str().unwrap().to_string());
+    }
+
+    ids
+}
+
+#[actix_web::test]
+async fn concurrent_creates_get_distinct_ids() {
+    let state = web::Data::new(AppState::new(Arc::new(YieldingStore::default()), Settings::default()));
+
+    let bodies = (0..20)
+        .map(|index| with(new_project(&format!("Project {}", index)), json!({ "owner": "" })))
+        .collect();
+
+    let ids = create_concurrently(&state, "/project", bodies).await;
+
+    let distinct = ids.iter().cloned().collect::<HashSet<String>>();
+    let expected = (1..=20).map(|number| format!("P{}", number)).collect::<HashSet<String>>();
+    assert_eq!(distinct, expected);
+
+    // The epics are counted per project
+    let bodies = ["P1", "P2", "P1", "P2", "P1"].iter().map(|project_id| epic_of(project_id)).collect();
+    let mut ids = create_concurrently(&state, "/epic", bodies).await;
+    ids.sort();
+    assert_eq!(ids, vec!["E1", "E1", "E2", "E2", "E3"]);
+
+    let counters = state.store.read("Metadata!A:B").await.unwrap();
+    assert_eq!(counters[0], vec![json!("P"), json!("20")]);
+    assert!(counters.contains(&vec![json!("E/P1"), json!("3")]));
+}
+
+#[actix_web::test]
+async fn counters_are_not_reused_after_delete() {
+    let state = state();
+
+    let project = with(new_project("Alpha"), json!({ "owner": "" }));
+
+    call(&state, TestRequest::post().uri("/project").set_json(project.clone())).await;
+    call(&state, TestRequest::post().uri("/project").set_json(project.clone())).await;
+    call(&state, TestRequest::delete().uri("/projects/P2")).await;
+
+    let (_, body) = call(&state, TestRequest::post().uri("/project").set_json(project)).await;
+    assert_eq!(body["content"][0]["id"], "P3");
+    assert_eq!(body["content"][0]["row"], "A2:G2");
+}
+
+#[actix_web::test]
+async fn first_sprint_is_numbered() {
+    let state = state();
+
+    let (status, body) = call(&state, TestRequest::post().uri("/sprint").set_json(with(new_sprint("Sprint 1"), json!({ "owner": "" })))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["id"], "S1");
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["denomination"], "Sprint 1");
+}
+
+#[actix_web::test]
+async fn ulid_mode_keeps_no_counter() {
+    let mut settings = Settings::default();
+    settings.ids.mode = IdMode::Ulid;
+
+    let state = web::Data::new(AppState::new(Arc::new(YieldingStore::default()), settings));
+
+    let ids = create_concurrently(&state, "/epic", (0..10).map(|_| epic_of("P1")).collect()).await;
+
+    assert_eq!(ids.iter().collect::<HashSet<&String>>().len(), 10);
+
+    for id in &ids {
+        assert_eq!(id.len(), 27, "{}", id);
+        assert!(id.starts_with('E'));
+    }
+
+    // They are sorted as they were created
+    let (_, body) = call(&state, TestRequest::post().uri("/epic").set_json(epic_of("P1"))).await;
+    let last = body["content"][0]["id"].as_str().unwrap();
+    assert!(ids.iter().all(|id| id.as_str() < last));
+
+    // The sheet of the counters is not even created
+    assert!(state.store.read("Metadata!A:B").await.is_err());
+}
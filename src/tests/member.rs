@@ -0,0 +1,85 @@
+use actix_web::{http::StatusCode, test::TestRequest};
+use serde_json::json;
+
+use super::{call, member, new_member, new_project, state, with};
+
+#[actix_web::test]
+async fn create_read_and_filter_members() {
+    let state = state();
+    member(&state).await;
+
+    let (status, body) = call(&state, TestRequest::post().uri("/member").set_json(new_member("John Roe", "john@example.com", "Developer"))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["id"], "M2");
+    assert_eq!(body["content"][0]["row"], "BG2:BM2");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/members")).await;
+    assert_eq!(body["content"].as_array().unwrap().len(), 2);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/members?role=Developer")).await;
+    assert_eq!(body["content"].as_array().unwrap().len(), 1);
+    assert_eq!(body["content"][0]["name"], "John Roe");
+
+    let (status, body) = call(&state, TestRequest::patch().uri("/members/M2").set_json(json!({ "capacityHours": 30 }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["capacityHours"], 30);
+
+    let (status, _) = call(&state, TestRequest::delete().uri("/members/M2")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = call(&state, TestRequest::get().uri("/members/M2")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn invalid_members_are_rejected() {
+    let state = state();
+
+    let (status, body) = call(&state, TestRequest::post().uri("/member").set_json(new_member(" ", "john", "Developer"))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let fields = body["fields"].as_array().unwrap().iter().map(|field| field["field"].clone()).collect::<Vec<_>>();
+    assert_eq!(fields, vec![json!("name"), json!("email")]);
+
+    let (status, _) = call(&state, TestRequest::post().uri("/member").set_json(new_member("John Roe", "john@example.com", "Tester"))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // No id was spent on them
+    member(&state).await;
+    let (status, _) = call(&state, TestRequest::get().uri("/members/M1")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = call(&state, TestRequest::patch().uri("/members/M1").set_json(json!({ "capacityHours": -1 }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn owners_are_members() {
+    let state = state();
+    member(&state).await;
+
+    let project = |owner: &str| with(new_project("Alpha"), json!({ "owner": owner }));
+
+    let (status, body) = call(&state, TestRequest::post().uri("/project").set_json(project("Jane Doe"))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["fields"][0]["field"], "owner");
+    assert_eq!(body["fields"][0]["message"], "There is no team member Jane Doe");
+
+    let (status, _) = call(&state, TestRequest::post().uri("/project").set_json(project("M1"))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = call(&state, TestRequest::patch().uri("/projects/P1").set_json(json!({ "owner": "M9" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = call(&state, TestRequest::patch().uri("/project").set_json(json!({ "content": "M9", "cell": "D1" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // The owner is still a member
+    let (status, body) = call(&state, TestRequest::delete().uri("/members/M1")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["errorBug"][0], "The member M1 is the owner of P1");
+
+    call(&state, TestRequest::patch().uri("/projects/P1").set_json(json!({ "owner": "" }))).await;
+
+    let (status, _) = call(&state, TestRequest::delete().uri("/members/M1")).await;
+    assert_eq!(status, StatusCode::OK);
+}
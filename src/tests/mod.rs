@@ -0,0 +1,195 @@
+mod assignment;
+mod audit;
+mod auth;
+mod contract;
+mod epic;
+mod errors;
+mod fake_sheets;
+mod history;
+mod ids;
+mod member;
+mod permission;
+mod product_backlog;
+mod project;
+mod resource;
+mod routes;
+mod settings;
+mod sprint;
+mod sprint_chart;
+mod sprint_item;
+mod storage;
+mod summary;
+mod trash;
+mod user_story;
+mod util;
+mod velocity;
+mod workflow;
+
+use std::{collections::HashMap, sync::Arc};
+
+use actix_web::{http::StatusCode, middleware::from_fn, test, web, App};
+use serde_json::{json, Value};
+
+use crate::{
+    config::{app_state::AppState, settings::Settings},
+    routes::configure,
+    service::auth::authenticate,
+    storage::memory::MemoryStore,
+    util::util::merge_patch,
+};
+
+///
+/// A fresh state backed by the `memory` store.
+pub fn state() -> web::Data<AppState> {
+    web::Data::new(AppState::new(Arc::new(MemoryStore::new()), Settings::default()))
+}
+
+///
+/// Settings of the `memory` store with the environment `vars`, e.g: `[("AUTH_API_KEYS", "key-of-jane:M1")]`.
+pub fn settings(vars: &[(&str, &str)]) -> Settings {
+    let vars = vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect::<HashMap<String, String>>();
+
+    Settings::from_sources(Some("[storage]\nbackend = \"memory\"\n"), &vars).unwrap()
+}
+
+///
+/// A fresh state backed by the `memory` store, with the environment `vars` (see `settings`).
+pub fn state_with(vars: &[(&str, &str)]) -> web::Data<AppState> {
+    web::Data::new(AppState::new(Arc::new(MemoryStore::new()), settings(vars)))
+}
+
+///
+/// A state where Jane (`key-of-jane`) is `M1` and John (`key-of-john`) is `M2`.
+pub fn protected_state() -> web::Data<AppState> {
+    state_with(&[("AUTH_API_KEYS", "key-of-jane:M1,key-of-john:M2")])
+}
+
+///
+/// It sends the request to an app with every route registered,
+/// returning the status and the JSON body of the response.
+pub async fn call(state: &web::Data<AppState>, request: test::TestRequest) -> (StatusCode, Value) {
+    let app = test::init_service(
+        App::new().app_data(state.clone()).wrap(from_fn(authenticate)).configure(configure)
+    ).await;
+
+    let response = test::call_service(&app, request.to_request()).await;
+    let status = response.status();
+    let body: Value = test::read_body_json(response).await;
+
+    (status, body)
+}
+
+///
+/// It sends the request as the caller of the API `key`.
+pub async fn call_as(state: &web::Data<AppState>, key: &str, request: test::TestRequest) -> (StatusCode, Value) {
+    call(state, request.insert_header(("X-API-Key", key))).await
+}
+
+///
+/// It sends `POST uri` with `body`, which must succeed, returning the body of the response.
+pub async fn create(state: &web::Data<AppState>, uri: &str, body: Value) -> Value {
+    let (status, body) = call(state, test::TestRequest::post().uri(uri).set_json(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    body
+}
+
+///
+/// The `body` with `changes` merged into it (see `merge_patch`), e.g: `with(new_item("Login"), json!({ "points": 5 }))`.
+pub fn with(mut body: Value, changes: Value) -> Value {
+    merge_patch(&mut body, &changes);
+    body
+}
+
+///
+/// Body of `POST /project`, owned by `M1`.
+pub fn new_project(denomination: &str) -> Value {
+    json!({
+        "denomination": denomination,
+        "description": "Scrum board",
+        "owner": "M1",
+        "status": "Pending",
+        "createdAt": 1700000000
+    })
+}
+
+///
+/// Body of `POST /epic`, owned by `M1`.
+pub fn new_epic(project_id: &str, title: &str) -> Value {
+    json!({
+        "projectId": project_id,
+        "title": title,
+        "description": "Everything about the login",
+        "status": "InProgress",
+        "owner": "M1",
+        "createdAt": 1700000000
+    })
+}
+
+///
+/// Body of `POST /us`, a story of `P1/E1`.
+pub fn new_story(title: &str) -> Value {
+    json!({
+        "project_id": "P1",
+        "epic_id": "E1",
+        "title": title,
+        "priority": "High",
+        "story_points": 5,
+        "user_story": { "role": "developer", "functionality": "work offline", "benefit": "no network" },
+        "acceptance_criteria": [[], []],
+        "created_at": 1700000000
+    })
+}
+
+///
+/// Body of `POST /pb`, an item of `P1/E1`.
+pub fn new_item(title: &str) -> Value {
+    json!({
+        "projectId": "P1",
+        "epicId": "E1",
+        "title": title,
+        "priority": "Medium",
+        "description": null,
+        "points": 3,
+        "kind": "Story",
+        "assignedAt": 1700000100,
+        "createdAt": 1700000000
+    })
+}
+
+///
+/// Body of `POST /sprint`, a sprint of `P1/E1` in progress owned by `M1`.
+pub fn new_sprint(denomination: &str) -> Value {
+    json!({
+        "projectId": "P1",
+        "epicId": "E1",
+        "denomination": denomination,
+        "description": null,
+        "goal": "Ship the login",
+        "owner": "M1",
+        "estimatedPoints": 0,
+        "status": "InProgress",
+        "completedAt": 0,
+        "startDate": 1700000000,
+        "endDate": 1701209600,
+        "createdAt": 1700000000
+    })
+}
+
+///
+/// Body of `POST /member`.
+pub fn new_member(name: &str, email: &str, role: &str) -> Value {
+    json!({
+        "name": name,
+        "email": email,
+        "role": role,
+        "capacityHours": 40,
+        "createdAt": 1700000000
+    })
+}
+
+///
+/// It creates the member `M1` (Jane Doe), the owner of the projects, epics and sprints of the tests.
+pub async fn member(state: &web::Data<AppState>) {
+    create(state, "/member", with(new_member("Jane Doe", "jane@example.com", "ProductOwner"), json!({ "capacityHours": 60 }))).await;
+}
@@ -0,0 +1,158 @@
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::json;
+
+use crate::config::app_state::AppState;
+
+use super::{call_as, new_item, new_member, new_project, new_story, protected_state, with};
+
+///
+/// Jane (`M1`) creates the project `P1` with the backlog item `PB1`, John (`M2`) has no role in it.
+async fn seed(state: &web::Data<AppState>) {
+    for (name, email) in [("Jane Doe", "jane@example.com"), ("John Roe", "john@example.com")] {
+        call_as(state, "key-of-jane", TestRequest::post().uri("/member").set_json(new_member(name, email, "Developer"))).await;
+    }
+
+    let (status, body) = call_as(state, "key-of-jane", TestRequest::post().uri("/project").set_json(new_project("Alpha"))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let item = with(new_item("Crash on save"), json!({ "kind": "Bug", "assignedAt": 0 }));
+
+    let (status, body) = call_as(state, "key-of-jane", TestRequest::post().uri("/pb").set_json(item)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+}
+
+#[actix_web::test]
+async fn roles_decide_what_the_caller_changes() {
+    let state = protected_state();
+    seed(&state).await;
+
+    // The creator is the ProductOwner
+    let (status, body) = call_as(&state, "key-of-jane", TestRequest::get().uri("/projects/P1/permissions")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"], json!([{ "projectId": "P1", "subject": "M1", "role": "ProductOwner" }]));
+
+    let (status, body) = call_as(&state, "key-of-john", TestRequest::get().uri("/projects/P1")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "FORBIDDEN");
+
+    let (status, _) = call_as(&state, "key-of-jane", TestRequest::put().uri("/projects/P1/permissions/M2").set_json(json!({ "role": "Developer" }))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::get().uri("/pbs?id=P1")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::patch().uri("/projects/P1").set_json(json!({ "description": "Mine" }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // A developer only moves the items assigned to it
+    let move_item = || TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "status": "InProgress" }));
+
+    let (status, _) = call_as(&state, "key-of-john", move_item()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    call_as(&state, "key-of-jane", TestRequest::put().uri("/projects/P1/backlog/PB1/assignee").set_json(json!({ "memberId": "M2" }))).await;
+
+    let (status, body) = call_as(&state, "key-of-john", move_item()).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "title": "Mine" }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // The legacy routes are guarded by the rows they address
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::delete().uri("/project").set_json(json!({ "range": ["A1", "G1"] }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::put().uri("/projects/P1/permissions/M2").set_json(json!({ "role": "ProductOwner" }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn a_project_keeps_a_product_owner() {
+    let state = protected_state();
+    seed(&state).await;
+
+    let (status, _) = call_as(&state, "key-of-jane", TestRequest::delete().uri("/projects/P1/permissions/M1")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = call_as(&state, "key-of-jane", TestRequest::put().uri("/projects/P1/permissions/M1").set_json(json!({ "role": "Viewer" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    call_as(&state, "key-of-jane", TestRequest::put().uri("/projects/P1/permissions/M2").set_json(json!({ "role": "ProductOwner" }))).await;
+
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::delete().uri("/projects/P1/permissions/M1")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = call_as(&state, "key-of-jane", TestRequest::get().uri("/projects/P1")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn the_project_is_the_one_the_handler_reads() {
+    let state = protected_state();
+    seed(&state).await;
+
+    // John is the ProductOwner of `P2`, not of `P1`
+    call_as(&state, "key-of-john", TestRequest::post().uri("/project").set_json(new_project("Beta"))).await;
+
+    let story = |project: serde_json::Value| with(new_story("Offline mode"), project);
+
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::post().uri("/us").set_json(story(json!({ "projectId": "P2" })))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::post().uri("/us").set_json(story(json!({ "project_id": "P1" })))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::get().uri("/uss?id=P2&projectId=P1&epicId=E1")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = call_as(&state, "key-of-jane", TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(body["content"].as_array().unwrap().is_empty());
+
+    for uri in ["/projects/P1/summary", "/projects/P1/velocity", "/projects/P1/sprints/S1/burndown"] {
+        let (status, _) = call_as(&state, "key-of-john", TestRequest::get().uri(uri)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", uri);
+    }
+
+    // The work of a member is listed in the projects of the caller
+    call_as(&state, "key-of-jane", TestRequest::put().uri("/projects/P1/backlog/PB1/assignee").set_json(json!({ "memberId": "M1" }))).await;
+
+    let (_, body) = call_as(&state, "key-of-jane", TestRequest::get().uri("/members/M1/work")).await;
+    assert_eq!(body["content"].as_array().unwrap().len(), 1);
+
+    let (status, body) = call_as(&state, "key-of-john", TestRequest::get().uri("/members/M1/work")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["content"].as_array().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn only_product_owners_change_the_members() {
+    let state = protected_state();
+    seed(&state).await;
+
+    // John has no role, it reads the members but changes none of them
+    let (status, _) = call_as(&state, "key-of-john", TestRequest::get().uri("/members/M1")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    for request in [
+        TestRequest::post().uri("/member").set_json(new_member("Ann Poe", "ann@example.com", "Developer")),
+        TestRequest::patch().uri("/members/M1").set_json(json!({ "name": "Mine" })),
+        TestRequest::delete().uri("/members/M1"),
+        TestRequest::patch().uri("/member").set_json(json!({ "content": "Mine", "cell": "BH1" })),
+        TestRequest::delete().uri("/member").set_json(json!({ "range": ["BG1", "BM1"] })),
+    ] {
+        let (status, body) = call_as(&state, "key-of-john", request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+    }
+
+    let (_, body) = call_as(&state, "key-of-john", TestRequest::get().uri("/members")).await;
+    assert_eq!(body["content"].as_array().unwrap().len(), 2);
+    assert_eq!(body["content"][0]["name"], "Jane Doe");
+
+    // Jane is the ProductOwner of `P1`
+    let (status, _) = call_as(&state, "key-of-jane", TestRequest::patch().uri("/members/M2").set_json(json!({ "capacityHours": 20 }))).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,93 @@
+use actix_web::{http::StatusCode, test::TestRequest};
+use serde_json::json;
+
+use super::{call, new_item, state, with};
+
+#[actix_web::test]
+async fn create_and_read_backlog_items() {
+    let state = state();
+
+    let (status, _) = call(&state, TestRequest::post().uri("/pb").set_json(with(new_item("Crash on save"), json!({ "kind": "Bug" })))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    call(&state, TestRequest::post().uri("/pb").set_json(with(new_item("Write docs"), json!({ "kind": "Task" })))).await;
+
+    let (status, body) = call(&state, TestRequest::get().uri("/pbs?id=P1")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let items = body["content"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["id"], "PB1");
+    assert_eq!(items[0]["kind"], "Bug");
+    assert_eq!(items[0]["points"], 3);
+    assert_eq!(items[1]["id"], "PB2");
+    assert_eq!(items[1]["kind"], "Task");
+}
+
+#[actix_web::test]
+async fn update_and_delete_backlog_item() {
+    let state = state();
+
+    call(&state, TestRequest::post().uri("/pb").set_json(with(new_item("Crash on save"), json!({ "kind": "Bug" })))).await;
+    call(&state, TestRequest::post().uri("/pb").set_json(with(new_item("Write docs"), json!({ "kind": "Task" })))).await;
+
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/pb").set_json(json!({ "content": "8", "cell": "AL1" }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = call(
+        &state,
+        TestRequest::delete().uri("/pb").set_json(json!({ "range": ["AF2", "AQ2"] }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/pbs?id=P1")).await;
+    let items = body["content"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["points"], 8);
+}
+
+#[actix_web::test]
+async fn backlog_items_follow_their_workflow() {
+    let state = state();
+
+    // An item stored before the status existed
+    state.store
+        .append("Projects!AF1:AP1", [
+            "PB1", "P1", "E1", "Login", "High", "", "5", "Story", "1700000100", "1700000000", "1700000000"
+        ].map(String::from).to_vec())
+        .await
+        .unwrap();
+
+    let item = with(new_item("Crash on save"), json!({ "kind": "Bug", "status": "InProgress" }));
+
+    let (_, body) = call(&state, TestRequest::post().uri("/pb").set_json(item)).await;
+    assert_eq!(body["content"][0]["status"], "InProgress");
+    assert_eq!(body["content"][0]["row"], "AF2:AQ2");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/pbs?id=P1&status=InProgress")).await;
+    let items = body["content"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["id"], "PB2");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/pbs?id=P1&status=ToDo")).await;
+    assert_eq!(body["content"][0]["id"], "PB1");
+
+    let (status, _) = call(&state, TestRequest::get().uri("/pbs?id=P1&status=Finished")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = call(&state, TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "status": "Done" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["errorBug"][0], "The status can not change from ToDo to Done, allowed: InProgress, Blocked");
+
+    for next in ["InProgress", "InReview", "Done"] {
+        let (status, body) = call(&state, TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "status": next }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["content"][0]["status"], next);
+    }
+
+    let (status, _) = call(&state, TestRequest::patch().uri("/pb").set_json(json!({ "content": "Blocked", "cell": "AQ1" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
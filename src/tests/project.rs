@@ -0,0 +1,88 @@
+use actix_web::{http::StatusCode, test::TestRequest};
+use serde_json::json;
+
+use super::{call, member, new_project, state};
+
+#[actix_web::test]
+async fn create_and_read_projects() {
+    let state = state();
+    member(&state).await;
+
+    let (status, body) = call(&state, TestRequest::post().uri("/project").set_json(new_project("Alpha"))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["message"], "Data was inserted in the sheet successfully!");
+    assert_eq!(body["content"][0]["id"], "P1");
+    assert_eq!(body["content"][0]["row"], "A1:G1");
+    assert_eq!(body["code"], json!(null));
+
+    call(&state, TestRequest::post().uri("/project").set_json(new_project("Beta"))).await;
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let projects = body["content"].as_array().unwrap();
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0]["id"], "P1");
+    assert_eq!(projects[0]["denomination"], "Alpha");
+    assert_eq!(projects[0]["status"], "Pending");
+    assert_eq!(projects[0]["row"], "A1:G1");
+    assert_eq!(projects[1]["id"], "P2");
+    assert_eq!(projects[1]["updatedAt"], 1700000000);
+}
+
+#[actix_web::test]
+async fn update_project_cell() {
+    let state = state();
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/project").set_json(new_project("Alpha"))).await;
+
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/project").set_json(json!({ "content": "Done", "cell": "E1" }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["status"], "Done");
+    assert_eq!(body["content"][0]["denomination"], "Alpha");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects")).await;
+    assert_eq!(body["content"][0]["status"], "Done");
+
+    // The row is validated before the cell is written
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/project").set_json(json!({ "content": "Finished", "cell": "E1" }))
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects")).await;
+    assert_eq!(body["content"][0]["status"], "Done");
+
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/project").set_json(json!({ "content": "Beta", "cell": "B2" }))
+    ).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+}
+
+#[actix_web::test]
+async fn delete_project_range() {
+    let state = state();
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/project").set_json(new_project("Alpha"))).await;
+    call(&state, TestRequest::post().uri("/project").set_json(new_project("Beta"))).await;
+
+    let (status, _) = call(
+        &state,
+        TestRequest::delete().uri("/project").set_json(json!({ "range": ["A2", "G2"] }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects")).await;
+    let projects = body["content"].as_array().unwrap();
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0]["denomination"], "Alpha");
+}
@@ -0,0 +1,190 @@
+use actix_web::{http::StatusCode, test::{self, TestRequest}, App};
+use serde_json::json;
+
+use crate::routes::configure;
+
+use super::{call, member, new_epic, new_item, new_project, new_story, state, with};
+
+#[actix_web::test]
+async fn get_project_by_id() {
+    let state = state();
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/project").set_json(new_project("Alpha"))).await;
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects/P1")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["denomination"], "Alpha");
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects/P9")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+}
+
+#[actix_web::test]
+async fn patch_epic_by_field_name() {
+    let state = state();
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P1", "Login"))).await;
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P2", "Reports"))).await;
+
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P2/epics/E1").set_json(json!({ "title": "Dashboards", "status": "Done" }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["title"], "Dashboards");
+    assert_eq!(body["content"][0]["status"], "Done");
+
+    // Epics are resolved inside their project
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/epics/E1")).await;
+    assert_eq!(body["content"][0]["title"], "Login");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P2/epics/E1")).await;
+    assert_eq!(body["content"][0]["title"], "Dashboards");
+}
+
+#[actix_web::test]
+async fn patch_rejects_unknown_fields_and_keys() {
+    let state = state();
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/epic").set_json(new_epic("P1", "Login"))).await;
+
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/epics/E1").set_json(json!({ "colour": "red", "id": "E7" }))
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let mut fields = body["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field["field"].as_str().unwrap().to_string())
+        .collect::<Vec<String>>();
+    fields.sort();
+    assert_eq!(fields, vec!["colour", "id"]);
+
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/epics/E1").set_json(json!({ "updatedAt": 1 }))
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/epics/E1").set_json(json!({ "status": "Finished" }))
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/epics/E1")).await;
+    assert_eq!(body["content"][0]["status"], "InProgress");
+}
+
+#[actix_web::test]
+async fn patch_and_delete_user_story() {
+    let state = state();
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/us").set_json(with(new_story("Offline mode"), json!({
+        "acceptance_criteria": [[{ "kind": "Success", "given": "g", "when": "w", "then": "t" }], ["Works offline"]]
+    })))).await;
+
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/epics/E1/stories/US1").set_json(json!({ "storyPoints": 8 }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["storyPoints"], 8);
+
+    let (status, body) = call(&state, TestRequest::delete().uri("/projects/P1/epics/E1/stories/US1")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["title"], "Offline mode");
+
+    let (status, _) = call(&state, TestRequest::get().uri("/projects/P1/epics/E1/stories/US1")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn cell_routes_are_deprecated() {
+    let state = state();
+    member(&state).await;
+    let app = test::init_service(App::new().app_data(state.clone()).configure(configure)).await;
+
+    let request = TestRequest::patch()
+        .uri("/project")
+        .set_json(json!({ "content": "Beta", "cell": "B1" }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.headers().get("Deprecation").unwrap(), "true");
+
+    let request = TestRequest::get().uri("/projects").to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.headers().get("Deprecation").is_none());
+}
+
+#[actix_web::test]
+async fn merge_patch_on_nested_and_optional_fields() {
+    let state = state();
+    member(&state).await;
+
+    call(&state, TestRequest::post().uri("/us").set_json(with(new_story("Offline mode"), json!({
+        "acceptance_criteria": [[{ "kind": "Success", "given": "g", "when": "w", "then": "t" }], ["Works offline"]]
+    })))).await;
+
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/epics/E1/stories/US1").set_json(json!({
+            "userStory": { "role": "tester" },
+            "acceptanceCriteria": [[], ["Works offline", "Syncs later"]]
+        }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let story = &body["content"][0];
+    assert_eq!(story["userStory"]["role"], "tester");
+    assert_eq!(story["userStory"]["functionality"], "work offline");
+    assert_eq!(story["acceptanceCriteria"], json!([[], ["Works offline", "Syncs later"]]));
+    assert!(story["updatedAt"].as_i64().unwrap() > 1700000000);
+
+    // The stored row reads back the same
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/epics/E1/stories/US1")).await;
+    assert_eq!(body["content"][0]["acceptanceCriteria"], json!([[], ["Works offline", "Syncs later"]]));
+
+    call(&state, TestRequest::post().uri("/pb").set_json(with(new_item("Crash on save"), json!({
+        "description": "Found in production",
+        "kind": "Bug"
+    })))).await;
+
+    // `null` removes optional fields, required ones are reported
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "description": null, "points": null }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["description"], "");
+    assert_eq!(body["content"][0]["points"], json!(null));
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/backlog/PB1")).await;
+    assert_eq!(body["content"][0]["points"], json!(null));
+
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "title": null, "kind": "Epic", "points": "3" }))
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let mut fields = body["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field["field"].as_str().unwrap().to_string())
+        .collect::<Vec<String>>();
+    fields.sort();
+    assert_eq!(fields, vec!["kind", "points", "title"]);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/backlog/PB1")).await;
+    assert_eq!(body["content"][0]["title"], "Crash on save");
+}
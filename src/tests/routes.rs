@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+
+use actix_web::{http::{Method, StatusCode}, test::TestRequest, App};
+use serde_json::json;
+
+use crate::routes::{duplicates, register};
+
+use super::{call, state};
+
+/// Table of the routes registered in the app.
+fn route_table() -> Vec<(Method, String)> {
+    let table = RefCell::new(vec![]);
+
+    let _ = App::new().configure(|cfg| *table.borrow_mut() = register(cfg));
+
+    table.into_inner()
+}
+
+#[test]
+fn every_route_is_unique() {
+    let table = route_table();
+
+    assert_eq!(duplicates(&table), vec![]);
+    assert!(table.contains(&(Method::POST, "/sprint".to_string())));
+    assert!(table.contains(&(Method::PATCH, "/sprint".to_string())));
+    assert!(table.contains(&(Method::DELETE, "/sprint".to_string())));
+}
+
+#[test]
+fn duplicates_are_detected() {
+    let table = vec![
+        (Method::POST, "/sprint".to_string()),
+        (Method::PATCH, "/sprint".to_string()),
+        (Method::POST, "/sprint".to_string()),
+    ];
+
+    assert_eq!(duplicates(&table), vec![(Method::POST, "/sprint".to_string())]);
+}
+
+#[actix_web::test]
+async fn sprint_update_and_delete_are_reachable() {
+    let state = state();
+
+    state.store
+        .append("Projects!AR1:BE1", [
+            "S1", "P1", "E1", "Sprint 1", "", "Set up", "Jane Doe", "13", "Pending", "0",
+            "1698790400", "1699999999", "1698790400", "1698790400"
+        ].map(String::from).to_vec())
+        .await
+        .unwrap();
+
+    let (status, body) = call(
+        &state,
+        TestRequest::patch().uri("/sprint").set_json(json!({ "content": "Done", "cell": "AZ1" }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["status"], "Done");
+
+    let (status, _) = call(
+        &state,
+        TestRequest::delete().uri("/sprint").set_json(json!({ "range": ["AR1", "BE1"] }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/sprints?id=P1")).await;
+    assert!(body["content"].as_array().unwrap().is_empty());
+}
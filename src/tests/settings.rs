@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::config::settings::{Settings, StorageBackend};
+
+fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+#[test]
+fn file_is_overridden_by_the_environment() {
+    let file = r#"
+        [server]
+        port = 9000
+        cors_origin = "https://app.example.com"
+
+        [storage]
+        backend = "sqlite"
+        sqlite_path = "data.db"
+
+        [sheets]
+        projects = "Board"
+    "#;
+
+    let settings = Settings::from_sources(Some(file), &vars(&[("SERVER_PORT", "9100"), ("STORAGE_BACKEND", "memory")])).unwrap();
+
+    assert_eq!(settings.bind_address(), ("127.0.0.1".to_string(), 9100));
+    assert_eq!(settings.server.cors_origin, "https://app.example.com");
+    assert_eq!(settings.storage.backend, StorageBackend::Memory);
+    assert_eq!(settings.storage.sqlite_path, "data.db");
+    assert_eq!(settings.sheets.projects, "Board");
+}
+
+#[test]
+fn spreadsheet_id_is_required_by_google_sheets() {
+    let err = Settings::from_sources(None, &vars(&[("SHEETS_BASE_URL", "http://127.0.0.1:9000")])).unwrap_err();
+
+    assert_eq!(err.problems.len(), 1);
+    assert!(err.problems[0].starts_with("storage.spreadsheet_id"));
+
+    let settings = Settings::from_sources(
+        None,
+        &vars(&[("SHEETS_BASE_URL", "http://127.0.0.1:9000"), ("SPREADSHEET_ID", "abc")])
+    ).unwrap();
+
+    assert_eq!(settings.storage.spreadsheet_id.as_deref(), Some("abc"));
+
+    // Only the environment gives a token
+    assert_eq!(settings.storage.sheets_token, "");
+}
+
+#[test]
+fn every_problem_is_reported() {
+    let err = Settings::from_sources(
+        Some("[server]\ncors_origin = \"localhost:1420\"\n"),
+        &vars(&[
+            ("STORAGE_BACKEND", "excel"),
+            ("SERVER_PORT", "http"),
+            ("PROJECTS_SHEET_NAME", "Projects!A1"),
+        ])
+    ).unwrap_err();
+
+    let fields: Vec<&str> = err.problems.iter().map(|problem| problem.split(':').next().unwrap_or_default()).collect();
+
+    assert_eq!(fields, vec!["SERVER_PORT", "STORAGE_BACKEND", "server.cors_origin", "storage.spreadsheet_id", "storage.credentials_path", "sheets.projects"]);
+    assert!(err.to_string().starts_with("Invalid settings:\n  - SERVER_PORT"));
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let err = Settings::from_sources(Some("[server]\nprot = 8080\n"), &vars(&[("STORAGE_BACKEND", "memory")])).unwrap_err();
+
+    assert!(err.problems[0].contains("prot"), "{:?}", err.problems);
+}
+
+#[test]
+fn workflow_tables_replace_the_default_ones() {
+    let file = r#"
+        [workflow.sprint]
+        Pending = ["InProgress"]
+        InProgress = ["Done"]
+        Done = ["InProgress"]
+    "#;
+
+    let settings = Settings::from_sources(Some(file), &vars(&[("STORAGE_BACKEND", "memory")])).unwrap();
+
+    assert_eq!(settings.workflow.sprint.get("Done"), Some(&vec!["InProgress".to_string()]));
+    assert_eq!(settings.workflow.sprint.get("Standby"), None);
+    assert_eq!(settings.workflow.project.get("Done"), Some(&vec![]));
+
+    let err = Settings::from_sources(
+        Some("[workflow.epic]\nPending = [\"Finished\"]\n"),
+        &vars(&[("STORAGE_BACKEND", "memory")])
+    ).unwrap_err();
+
+    assert_eq!(err.problems.len(), 1);
+    assert!(err.problems[0].starts_with("workflow.epic: 'Finished'"), "{:?}", err.problems);
+}
@@ -0,0 +1,33 @@
+use actix_web::{http::StatusCode, test::TestRequest};
+use serde_json::json;
+
+use super::{call, member, new_sprint, state, with};
+
+#[actix_web::test]
+async fn create_and_read_sprints() {
+    let state = state();
+    member(&state).await;
+
+    // A sprint stored before the counters existed, the counter starts from it
+    state.store
+        .append("Projects!AR1:BE1", [
+            "S1", "P1", "E1", "Sprint 1", "", "Set up", "Jane Doe", "13", "Done", "1699999999",
+            "1698790400", "1699999999", "1698790400", "1699999999"
+        ].map(String::from).to_vec())
+        .await
+        .unwrap();
+
+    let (status, _) = call(&state, TestRequest::post().uri("/sprint").set_json(with(new_sprint("Sprint 2"), json!({ "estimatedPoints": 21, "status": "Pending" })))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = call(&state, TestRequest::get().uri("/sprints?id=P1")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let sprints = body["content"].as_array().unwrap();
+    assert_eq!(sprints.len(), 2);
+    assert_eq!(sprints[0]["status"], "Done");
+    assert_eq!(sprints[1]["id"], "S2");
+    assert_eq!(sprints[1]["denomination"], "Sprint 2");
+    assert_eq!(sprints[1]["estimatedPoints"], 21);
+    assert_eq!(sprints[1]["description"], "");
+}
@@ -0,0 +1,156 @@
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::json;
+
+use crate::{config::app_state::AppState, util::util::unix_now};
+
+use super::{call, create, member, new_item, new_sprint, state, with};
+
+const DAY: i64 = 86_400;
+
+///
+/// Items `PB1` (3 points), `PB2` (5 points) and `PB3` (2 points) of `P1`,
+/// and the sprint `S1` from two days ago until two days ahead.
+async fn seed(state: &web::Data<AppState>, first_day: i64) {
+    member(state).await;
+    for (title, points) in [("Login", 3), ("Logout", 5), ("Docs", 2)] {
+        create(state, "/pb", with(new_item(title), json!({ "points": points }))).await;
+    }
+
+    create(state, "/sprint", with(new_sprint("Sprint 1"), json!({
+        "startDate": first_day + 9 * 3600,
+        "endDate": first_day + 4 * DAY + 18 * 3600
+    }))).await;
+
+    // `PB3` is added on the second day
+    for (position, item_id, points, added_at) in [(1, "PB1", 3, first_day + 3600), (2, "PB2", 5, first_day + 7200), (3, "PB3", 2, first_day + DAY + 3600)] {
+        state.store
+            .append("SprintItems!A1:F1", vec![
+                "P1".to_string(), "S1".to_string(), item_id.to_string(), position.to_string(), added_at.to_string(), "".to_string()
+            ])
+            .await
+            .unwrap();
+
+        state.store
+            .append("ScopeChanges!A1:E1", vec![
+                "P1".to_string(), "S1".to_string(), item_id.to_string(), points.to_string(), added_at.to_string()
+            ])
+            .await
+            .unwrap();
+    }
+}
+
+#[actix_web::test]
+async fn burndown_and_burnup_by_day() {
+    let state = state();
+    let first_day = unix_now().div_euclid(DAY) * DAY - 2 * DAY;
+    seed(&state, first_day).await;
+
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/sprints/S1/items/PB1").set_json(json!({ "completedAt": first_day + DAY + 7200 }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1/burndown")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let chart = &body["content"][0];
+    assert_eq!(chart["committedPoints"], 8);
+    assert_eq!(chart["scopeChanges"], json!([{ "date": first_day + DAY + 3600, "itemId": "PB3", "points": 2 }]));
+    assert_eq!(chart["days"], json!([
+        { "date": first_day, "ideal": 8.0, "remaining": 8 },
+        { "date": first_day + DAY, "ideal": 6.0, "remaining": 7 },
+        { "date": first_day + 2 * DAY, "ideal": 4.0, "remaining": 7 },
+        { "date": first_day + 3 * DAY, "ideal": 2.0, "remaining": null },
+        { "date": first_day + 4 * DAY, "ideal": 0.0, "remaining": null }
+    ]));
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1/burnup")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["days"], json!([
+        { "date": first_day, "ideal": 0.0, "completed": 0, "scope": 8 },
+        { "date": first_day + DAY, "ideal": 2.0, "completed": 3, "scope": 10 },
+        { "date": first_day + 2 * DAY, "ideal": 4.0, "completed": 3, "scope": 10 },
+        { "date": first_day + 3 * DAY, "ideal": 6.0, "completed": null, "scope": null },
+        { "date": first_day + 4 * DAY, "ideal": 8.0, "completed": null, "scope": null }
+    ]));
+
+    // Opening the item again
+    call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1/items/PB1").set_json(json!({ "completedAt": null }))).await;
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1/burndown")).await;
+    assert_eq!(body["content"][0]["days"][1]["remaining"], 10);
+}
+
+#[actix_web::test]
+async fn scope_changes_are_recorded() {
+    let state = state();
+    let first_day = unix_now().div_euclid(DAY) * DAY - 2 * DAY;
+    seed(&state, first_day).await;
+
+    // Today `PB2` leaves the sprint and `PB3` is estimated again
+    let (status, _) = call(&state, TestRequest::delete().uri("/projects/P1/sprints/S1/items/PB2")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    call(&state, TestRequest::patch().uri("/projects/P1/backlog/PB3").set_json(json!({ "points": 4 }))).await;
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1/burnup")).await;
+
+    let chart = &body["content"][0];
+    let changes = chart["scopeChanges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| format!("{} {}", change["itemId"].as_str().unwrap(), change["points"]))
+        .collect::<Vec<String>>();
+    assert_eq!(changes, vec!["PB3 2", "PB2 -5", "PB3 2"]);
+
+    // The days before keep the scope they had
+    assert_eq!(chart["committedPoints"], 8);
+    assert_eq!(chart["days"][1]["scope"], 10);
+    assert_eq!(chart["days"][2]["scope"], 7);
+}
+
+#[actix_web::test]
+async fn completed_items_keep_their_points() {
+    let state = state();
+    let first_day = unix_now().div_euclid(DAY) * DAY - 2 * DAY;
+    seed(&state, first_day).await;
+
+    // Today `PB1` is done, then estimated again
+    for status in ["InProgress", "Done"] {
+        let (code, body) = call(&state, TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "status": status }))).await;
+        assert_eq!(code, StatusCode::OK, "{}", body);
+    }
+
+    let (status, body) = call(&state, TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "points": 6 }))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1/burnup")).await;
+
+    let chart = &body["content"][0];
+    assert_eq!(chart["scopeChanges"].as_array().unwrap().len(), 2);
+    assert_eq!(chart["days"][2]["completed"], 3);
+    assert_eq!(chart["days"][2]["scope"], 13);
+}
+
+#[actix_web::test]
+async fn chart_errors() {
+    let state = state();
+    seed(&state, 1700006400).await;
+
+    let (status, _) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S9/burndown")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/sprints/S1/items/PB9").set_json(json!({ "completedAt": 1700006400 }))
+    ).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "endDate": 1600000000 }))).await;
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1/burnup")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["errorBug"][0], "The sprint S1 ends before it starts");
+}
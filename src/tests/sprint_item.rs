@@ -0,0 +1,173 @@
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::{json, Value};
+
+use crate::{config::app_state::AppState, util::row::text};
+
+use super::{call, create, member, new_item, new_sprint, state, with};
+
+///
+/// Items `PB1` (3 points), `PB2` (5 points) and `PB3` (no points), and the sprints `S1` and `S2` of `P1`.
+async fn seed(state: &web::Data<AppState>) {
+    member(state).await;
+    for (title, points) in [("Login", json!(3)), ("Logout", json!(5)), ("Docs", json!(null))] {
+        create(state, "/pb", with(new_item(title), json!({ "points": points }))).await;
+    }
+
+    for denomination in ["Sprint 1", "Sprint 2"] {
+        create(state, "/sprint", new_sprint(denomination)).await;
+    }
+}
+
+fn ids(body: &Value) -> Vec<&str> {
+    body["content"].as_array().unwrap().iter().map(|item| item["id"].as_str().unwrap()).collect()
+}
+
+async fn estimated_points(state: &web::Data<AppState>, sprint_id: &str) -> Value {
+    let (_, body) = call(state, TestRequest::get().uri(&format!("/projects/P1/sprints/{}", sprint_id))).await;
+
+    body["content"][0]["estimatedPoints"].clone()
+}
+
+#[actix_web::test]
+async fn add_reorder_and_remove_items() {
+    let state = state();
+    seed(&state).await;
+
+    for item_id in ["PB1", "PB2", "PB3"] {
+        let (status, _) = call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": item_id }))).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1/items")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), vec!["PB1", "PB2", "PB3"]);
+    assert_eq!(estimated_points(&state, "S1").await, 8);
+
+    let (status, body) = call(
+        &state,
+        TestRequest::put().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemIds": ["PB3", "PB1", "PB2"] }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), vec!["PB3", "PB1", "PB2"]);
+
+    let (status, body) = call(&state, TestRequest::delete().uri("/projects/P1/sprints/S1/items/PB2")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), vec!["PB3", "PB1"]);
+    assert_eq!(estimated_points(&state, "S1").await, 3);
+
+    let (status, _) = call(&state, TestRequest::delete().uri("/projects/P1/sprints/S1/items/PB2")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn order_must_contain_every_item() {
+    let state = state();
+    seed(&state).await;
+
+    call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB1" }))).await;
+    call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB2" }))).await;
+
+    for order in [json!(["PB2"]), json!(["PB2", "PB2"]), json!(["PB2", "PB1", "PB3"])] {
+        let (status, body) = call(
+            &state,
+            TestRequest::put().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemIds": order }))
+        ).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    }
+}
+
+#[actix_web::test]
+async fn item_is_in_one_active_sprint() {
+    let state = state();
+    seed(&state).await;
+
+    call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB1" }))).await;
+
+    let (status, body) = call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB1" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "CONFLICT");
+
+    let (status, _) = call(&state, TestRequest::post().uri("/projects/P1/sprints/S2/items").set_json(json!({ "itemId": "PB1" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = call(&state, TestRequest::post().uri("/projects/P1/sprints/S2/items").set_json(json!({ "itemId": "PB9" }))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // A finished sprint does not hold its items
+    call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "status": "Done" }))).await;
+
+    let (status, _) = call(&state, TestRequest::post().uri("/projects/P1/sprints/S2/items").set_json(json!({ "itemId": "PB1" }))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB2" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn points_follow_the_backlog_items() {
+    let state = state();
+    seed(&state).await;
+
+    call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB1" }))).await;
+    call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB2" }))).await;
+
+    call(&state, TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "points": 8 }))).await;
+    assert_eq!(estimated_points(&state, "S1").await, 13);
+
+    call(&state, TestRequest::delete().uri("/projects/P1/backlog/PB2")).await;
+    assert_eq!(estimated_points(&state, "S1").await, 8);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1/items")).await;
+    assert_eq!(ids(&body), vec!["PB1"]);
+
+    // The relations of a removed sprint are removed with it
+    call(&state, TestRequest::delete().uri("/projects/P1/sprints/S1")).await;
+
+    let (status, _) = call(&state, TestRequest::post().uri("/projects/P1/sprints/S2/items").set_json(json!({ "itemId": "PB1" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(state.store.read("SprintItems!A:F").await.unwrap().iter().filter(|row| !row.is_empty()).count(), 1);
+}
+
+#[actix_web::test]
+async fn legacy_patches_keep_the_points() {
+    let state = state();
+    seed(&state).await;
+
+    call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB1" }))).await;
+
+    // The points of `PB1`
+    let (status, _) = call(&state, TestRequest::patch().uri("/pb").set_json(json!({ "content": "8", "cell": "AL1" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(estimated_points(&state, "S1").await, 8);
+
+    // The estimated points of `S1` are computed from its items
+    let (status, _) = call(&state, TestRequest::patch().uri("/sprint").set_json(json!({ "content": "20", "cell": "AY1" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "estimatedPoints": 20 }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(estimated_points(&state, "S1").await, 8);
+
+    let (status, _) = call(&state, TestRequest::patch().uri("/projects/P1/sprints/S2").set_json(json!({ "estimatedPoints": 20 }))).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn done_items_are_completed_in_the_sprint() {
+    let state = state();
+    seed(&state).await;
+
+    call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB1" }))).await;
+
+    let completed_at = || async { text(&state.store.read("SprintItems!A1:F1").await.unwrap()[0], 5) };
+
+    for status in ["InProgress", "Done"] {
+        call(&state, TestRequest::patch().uri("/projects/P1/backlog/PB1").set_json(json!({ "status": status }))).await;
+    }
+    assert!(completed_at().await.parse::<i64>().unwrap() > 0);
+
+    // Reopened with the legacy endpoint
+    let (status, _) = call(&state, TestRequest::patch().uri("/pb").set_json(json!({ "content": "InProgress", "cell": "AQ1" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(completed_at().await, "");
+}
@@ -0,0 +1,125 @@
+use serde_json::Value;
+
+use crate::{
+    interface::store::Store,
+    storage::memory::MemoryStore,
+    util::{error::AppError, range::{column_to_index, parse_range}},
+};
+
+use super::fake_sheets::index_to_column;
+
+fn strings(row: &[&str]) -> Vec<String> {
+    row.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn parse_a1_ranges() {
+    assert_eq!(column_to_index("A"), Some(0));
+    assert_eq!(column_to_index("AA"), Some(26));
+    assert_eq!(column_to_index("BE"), Some(56));
+    assert_eq!(index_to_column(0), "A");
+    assert_eq!(index_to_column(26), "AA");
+    assert_eq!(index_to_column(56), "BE");
+
+    let range = parse_range("Projects!AF3:AP3").unwrap();
+    assert_eq!(range.sheet, "Projects");
+    assert_eq!((range.start_col, range.end_col), (31, 41));
+    assert_eq!((range.start_row, range.end_row), (Some(3), Some(3)));
+
+    let range = parse_range("Projects!R:AD").unwrap();
+    assert_eq!((range.start_row, range.end_row), (None, None));
+
+    assert!(parse_range("A1:G1").is_err());
+}
+
+#[actix_web::test]
+async fn memory_store_behaves_like_a_sheet() {
+    let store = MemoryStore::new();
+
+    assert_eq!(store.append("Projects!A1:C1", strings(&["P1", "Alpha", ""])).await.unwrap(), 1);
+    assert_eq!(store.append("Projects!A1:C1", strings(&["P2", "Beta", "Second"])).await.unwrap(), 2);
+
+    // Other blocks of columns are independent
+    assert_eq!(store.append("Projects!I5:J5", strings(&["E1", "P1"])).await.unwrap(), 5);
+
+    let rows = store.read("Projects!A:C").await.unwrap();
+    assert_eq!(rows, vec![
+        vec![Value::from("P1"), Value::from("Alpha")],
+        vec![Value::from("P2"), Value::from("Beta"), Value::from("Second")],
+    ]);
+    assert_eq!(store.read("Projects!I:J").await.unwrap().len(), 5);
+
+    // The rows of a batch follow each other
+    assert_eq!(store.append_rows("Projects!I1:J1", vec![strings(&["E2", "P1"]), strings(&["E3", "P1"])]).await.unwrap(), 6);
+    assert_eq!(store.read("Projects!I7:J7").await.unwrap(), vec![vec![Value::from("E3"), Value::from("P1")]]);
+
+    store.update("Projects!B2:B2", strings(&["Gamma"])).await.unwrap();
+    store.clear("Projects!A1:C1").await.unwrap();
+
+    let rows = store.read("Projects!A:C").await.unwrap();
+    assert_eq!(rows[0], Vec::<Value>::new());
+    assert_eq!(rows[1][1], "Gamma");
+
+    // A sheet that was never created can't be read
+    assert!(matches!(store.read("Metadata!A:B").await, Err(AppError::Validation(_))));
+
+    store.ensure_sheet("Metadata").await.unwrap();
+    assert!(store.read("Metadata!A:B").await.unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn fake_sheets_answers_like_the_api() {
+    use actix_web::{test, web, App};
+
+    use super::fake_sheets::{fake_routes, FakeSheets};
+
+    let fake = web::Data::new(FakeSheets::new(&["Projects"]));
+    let app = test::init_service(App::new().app_data(fake.clone()).configure(fake_routes)).await;
+
+    let request = test::TestRequest::post()
+        .uri("/v4/spreadsheets/fake/values/Projects%21A1%3AB1:append?valueInputOption=USER_ENTERED")
+        .set_json(serde_json::json!({ "range": "Projects!A1:B1", "majorDimension": "ROWS", "values": [["P1", "Alpha"]] }))
+        .to_request();
+    let body: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(body["updates"]["updatedRange"], "Projects!A1:B1");
+
+    let request = test::TestRequest::get().uri("/v4/spreadsheets/fake/values/Projects%21A%3AB").to_request();
+    let body: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(body["values"], serde_json::json!([["P1", "Alpha"]]));
+
+    // Unknown sheets are rejected
+    let request = test::TestRequest::get().uri("/v4/spreadsheets/fake/values/Epics%21A%3AB").to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), 400);
+
+    assert_eq!(fake.requests(), vec![
+        "values.append Projects!A1:B1".to_string(),
+        "values.get Projects!A:B".to_string(),
+        "values.get Epics!A:B".to_string(),
+    ]);
+}
+
+#[actix_web::test]
+async fn sqlite_views_follow_the_migrations() {
+    use crate::storage::sqlite::SqliteStore;
+
+    let path = std::env::temp_dir().join(format!("little_things_{:016x}.db", rand::random::<u64>()));
+    let path = path.to_str().unwrap();
+
+    let store = SqliteStore::open(path, "Projects").unwrap();
+    store.append("Projects!AF1:AQ1", strings(&["PB1", "P1", "E1", "Export", "1", "", "3", "Task", "", "0", "0", "Done"])).await.unwrap();
+    store.append("Projects!BG1:BM1", strings(&["M1", "Jane Doe", "jane@example.com", "Developer", "40", "0", "0"])).await.unwrap();
+    assert!(matches!(store.read("Metadata!A:B").await, Err(AppError::Validation(_))));
+    drop(store);
+
+    // A second start applies nothing again
+    drop(SqliteStore::open(path, "Projects").unwrap());
+
+    let connection = rusqlite::Connection::open(path).unwrap();
+    let query = |sql: &str| connection.query_row(sql, [], |row| row.get::<_, String>(0)).unwrap();
+
+    assert_eq!(query("SELECT GROUP_CONCAT(version) FROM schema_migrations"), "1,2,3");
+    assert_eq!(query("SELECT status FROM product_backlog_items"), "Done");
+    assert_eq!(query("SELECT name FROM team_members"), "Jane Doe");
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,96 @@
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::json;
+
+use crate::config::app_state::AppState;
+
+use super::{call, create, member, new_epic, new_item, new_project, new_sprint, new_story, state, with};
+
+///
+/// The project `P1` with the epics `E1` and `E2`:
+///
+/// - `E1`: `US1` (5, ToDo), `US2` (3, Done), `PB1` (Bug, 3, ToDo), `PB2` (Story, 5, Done) and `S1` (InProgress).
+/// - `E2`: `US3` (8, InProgress), `PB3` (Bug, 2, Done), `PB4` (Task, no points, InProgress) and `S2` (Pending).
+async fn seed(state: &web::Data<AppState>) {
+    member(state).await;
+    create(state, "/project", with(new_project("Alpha"), json!({ "status": "InProgress" }))).await;
+
+    for title in ["Login", "Reports"] {
+        create(state, "/epic", with(new_epic("P1", title), json!({ "description": "" }))).await;
+    }
+
+    for (epic_id, points, status) in [("E1", 5, "ToDo"), ("E1", 3, "Done"), ("E2", 8, "InProgress")] {
+        create(state, "/us", with(new_story("Story"), json!({ "epic_id": epic_id, "story_points": points, "status": status }))).await;
+    }
+
+    for (epic_id, kind, points, status) in [
+        ("E1", "Bug", json!(3), "ToDo"),
+        ("E1", "Story", json!(5), "Done"),
+        ("E2", "Bug", json!(2), "Done"),
+        ("E2", "Task", json!(null), "InProgress"),
+    ] {
+        create(state, "/pb", with(new_item("Item"), json!({ "epicId": epic_id, "kind": kind, "points": points, "status": status }))).await;
+    }
+
+    for (epic_id, status) in [("E1", "InProgress"), ("E2", "Pending")] {
+        create(state, "/sprint", with(new_sprint("Sprint"), json!({ "epicId": epic_id, "goal": "Ship it", "status": status }))).await;
+    }
+}
+
+#[actix_web::test]
+async fn project_summary() {
+    let state = state();
+    seed(&state).await;
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects/P1/summary")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let summary = &body["content"][0];
+    assert_eq!(summary["epicId"], json!(null));
+
+    let stories = &summary["userStories"];
+    assert_eq!((&stories["count"], &stories["points"], &stories["completedPoints"]), (&json!(3), &json!(16), &json!(3)));
+    assert_eq!(stories["percentComplete"], 18.75);
+    assert_eq!(stories["byStatus"]["ToDo"], json!({ "count": 1, "points": 5 }));
+    assert_eq!(stories["byStatus"]["InProgress"], json!({ "count": 1, "points": 8 }));
+    assert_eq!(stories["byStatus"]["Blocked"], json!({ "count": 0, "points": 0 }));
+
+    let items = &summary["backlogItems"];
+    assert_eq!((&items["count"], &items["points"], &items["completedPoints"]), (&json!(4), &json!(10), &json!(7)));
+    assert_eq!(items["percentComplete"], 70.0);
+
+    assert_eq!(summary["backlogItemsByKind"], json!({
+        "Bug": { "count": 2, "points": 5 },
+        "Story": { "count": 1, "points": 5 },
+        "Task": { "count": 1, "points": 0 }
+    }));
+    assert_eq!(summary["openBugs"], 1);
+    assert_eq!(summary["sprintsInProgress"], json!(["S1"]));
+}
+
+#[actix_web::test]
+async fn epic_summary() {
+    let state = state();
+    seed(&state).await;
+
+    let (status, body) = call(&state, TestRequest::get().uri("/projects/P1/epics/E1/summary")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let summary = &body["content"][0];
+    assert_eq!(summary["epicId"], "E1");
+    assert_eq!(summary["userStories"]["count"], 2);
+    assert_eq!(summary["userStories"]["percentComplete"], 37.5);
+    assert_eq!(summary["backlogItems"]["points"], 8);
+    assert_eq!(summary["backlogItems"]["percentComplete"], 62.5);
+    assert_eq!(summary["openBugs"], 1);
+    assert_eq!(summary["sprintsInProgress"], json!(["S1"]));
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/epics/E2/summary")).await;
+    assert_eq!(body["content"][0]["openBugs"], 0);
+    assert_eq!(body["content"][0]["sprintsInProgress"], json!([]));
+
+    let (status, _) = call(&state, TestRequest::get().uri("/projects/P1/epics/E9/summary")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = call(&state, TestRequest::get().uri("/projects/P9/summary")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
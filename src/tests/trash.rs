@@ -0,0 +1,179 @@
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::{json, Value};
+
+use crate::config::app_state::AppState;
+
+use super::{call, call_as, create, member, new_epic, new_item, new_member, new_project, new_sprint, state, state_with, with};
+
+async fn seed(state: &web::Data<AppState>) {
+    member(state).await;
+
+    for (project_id, title) in [("P1", "Login"), ("P2", "Reports")] {
+        create(state, "/epic", with(new_epic(project_id, title), json!({ "description": "" }))).await;
+    }
+}
+
+async fn trash(state: &web::Data<AppState>, query: &str) -> Vec<Value> {
+    let (status, body) = call(state, TestRequest::get().uri(&format!("/trash{}", query))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    body["content"].as_array().unwrap().clone()
+}
+
+async fn epics(state: &web::Data<AppState>, project_id: &str) -> usize {
+    let (_, body) = call(state, TestRequest::get().uri(&format!("/epics?id={}", project_id))).await;
+
+    body["content"].as_array().map(Vec::len).unwrap_or_default()
+}
+
+#[actix_web::test]
+async fn removed_entities_are_restored() {
+    let state = state();
+    seed(&state).await;
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/epics/E1")).await;
+    let row = body["content"][0]["row"].clone();
+
+    let (status, _) = call(&state, TestRequest::delete().uri("/projects/P1/epics/E1")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(epics(&state, "P1").await, 0);
+
+    let items = trash(&state, "?entity=epic").await;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["entityId"], "P1/E1");
+    assert_eq!(items[0]["fields"]["title"], "Login");
+    assert!(items[0]["deletedAt"].as_i64().unwrap() > 0);
+    assert!(trash(&state, "?entity=sprint").await.is_empty());
+
+    let (status, _) = call(&state, TestRequest::post().uri(&format!("/trash/{}/restore", items[0]["id"].as_str().unwrap()))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(trash(&state, "").await.is_empty());
+
+    // Back in the row it was removed from
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/epics/E1")).await;
+    assert_eq!(body["content"][0]["title"], "Login");
+    assert_eq!(body["content"][0]["row"], row);
+
+    let (status, _) = call(&state, TestRequest::post().uri("/trash/0000000000000000/restore")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn legacy_deletes_go_to_the_trash() {
+    let state = state();
+    seed(&state).await;
+
+    // The cells of an entity are not removed on their own
+    let (status, _) = call(&state, TestRequest::delete().uri("/epic").set_json(json!({ "range": ["L2", "L2"] }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(trash(&state, "").await.is_empty());
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P2/epics/E1")).await;
+    assert_eq!(body["content"][0]["status"], "InProgress");
+
+    call(&state, TestRequest::delete().uri("/epic").set_json(json!({ "range": ["I2", "P2"] }))).await;
+    assert_eq!(epics(&state, "P2").await, 0);
+
+    let items = trash(&state, "").await;
+    assert_eq!(items[0]["entityId"], "P2/E1");
+    assert_eq!(items[0]["fields"]["description"], "");
+}
+
+#[actix_web::test]
+async fn purge_keeps_the_retention_period() {
+    let state = state();
+    seed(&state).await;
+
+    call(&state, TestRequest::delete().uri("/projects/P1/epics/E1")).await;
+
+    let (status, body) = call(&state, TestRequest::post().uri("/trash/purge")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["content"].as_array().unwrap().is_empty());
+    assert_eq!(trash(&state, "").await.len(), 1);
+
+    // Without retention every item is purged
+    let purging = state_with(&[("TRASH_RETENTION_DAYS", "0")]);
+    seed(&purging).await;
+
+    call(&purging, TestRequest::delete().uri("/projects/P2/epics/E1")).await;
+
+    let (_, body) = call(&purging, TestRequest::post().uri("/trash/purge")).await;
+    assert_eq!(body["content"][0]["entityId"], "P2/E1");
+    assert!(trash(&purging, "").await.is_empty());
+}
+
+#[actix_web::test]
+async fn relations_come_back_with_the_entity() {
+    let state = state();
+    member(&state).await;
+
+    create(&state, "/pb", new_item("Login")).await;
+    create(&state, "/sprint", new_sprint("Sprint 1")).await;
+
+    call(&state, TestRequest::post().uri("/projects/P1/sprints/S1/items").set_json(json!({ "itemId": "PB1" }))).await;
+    call(&state, TestRequest::put().uri("/projects/P1/backlog/PB1/assignee").set_json(json!({ "memberId": "M1" }))).await;
+
+    let (status, _) = call(&state, TestRequest::delete().uri("/projects/P1/backlog/PB1")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1")).await;
+    assert_eq!(body["content"][0]["estimatedPoints"], 0);
+
+    let items = trash(&state, "?entity=backlog_item").await;
+    assert_eq!(items[0]["relations"]["sprint_item"][0][2], "PB1");
+    assert_eq!(items[0]["relations"]["assignment"][0][3], "M1");
+
+    let (status, _) = call(&state, TestRequest::post().uri(&format!("/trash/{}/restore", items[0]["id"].as_str().unwrap()))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1/items")).await;
+    assert_eq!(body["content"][0]["id"], "PB1");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/backlog/PB1")).await;
+    assert_eq!(body["content"][0]["assignee"], "M1");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1")).await;
+    assert_eq!(body["content"][0]["estimatedPoints"], 3);
+}
+
+#[actix_web::test]
+async fn purge_and_restore_follow_the_roles() {
+    let state = state_with(&[("AUTH_API_KEYS", "key-of-jane:M1,key-of-john:M2,key-of-ann:M3"), ("TRASH_RETENTION_DAYS", "0")]);
+
+    for (name, email) in [("Jane Doe", "jane@example.com"), ("John Roe", "john@example.com"), ("Ann Poe", "ann@example.com")] {
+        call_as(&state, "key-of-jane", TestRequest::post().uri("/member").set_json(new_member(name, email, "Developer"))).await;
+    }
+
+    call_as(&state, "key-of-jane", TestRequest::post().uri("/project").set_json(new_project("Alpha"))).await;
+    call_as(&state, "key-of-john", TestRequest::post().uri("/project").set_json(with(new_project("Beta"), json!({ "owner": "M2" })))).await;
+
+    for (key, project_id) in [("key-of-jane", "P1"), ("key-of-john", "P2")] {
+        let (status, _) = call_as(&state, key, TestRequest::delete().uri(&format!("/projects/{}", project_id))).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    // John only purges the project where it is `ProductOwner`
+    let (status, body) = call_as(&state, "key-of-john", TestRequest::post().uri("/trash/purge")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"].as_array().unwrap().len(), 1);
+    assert_eq!(body["content"][0]["entityId"], "P2");
+
+    let (_, body) = call_as(&state, "key-of-jane", TestRequest::get().uri("/trash")).await;
+    assert_eq!(body["content"][0]["entityId"], "P1");
+
+    // The members are removed and restored by a `ProductOwner` of any project
+    let (status, _) = call_as(&state, "key-of-ann", TestRequest::delete().uri("/members/M3")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = call_as(&state, "key-of-jane", TestRequest::delete().uri("/members/M3")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call_as(&state, "key-of-jane", TestRequest::get().uri("/trash?entity=member")).await;
+    let restore = format!("/trash/{}/restore", body["content"][0]["id"].as_str().unwrap());
+
+    let (status, _) = call_as(&state, "key-of-ann", TestRequest::post().uri(&restore)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = call_as(&state, "key-of-jane", TestRequest::post().uri(&restore)).await;
+    assert_eq!(status, StatusCode::OK);
+}
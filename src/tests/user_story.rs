@@ -0,0 +1,155 @@
+use actix_web::{http::StatusCode, test::TestRequest};
+use serde_json::json;
+
+use super::{call, new_story, state, with};
+
+///
+/// A story with two scenarios and a rule.
+fn new_user_story(title: &str) -> serde_json::Value {
+    with(new_story(title), json!({
+        "user_story": { "functionality": "run the API offline", "benefit": "I can test without Google" },
+        "acceptance_criteria": [
+            [
+                { "kind": "Success", "given": "a store", "when": "I create", "then": "it is saved" },
+                { "kind": "Failure", "given": "no store", "when": "I create", "then": "it fails" }
+            ],
+            ["Works offline"]
+        ]
+    }))
+}
+
+#[actix_web::test]
+async fn create_and_read_user_stories() {
+    let state = state();
+
+    let (status, _) = call(&state, TestRequest::post().uri("/us").set_json(new_user_story("Offline mode"))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    call(&state, TestRequest::post().uri("/us").set_json(new_user_story("Export"))).await;
+
+    let (status, body) = call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let stories = body["content"].as_array().unwrap();
+    assert_eq!(stories.len(), 2);
+    assert_eq!(stories[0]["id"], "US1");
+    assert_eq!(stories[1]["id"], "US2");
+    assert_eq!(stories[0]["storyPoints"], 5);
+    assert_eq!(stories[0]["userStory"]["functionality"], "run the API offline");
+
+    let scenarios = stories[0]["acceptanceCriteria"][0].as_array().unwrap();
+    assert_eq!(scenarios.len(), 2);
+    assert_eq!(scenarios[0]["kind"], "Success");
+    assert_eq!(scenarios[0]["given"], "a store");
+    assert_eq!(scenarios[1]["kind"], "Failure");
+    assert_eq!(scenarios[1]["then"], "it fails");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E2")).await;
+    assert!(body["content"].as_array().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn user_stories_by_status() {
+    let state = state();
+
+    call(&state, TestRequest::post().uri("/us").set_json(new_user_story("Offline mode"))).await;
+
+    let story = with(new_user_story("Export"), json!({ "status": "Blocked" }));
+
+    let (_, body) = call(&state, TestRequest::post().uri("/us").set_json(story)).await;
+    assert_eq!(body["content"][0]["row"], "R2:AE2");
+
+    let (_, body) = call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E1&status=ToDo")).await;
+    let stories = body["content"].as_array().unwrap();
+    assert_eq!(stories.len(), 1);
+    assert_eq!(stories[0]["id"], "US1");
+
+    let (status, _) = call(&state, TestRequest::patch().uri("/projects/P1/epics/E1/stories/US2").set_json(json!({ "status": "InReview" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = call(&state, TestRequest::patch().uri("/projects/P1/epics/E1/stories/US2").set_json(json!({ "status": "InProgress" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["status"], "InProgress");
+}
+
+#[actix_web::test]
+async fn update_user_story_cell_and_scenario() {
+    let state = state();
+
+    call(&state, TestRequest::post().uri("/us").set_json(new_user_story("Offline mode"))).await;
+
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/us").set_json(json!({ "content": "Offline first", "cell": "U1", "index": null }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Scenarios are stored in `AA` as `index:kind^given^when^then|...`
+    let (status, _) = call(
+        &state,
+        TestRequest::patch().uri("/us").set_json(json!({
+            "content": "Failure^a broken store^I create^it fails",
+            "cell": "AA1",
+            "index": 0
+        }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+    let story = &body["content"][0];
+    assert_eq!(story["title"], "Offline first");
+    assert_eq!(story["acceptanceCriteria"][0][0]["kind"], "Failure");
+    assert_eq!(story["acceptanceCriteria"][0][0]["given"], "a broken store");
+    assert_eq!(story["acceptanceCriteria"][0].as_array().unwrap().len(), 2);
+}
+
+#[actix_web::test]
+async fn delete_user_story_scenario_and_row() {
+    let state = state();
+
+    call(&state, TestRequest::post().uri("/us").set_json(new_user_story("Offline mode"))).await;
+
+    let (status, _) = call(
+        &state,
+        TestRequest::delete().uri("/us").set_json(json!({ "range": ["AA1", "AA1"], "index": 1 }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+    let scenarios = body["content"][0]["acceptanceCriteria"][0].as_array().unwrap();
+    assert_eq!(scenarios.len(), 1);
+    assert_eq!(scenarios[0]["kind"], "Success");
+
+    let (status, _) = call(
+        &state,
+        TestRequest::delete().uri("/us").set_json(json!({ "range": ["R1", "AE1"], "index": null }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+    assert!(body["content"].as_array().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn the_row_of_a_read_addresses_the_story() {
+    let state = state();
+
+    for title in ["Offline mode", "Export"] {
+        call(&state, TestRequest::post().uri("/us").set_json(new_user_story(title))).await;
+    }
+
+    let (_, body) = call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+    let export = body["content"].as_array().unwrap().iter().find(|story| story["id"] == "US2").unwrap().clone();
+    let (start, end) = export["row"].as_str().unwrap().split_once(':').unwrap();
+
+    let (status, _) = call(
+        &state,
+        TestRequest::delete().uri("/us").set_json(json!({ "range": [start, end], "index": null }))
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/uss?projectId=P1&epicId=E1")).await;
+    let stories = body["content"].as_array().unwrap();
+    assert_eq!(stories.len(), 1);
+    assert_eq!(stories[0]["id"], "US1");
+}
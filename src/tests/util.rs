@@ -0,0 +1,44 @@
+use serde_json::{json, Value};
+
+use crate::util::util::{convert_pattern_to_string, convert_pattern_to_vec, merge_patch};
+
+#[test]
+fn pattern_encoding_round_trip() {
+    let scenarios = vec![
+        vec!["0:Success".to_string(), "given".to_string(), "when".to_string(), "then".to_string()],
+        vec!["1:Failure".to_string(), "g".to_string(), "w".to_string(), "t".to_string()],
+    ];
+
+    let encoded = convert_pattern_to_string(scenarios.clone());
+    assert_eq!(encoded, "0:Success^given^when^then|1:Failure^g^w^t");
+
+    let decoded = convert_pattern_to_vec(vec![vec![Value::String(encoded)]]);
+    assert_eq!(decoded, scenarios);
+}
+
+#[test]
+fn pattern_without_carets() {
+    let decoded = convert_pattern_to_vec(vec![vec![Value::String("0:Works offline|1:Is fast".to_string())]]);
+
+    assert_eq!(decoded, vec![vec!["0:Works offline".to_string()], vec!["1:Is fast".to_string()]]);
+    assert_eq!(convert_pattern_to_string(decoded), "0:Works offline|1:Is fast");
+}
+
+#[test]
+fn merge_patch_follows_rfc_7396() {
+    let mut target = json!({ "a": "b", "c": { "d": "e", "f": "g" } });
+    merge_patch(&mut target, &json!({ "a": "z", "c": { "f": null } }));
+    assert_eq!(target, json!({ "a": "z", "c": { "d": "e" } }));
+
+    let mut target = json!({ "a": ["b"] });
+    merge_patch(&mut target, &json!({ "a": "c" }));
+    assert_eq!(target, json!({ "a": "c" }));
+
+    let mut target = json!({ "a": "foo" });
+    merge_patch(&mut target, &json!({ "b": { "c": null } }));
+    assert_eq!(target, json!({ "a": "foo", "b": {} }));
+
+    let mut target = json!({ "e": null });
+    merge_patch(&mut target, &json!({ "a": 1 }));
+    assert_eq!(target, json!({ "e": null, "a": 1 }));
+}
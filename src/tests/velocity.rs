@@ -0,0 +1,119 @@
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::{json, Value};
+
+use crate::config::app_state::AppState;
+
+use super::{call, create, member, new_item, new_project, new_sprint, state, with};
+
+///
+/// The project `P1` with the items `PB1`..`PB5` (3, 5, 2, 8 and 13 points) and `sprints` sprints.
+async fn seed(state: &web::Data<AppState>, sprints: usize) {
+    member(state).await;
+    create(state, "/project", new_project("Alpha")).await;
+
+    for points in [3, 5, 2, 8, 13] {
+        create(state, "/pb", with(new_item("Login"), json!({ "points": points }))).await;
+    }
+
+    for index in 1..=sprints {
+        create(state, "/sprint", new_sprint(&format!("Sprint {}", index))).await;
+    }
+}
+
+async fn get_velocity(state: &web::Data<AppState>, uri: &str) -> (StatusCode, Value) {
+    let (status, body) = call(state, TestRequest::get().uri(uri)).await;
+
+    (status, body["content"][0].clone())
+}
+
+async fn complete(state: &web::Data<AppState>, sprint_id: &str, item_ids: &[&str], done_items: &[&str], completed_at: i64) {
+    for item_id in item_ids {
+        call(state, TestRequest::post().uri(&format!("/projects/P1/sprints/{}/items", sprint_id)).set_json(json!({ "itemId": item_id }))).await;
+    }
+
+    for item_id in done_items {
+        call(
+            state,
+            TestRequest::patch().uri(&format!("/projects/P1/sprints/{}/items/{}", sprint_id, item_id)).set_json(json!({ "completedAt": completed_at }))
+        ).await;
+    }
+
+    call(
+        state,
+        TestRequest::patch().uri(&format!("/projects/P1/sprints/{}", sprint_id)).set_json(json!({ "status": "Done", "completedAt": completed_at }))
+    ).await;
+}
+
+#[actix_web::test]
+async fn velocity_by_sprint() {
+    let state = state();
+    seed(&state, 4).await;
+
+    complete(&state, "S1", &["PB1", "PB2"], &["PB1", "PB2"], 1700100000).await;
+    complete(&state, "S2", &["PB3", "PB4"], &["PB3"], 1700200000).await;
+
+    // A sprint done before its items were linked keeps its points
+    call(&state, TestRequest::patch().uri("/projects/P1/sprints/S3").set_json(json!({ "estimatedPoints": 6 }))).await;
+    complete(&state, "S3", &[], &[], 1700300000).await;
+
+    let (status, velocity) = get_velocity(&state, "/projects/P1/velocity?window=2").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let sprints = velocity["sprints"].as_array().unwrap();
+    assert_eq!(sprints.len(), 3);
+    assert_eq!(
+        sprints.iter().map(|sprint| (sprint["sprintId"].clone(), sprint["committed"].clone(), sprint["completed"].clone(), sprint["rollingAverage"].clone())).collect::<Vec<_>>(),
+        vec![
+            (json!("S1"), json!(8), json!(8), json!(8.0)),
+            (json!("S2"), json!(10), json!(2), json!(5.0)),
+            (json!("S3"), json!(6), json!(6), json!(4.0))
+        ]
+    );
+    assert_eq!(velocity["average"], 4.0);
+    assert_eq!(velocity["remainingPoints"], 21);
+
+    let forecast = &velocity["forecast"];
+    assert_eq!(forecast["sprints"], 6);
+
+    // Between 21 / 8 and 21 / 2 sprints
+    let percentiles = ["p50", "p85", "p95"].map(|name| forecast[name].as_i64().unwrap());
+    assert!(percentiles.windows(2).all(|pair| pair[0] <= pair[1]), "{}", forecast);
+    assert!(percentiles[0] >= 3 && percentiles[2] <= 11, "{}", forecast);
+
+    let (_, velocity) = get_velocity(&state, "/projects/P1/velocity").await;
+    assert_eq!(velocity["window"], 3);
+    assert_eq!(velocity["average"], 5.33);
+}
+
+#[actix_web::test]
+async fn forecast_from_the_throughput() {
+    let state = state();
+    seed(&state, 1).await;
+
+    let (status, velocity) = get_velocity(&state, "/projects/P1/velocity").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(velocity["sprints"], json!([]));
+    assert_eq!(velocity["remainingPoints"], 31);
+    assert_eq!(velocity["forecast"], json!({ "sprints": null, "p50": null, "p85": null, "p95": null }));
+
+    call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "estimatedPoints": 5 }))).await;
+    complete(&state, "S1", &[], &[], 1700100000).await;
+
+    let (_, velocity) = get_velocity(&state, "/projects/P1/velocity").await;
+    assert_eq!(velocity["forecast"], json!({ "sprints": 7, "p50": 7, "p85": 7, "p95": 7 }));
+}
+
+#[actix_web::test]
+async fn velocity_errors() {
+    let state = state();
+    seed(&state, 1).await;
+
+    let (status, _) = get_velocity(&state, "/projects/P1/velocity?window=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = get_velocity(&state, "/projects/P1/velocity?window=many").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = get_velocity(&state, "/projects/P9/velocity").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use actix_web::{http::StatusCode, test::TestRequest, web};
+use serde_json::json;
+
+use crate::{
+    config::{app_state::AppState, settings::Settings},
+    storage::memory::MemoryStore,
+    util::util::unix_now,
+};
+
+use super::{call, create, member, new_project, new_sprint, state, with};
+
+async fn seed(state: &web::Data<AppState>) {
+    member(state).await;
+    create(state, "/project", new_project("Alpha")).await;
+    create(state, "/sprint", with(new_sprint("Sprint 1"), json!({ "status": "Pending" }))).await;
+}
+
+#[actix_web::test]
+async fn done_can_not_be_left() {
+    let state = state();
+    seed(&state).await;
+
+    let (status, _) = call(&state, TestRequest::patch().uri("/projects/P1").set_json(json!({ "status": "Done" }))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = call(&state, TestRequest::patch().uri("/projects/P1").set_json(json!({ "status": "Pending" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "INVALID_TRANSITION");
+    assert_eq!(body["errorBug"][0], "The status can not change from Done to Pending, allowed: none");
+    assert_eq!(body["fields"][0]["field"], "status");
+
+    // Writing the cell does not skip the workflow
+    let (status, _) = call(&state, TestRequest::patch().uri("/project").set_json(json!({ "content": "Pending", "cell": "E1" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1")).await;
+    assert_eq!(body["content"][0]["status"], "Done");
+
+    let (status, body) = call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "status": "Standby" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["status"], "Standby");
+
+    let (status, body) = call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "status": "Done" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["errorBug"][0], "The status can not change from Standby to Done, allowed: Pending, InProgress");
+}
+
+#[actix_web::test]
+async fn sprint_is_stamped_when_done() {
+    let mut settings = Settings::default();
+    settings.workflow.sprint.insert("Done".to_string(), vec!["InProgress".to_string()]);
+
+    let state = web::Data::new(AppState::new(Arc::new(MemoryStore::new()), settings));
+    seed(&state).await;
+
+    let before = unix_now();
+
+    let (_, body) = call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "status": "Done" }))).await;
+    let completed_at = body["content"][0]["completedAt"].as_i64().unwrap();
+    assert!(completed_at >= before && completed_at <= unix_now(), "{}", body);
+
+    // Reopened
+    let (status, body) = call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "status": "InProgress" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["content"][0]["completedAt"], 0);
+
+    // The date sent is kept
+    let (_, body) = call(
+        &state,
+        TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "status": "Done", "completedAt": 1701209600 }))
+    ).await;
+    assert_eq!(body["content"][0]["completedAt"], 1701209600);
+
+    call(&state, TestRequest::patch().uri("/projects/P1/sprints/S1").set_json(json!({ "status": "InProgress" }))).await;
+
+    // The cell of the status is written with the date
+    let (status, body) = call(&state, TestRequest::patch().uri("/sprint").set_json(json!({ "content": "Done", "cell": "AZ1" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["content"][0]["completedAt"].as_i64().unwrap() >= before);
+
+    let (_, body) = call(&state, TestRequest::get().uri("/projects/P1/sprints/S1")).await;
+    assert!(body["content"][0]["completedAt"].as_i64().unwrap() >= before);
+}
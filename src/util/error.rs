@@ -0,0 +1,156 @@
+use std::fmt;
+
+use actix_web::{error::JsonPayloadError, http::StatusCode, HttpResponse, ResponseError};
+
+use crate::model::response::{FieldError, Response};
+
+///
+/// Errors that can happen while a request is handled:
+///
+/// - `Auth`: The credentials could not be loaded or the token was rejected (401).
+/// - `Forbidden`: The caller was identified but its role in the project does not allow the request (403).
+/// - `Upstream`: The storage backend (e.g: Google Sheets) failed (502).
+/// - `CorruptRow`: A stored row could not be parsed into its model (500).
+/// - `NotFound`: The requested entity does not exist (404).
+/// - `Conflict`: The request is valid but it clashes with the stored data (409).
+/// - `InvalidTransition`: The status can not change from `from` to `to`, only to the `allowed` ones (409).
+/// - `Validation`: The request is malformed, e.g: an invalid cell or a missing parameter (400).
+/// - `InvalidFields`: Some fields of the request are not valid (400).
+#[derive(Debug)]
+pub enum AppError {
+    Auth(String),
+    Forbidden(String),
+    Upstream(String),
+    CorruptRow(String),
+    NotFound(String),
+    Conflict(String),
+    InvalidTransition { from: String, to: String, allowed: Vec<String> },
+    Validation(String),
+    InvalidFields(Vec<FieldError>)
+}
+
+impl AppError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            AppError::Auth(_) => "Authentication failed",
+            AppError::Forbidden(_) => "The request is not allowed to the caller",
+            AppError::Upstream(_) => "The storage could not complete the operation",
+            AppError::CorruptRow(_) => "The stored data could not be read",
+            AppError::NotFound(_) => "The requested data was not found",
+            AppError::Conflict(_) => "The request conflicts with the stored data",
+            AppError::InvalidTransition { .. } => "The status transition is not allowed",
+            AppError::Validation(_) | AppError::InvalidFields(_) => "The request is not valid"
+        }
+    }
+
+    ///
+    /// Machine readable kind of the error, sent in the `code` of the response.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Auth(_) => "AUTH",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::Upstream(_) => "UPSTREAM",
+            AppError::CorruptRow(_) => "CORRUPT_ROW",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::InvalidTransition { .. } => "INVALID_TRANSITION",
+            AppError::Validation(_) | AppError::InvalidFields(_) => "VALIDATION"
+        }
+    }
+
+    ///
+    /// It treats a row that could not be parsed as an invalid request,
+    /// e.g: when the row was built from the values sent by the client.
+    pub fn into_validation(self) -> AppError {
+        match self {
+            AppError::CorruptRow(detail) => AppError::Validation(detail),
+            err => err
+        }
+    }
+}
+
+///
+/// Errors of the JSON bodies, the field is reported when serde names it (e.g: a missing field).
+impl From<JsonPayloadError> for AppError {
+    fn from(err: JsonPayloadError) -> Self {
+        let detail = err.to_string();
+
+        let field = detail
+            .split_once("field `")
+            .and_then(|(_, rest)| rest.split_once('`'))
+            .map(|(field, _)| field.to_string());
+
+        match field {
+            Some(field) => AppError::InvalidFields(vec![FieldError { field, message: detail }]),
+            None => AppError::Validation(detail)
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Auth(detail)
+            | AppError::Forbidden(detail)
+            | AppError::Upstream(detail)
+            | AppError::CorruptRow(detail)
+            | AppError::NotFound(detail)
+            | AppError::Conflict(detail)
+            | AppError::Validation(detail) => write!(f, "{}", detail),
+            AppError::InvalidTransition { from, to, allowed } => write!(
+                f,
+                "The status can not change from {} to {}, allowed: {}",
+                from,
+                to,
+                match allowed.is_empty() {
+                    true => "none".to_string(),
+                    false => allowed.join(", ")
+                }
+            ),
+            AppError::InvalidFields(fields) => write!(
+                f,
+                "{}",
+                fields
+                    .iter()
+                    .map(|field| format!("`{}`: {}", field.field, field.message))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::Auth(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            AppError::CorruptRow(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) | AppError::InvalidTransition { .. } => StatusCode::CONFLICT,
+            AppError::Validation(_) | AppError::InvalidFields(_) => StatusCode::BAD_REQUEST
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let fields = match self {
+            AppError::InvalidFields(fields) => Some(fields.clone()),
+            AppError::InvalidTransition { from, allowed, .. } => Some(vec![FieldError {
+                field: "status".to_string(),
+                message: format!("Allowed from {}: [{}]", from, allowed.join(", "))
+            }]),
+            _ => None
+        };
+
+        HttpResponse::build(self.status_code()).json(Response::<String> {
+            message: self.message(),
+            content: None,
+            error_bug: Some(vec![self.to_string()]),
+            code: Some(self.code()),
+            fields
+        })
+    }
+}
@@ -1,2 +1,4 @@
-pub mod cons;
+pub mod error;
+pub mod range;
+pub mod row;
 pub mod util;
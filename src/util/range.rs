@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+///
+/// A range written in A1 notation, e.g: `Projects!A2:G2` or `Projects!R:AD`.
+///
+/// A1Range attributes:
+///
+/// - `sheet`: Name of the sheet.
+/// - `start_col`/`end_col`: Zero based index of the columns (A = 0).
+/// - `start_row`/`end_row`: One based index of the rows, `None` when the range is open (e.g: `A:G`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct A1Range {
+    pub sheet: String,
+    pub start_col: usize,
+    pub start_row: Option<usize>,
+    pub end_col: usize,
+    pub end_row: Option<usize>
+}
+
+impl A1Range {
+    pub fn contains_column(&self, col: usize) -> bool {
+        col >= self.start_col && col <= self.end_col
+    }
+
+    pub fn contains_row(&self, row: usize) -> bool {
+        row >= self.start_row.unwrap_or(1) && self.end_row.is_none_or(|end| row <= end)
+    }
+}
+
+///
+/// It converts a column into its zero based index, e.g: `A` -> 0, `AA` -> 26.
+pub fn column_to_index(column: &str) -> Option<usize> {
+    if column.is_empty() || !column.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None
+    }
+
+    Some(
+        column
+            .to_ascii_uppercase()
+            .chars()
+            .fold(0, |acc, c| acc * 26 + (c as usize - 'A' as usize + 1)) - 1
+    )
+}
+
+/// Splits a cell like `AB12` into its column and row.
+pub fn parse_cell(cell: &str) -> Result<(usize, Option<usize>), String> {
+    let letters = cell.trim().chars().take_while(|c| c.is_ascii_alphabetic()).collect::<String>();
+    let digits = &cell.trim()[letters.len()..];
+
+    let col = column_to_index(&letters).ok_or(format!("Invalid column in cell '{}'", cell))?;
+
+    let row = match digits {
+        "" => None,
+        _ => Some(digits.parse::<usize>().map_err(|_| format!("Invalid row in cell '{}'", cell))?)
+    };
+
+    Ok((col, row))
+}
+
+pub fn parse_range(range: &str) -> Result<A1Range, String> {
+    let (sheet, cells) = range
+        .split_once("!")
+        .ok_or(format!("The range '{}' does not specify a sheet", range))?;
+
+    let (start, end) = cells.split_once(":").unwrap_or((cells, cells));
+
+    let (start_col, start_row) = parse_cell(start)?;
+    let (end_col, end_row) = parse_cell(end)?;
+
+    Ok(A1Range {
+        sheet: sheet.trim_matches('\'').to_string(),
+        start_col,
+        start_row,
+        end_col,
+        end_row
+    })
+}
+
+///
+/// It builds the rows of a range the same way the Sheets API does:
+/// every row starts at the first column of the range, trailing empty cells are omitted
+/// and empty rows after the last non-empty row are not returned.
+///
+/// `cells`: (row, col, value) of the cells already stored, those outside the range are ignored.
+pub fn assemble_rows(range: &A1Range, cells: Vec<(usize, usize, String)>) -> Vec<Vec<Value>> {
+    let mut grid: BTreeMap<usize, BTreeMap<usize, String>> = BTreeMap::new();
+
+    cells
+        .into_iter()
+        .filter(|(row, col, value)| range.contains_row(*row) && range.contains_column(*col) && !value.is_empty())
+        .for_each(|(row, col, value)| {
+            grid.entry(row).or_default().insert(col, value);
+        });
+
+    let Some(last_row) = grid.keys().next_back().cloned() else { return vec![] };
+
+    (range.start_row.unwrap_or(1)..=last_row)
+        .map(|row| {
+            let Some(columns) = grid.get(&row) else { return vec![] };
+            let last_col = columns.keys().next_back().cloned().unwrap_or(range.start_col);
+
+            (range.start_col..=last_col)
+                .map(|col| Value::String(columns.get(&col).cloned().unwrap_or_default()))
+                .collect()
+        })
+        .collect()
+}
+
+///
+/// It returns the row where an append must be written:
+/// the one after the last non-empty row below the start of the range (like the table detection of the Sheets API).
+pub fn next_free_row(range: &A1Range, cells: &[(usize, usize, String)]) -> usize {
+    let start_row = range.start_row.unwrap_or(1);
+
+    cells
+        .iter()
+        .filter(|(row, col, value)| *row >= start_row && range.contains_column(*col) && !value.is_empty())
+        .map(|(row, _, _)| row + 1)
+        .max()
+        .unwrap_or(start_row)
+}
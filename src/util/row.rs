@@ -0,0 +1,50 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use super::error::AppError;
+
+///
+/// Text of a cell, empty when the row is shorter than `index`
+/// (the Sheets API omits the trailing empty cells).
+pub fn text(row: &[Value], index: usize) -> String {
+    row.get(index)
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+///
+/// It parses a cell into `T`, e.g: a timestamp or a `Status`.
+///
+/// `field`: Name of the attribute, reported when the cell is invalid.
+pub fn parse<T: FromStr>(row: &[Value], index: usize, field: &str) -> Result<T, AppError> {
+    let value = text(row, index);
+
+    value
+        .trim()
+        .parse::<T>()
+        .map_err(|_| AppError::CorruptRow(format!("`{}` has an invalid value: '{}'", field, value)))
+}
+
+///
+/// Whether every cell of the row is empty, e.g: a row removed with `values_clear`.
+pub fn is_empty(row: &[Value]) -> bool {
+    row.iter().all(|cell| cell.as_str().unwrap_or_default().trim().is_empty())
+}
+
+///
+/// Cells of a row built from the values sent to the store.
+pub fn cells(values: &[String]) -> Vec<Value> {
+    values.iter().cloned().map(Value::String).collect()
+}
+
+///
+/// It replaces the cell in `index`, filling the missing cells of the row with empty values.
+pub fn set(row: &mut Vec<Value>, index: usize, value: String) {
+    if row.len() <= index {
+        row.resize(index + 1, Value::String(String::new()));
+    }
+
+    row[index] = Value::String(value);
+}
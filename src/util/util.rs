@@ -1,8 +1,9 @@
-use std::str::FromStr;
+use std::{str::FromStr, time::{SystemTime, UNIX_EPOCH}};
 
-use google_sheets4::api::ValueRange;
 use serde::{Deserialize, Serialize};
 
+use crate::interface::crudy::ProjectScoped;
+
 /// It determines the status of a project, epic or something like that:
 ///
 /// - `Pending`: When the project/epic has been created but it has not one advancing.
@@ -11,7 +12,7 @@ use serde::{Deserialize, Serialize};
 /// - `Standby`: When you have desisted at the moment (pause) or maybe something is missing,
 /// or even you are editing (only when the edition could be take along time).
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Status {
     Pending,
     InProgress,
@@ -19,16 +20,34 @@ pub enum Status {
     Standby
 }
 
+///
+/// It determines where a user story or a backlog item is in the workflow of the team:
+///
+/// - `ToDo`: When it is waiting to be started (rows stored without status are read as `ToDo`).
+/// - `InProgress`: When somebody is working on it.
+/// - `InReview`: When it is finished and it is being reviewed or tested.
+/// - `Done`: When it has been completed.
+/// - `Blocked`: When it can not advance, e.g: it depends on something else.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ItemStatus {
+    #[default]
+    ToDo,
+    InProgress,
+    InReview,
+    Done,
+    Blocked
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Identificator {
     pub id: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct DoubleIdentificator {
-    pub project_id: String,
-    pub epic_id: String
+/// The lists of epics and sprints are read by project, e.g: `GET /epics?id=P1`.
+impl ProjectScoped for Identificator {
+    fn project_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
 }
 
 impl FromStr for Status {
@@ -45,29 +64,29 @@ impl FromStr for Status {
    }
 }
 
-pub fn extract_num(input: &str) -> i32 {
-    input.trim().chars()
-        .filter(|c| c.is_ascii_digit())
-        .collect::<String>()
-        .parse::<i32>()
-        .unwrap()
-}
+impl FromStr for ItemStatus {
+   type Err = ();
 
-pub fn extract_string(input: &str) -> String {
-    input.trim().chars()
-        .filter(|c| c.is_ascii_alphabetic())
-        .collect::<String>()
+   fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "ToDo" => Ok(ItemStatus::ToDo),
+            "InProgress" => Ok(ItemStatus::InProgress),
+            "InReview" => Ok(ItemStatus::InReview),
+            "Done" => Ok(ItemStatus::Done),
+            "Blocked" => Ok(ItemStatus::Blocked),
+            _ => Err(())
+        }
+   }
 }
 
-pub fn convert_pattern_to_vec(input: ValueRange) -> Vec<Vec<String>> {
+pub fn convert_pattern_to_vec(input: Vec<Vec<serde_json::Value>>) -> Vec<Vec<String>> {
     input
-        .values
-        .unwrap_or_default()
         .into_iter()
         .map(|element| {
-            element[0]
-                .as_str()
-                .unwrap()
+            element
+                .first()
+                .and_then(|cell| cell.as_str())
+                .unwrap_or_default()
                 .split("|")
                 .map(|condition| {
                     condition
@@ -88,3 +107,36 @@ pub fn convert_pattern_to_string(input: Vec<Vec<String>>) -> String {
         .collect::<Vec<String>>()
         .join("|")
 }
+
+///
+/// Current date in unix epoch (seconds).
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+///
+/// It applies a JSON Merge Patch (RFC 7396): objects are merged recursively,
+/// `null` removes the member and any other value replaces it.
+pub fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return
+    };
+
+    if !target.is_object() {
+        *target = serde_json::Value::Object(serde_json::Map::new());
+    }
+
+    if let serde_json::Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+            }
+        }
+    }
+}